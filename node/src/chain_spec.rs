@@ -1,6 +1,6 @@
 use node_subtensor_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, CouncilConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
+	SystemConfig, TechnicalCommitteeConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -120,7 +120,16 @@ fn testnet_genesis(
 		},
 		sudo: SudoConfig {
 			// Assign network admin rights.
-			key: Some(root_key),
+			key: Some(root_key.clone()),
+		},
+		council: CouncilConfig {
+			// The sudo key seeds both collectives, members are changed with a root set_members.
+			members: vec![root_key.clone()],
+			phantom: Default::default(),
+		},
+		technical_committee: TechnicalCommitteeConfig {
+			members: vec![root_key],
+			phantom: Default::default(),
 		},
 		transaction_payment: Default::default(),
 		subtensor_module: Default::default(),
@@ -507,7 +516,16 @@ fn network_genesis(
 		},
 		sudo: SudoConfig {
 			// Assign network admin rights.
-			key: Some(root_key),
+			key: Some(root_key.clone()),
+		},
+		council: CouncilConfig {
+			// The sudo key seeds both collectives, members are changed with a root set_members.
+			members: vec![root_key.clone()],
+			phantom: Default::default(),
+		},
+		technical_committee: TechnicalCommitteeConfig {
+			members: vec![root_key],
+			phantom: Default::default(),
 		},
 		transaction_payment: Default::default(),
 		subtensor_module: Default::default()
@@ -0,0 +1,98 @@
+//! RPC interface for the subtensor pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use pallet_subtensor::{NeuronMetadata, NeuronInfo, SubnetHyperparams};
+pub use pallet_subtensor_runtime_api::SubtensorApi as SubtensorRuntimeApi;
+
+#[rpc]
+pub trait SubtensorApi<BlockHash, AccountId> {
+	/// ---- Returns the full set of subnet hyperparameters at the given block, or at the best block.
+	#[rpc(name = "subtensor_getHyperparameters")]
+	fn get_hyperparameters(&self, at: Option<BlockHash>) -> Result<SubnetHyperparams>;
+
+	/// ---- Returns the neurons owned by the coldkey, in registration order.
+	#[rpc(name = "subtensor_getNeuronsByColdkey")]
+	fn get_neurons_by_coldkey(&self, coldkey: AccountId, at: Option<BlockHash>) -> Result<Vec<NeuronMetadata<AccountId>>>;
+
+	/// ---- Returns the stake summed over every neuron owned by the coldkey.
+	#[rpc(name = "subtensor_getTotalStakeForColdkey")]
+	fn get_total_stake_for_coldkey(&self, coldkey: AccountId, at: Option<BlockHash>) -> Result<u64>;
+
+	/// ---- Returns the metagraph: every registered neuron with its validator permit and vtrust, ordered by uid.
+	#[rpc(name = "subtensor_getNeurons")]
+	fn get_neurons(&self, at: Option<BlockHash>) -> Result<Vec<NeuronInfo<AccountId>>>;
+
+	/// ---- Returns the neuron at the uid with its validator permit and vtrust, if the uid is registered.
+	#[rpc(name = "subtensor_getNeuron")]
+	fn get_neuron(&self, uid: u32, at: Option<BlockHash>) -> Result<Option<NeuronInfo<AccountId>>>;
+}
+
+/// Implements the SubtensorApi RPC trait by calling into the runtime.
+pub struct Subtensor<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Subtensor<C, B> {
+	/// Creates a new instance of the subtensor RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error code returned when the runtime call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId> SubtensorApi<<Block as BlockT>::Hash, AccountId> for Subtensor<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SubtensorRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn get_hyperparameters(&self, at: Option<<Block as BlockT>::Hash>) -> Result<SubnetHyperparams> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.get_hyperparameters(&at).map_err(|e| runtime_error("Unable to query hyperparameters.", e))
+	}
+
+	fn get_neurons_by_coldkey(&self, coldkey: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<NeuronMetadata<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.get_neurons_by_coldkey(&at, coldkey).map_err(|e| runtime_error("Unable to query neurons by coldkey.", e))
+	}
+
+	fn get_total_stake_for_coldkey(&self, coldkey: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<u64> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.get_total_stake_for_coldkey(&at, coldkey).map_err(|e| runtime_error("Unable to query total stake for coldkey.", e))
+	}
+
+	fn get_neurons(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<NeuronInfo<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.get_neurons(&at).map_err(|e| runtime_error("Unable to query neurons.", e))
+	}
+
+	fn get_neuron(&self, uid: u32, at: Option<<Block as BlockT>::Hash>) -> Result<Option<NeuronInfo<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.get_neuron(&at, uid).map_err(|e| runtime_error("Unable to query neuron.", e))
+	}
+}
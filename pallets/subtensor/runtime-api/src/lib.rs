@@ -0,0 +1,42 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Runtime API for querying the subtensor pallet.
+use codec::Codec;
+use sp_std::vec::Vec;
+use pallet_subtensor::{EpochRecord, NeuronEpochRecord, SubnetHyperparams, ScheduledHyperparameterChange, Hyperparameter, HyperparameterRange, NeuronMetadata, NeuronInfo};
+
+sp_api::decl_runtime_apis! {
+	pub trait SubtensorApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// ---- Returns the network totals and per-uid results of the last EpochHistoryLength epochs, oldest first.
+		fn get_epoch_history() -> Vec<EpochRecord>;
+
+		/// ---- Returns the results of a single uid over the last EpochHistoryLength epochs, oldest first.
+		fn get_epoch_history_for_uid( uid: u32 ) -> Vec<NeuronEpochRecord>;
+
+		/// ---- Returns the validator trust of every uid at the last epoch, normalized to u64::MAX.
+		fn get_vtrust() -> Vec<u64>;
+
+		/// ---- Returns the full current set of subnet hyperparameters.
+		fn get_hyperparameters() -> SubnetHyperparams;
+
+		/// ---- Returns the hyperparameter changes waiting in the schedule, ordered by the block they apply at.
+		fn get_scheduled_hyperparameter_changes() -> Vec<ScheduledHyperparameterChange>;
+
+		/// ---- Returns the inclusive bounds enforced on every hyperparameter.
+		fn get_hyperparameter_ranges() -> Vec<(Hyperparameter, HyperparameterRange)>;
+
+		/// ---- Returns the neurons owned by the coldkey, in registration order.
+		fn get_neurons_by_coldkey( coldkey: AccountId ) -> Vec<NeuronMetadata<AccountId>>;
+
+		/// ---- Returns the stake summed over every neuron owned by the coldkey.
+		fn get_total_stake_for_coldkey( coldkey: AccountId ) -> u64;
+
+		/// ---- Returns every registered neuron with its validator permit and vtrust, ordered by uid.
+		fn get_neurons() -> Vec<NeuronInfo<AccountId>>;
+
+		/// ---- Returns the neuron at the uid with its validator permit and vtrust, if the uid is registered.
+		fn get_neuron( uid: u32 ) -> Option<NeuronInfo<AccountId>>;
+	}
+}
@@ -0,0 +1,99 @@
+use super::*;
+use frame_support::weights::Weight;
+
+impl<T: Config> Pallet<T> {
+    /***********************************************************
+     * do_announce_coldkey_swap() - main function called from parent module
+     ***********************************************************/
+
+    /// Schedules the swap of every neuron owned by the calling coldkey to the new coldkey,
+    /// ColdkeySwapDelay blocks from now.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - ColdkeySwapToSameColdkey : The new coldkey is the calling coldkey
+    /// - ColdkeyOwnsNoNeurons : The calling coldkey owns no neuron
+    /// - ColdkeySwapAlreadyAnnounced : The calling coldkey already has a pending swap
+    ///
+    pub fn do_announce_coldkey_swap(origin: T::Origin, new_coldkey: T::AccountId) -> dispatch::DispatchResult {
+
+        // ---- We check the transaction is signed by the caller
+        // and retrieve the T::AccountId pubkey information.
+        let coldkey = ensure_signed(origin)?;
+        ensure!(coldkey != new_coldkey, Error::<T>::ColdkeySwapToSameColdkey);
+        ensure!(OwnedHotkeys::<T>::contains_key(&coldkey), Error::<T>::ColdkeyOwnsNoNeurons);
+        ensure!(!ColdkeySwaps::<T>::contains_key(&coldkey), Error::<T>::ColdkeySwapAlreadyAnnounced);
+
+        // ---- We record the swap and queue it for the block at which it executes.
+        let execution_block: u64 = Self::get_current_block_as_u64().saturating_add(T::ColdkeySwapDelay::get());
+        ColdkeySwaps::<T>::insert(&coldkey, (new_coldkey.clone(), execution_block));
+        ScheduledColdkeySwaps::<T>::append(execution_block, coldkey.clone());
+
+        // ---- Emit the announcement event.
+        Self::deposit_event(Event::ColdkeySwapAnnounced(coldkey, new_coldkey, execution_block));
+
+        // --- Done and ok.
+        Ok(())
+    }
+
+    /// Cancels the pending swap of the calling coldkey and removes it from the queue of its block,
+    /// so that announcing and cancelling repeatedly cannot grow the queue.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NoColdkeySwapAnnounced : The calling coldkey has no pending swap
+    ///
+    pub fn do_cancel_coldkey_swap(origin: T::Origin) -> dispatch::DispatchResult {
+        let coldkey = ensure_signed(origin)?;
+        let (new_coldkey, execution_block) = ColdkeySwaps::<T>::take(&coldkey).ok_or(Error::<T>::NoColdkeySwapAnnounced)?;
+        ScheduledColdkeySwaps::<T>::mutate_exists(execution_block, |scheduled| {
+            if let Some(coldkeys) = scheduled {
+                coldkeys.retain(|scheduled_coldkey| scheduled_coldkey != &coldkey);
+                if coldkeys.is_empty() {
+                    *scheduled = None;
+                }
+            }
+        });
+        Self::deposit_event(Event::ColdkeySwapCancelled(coldkey, new_coldkey));
+        Ok(())
+    }
+
+    /// Executes the swaps whose delay ends at the current block. A queued coldkey whose swap
+    /// no longer executes at this block is skipped.
+    pub fn execute_scheduled_coldkey_swaps() -> Weight {
+        let block: u64 = Self::get_current_block_as_u64();
+        if !ScheduledColdkeySwaps::<T>::contains_key(block) {
+            return T::DbWeight::get().reads(1);
+        }
+        let mut weight: Weight = T::DbWeight::get().reads_writes(1, 1);
+        for coldkey in ScheduledColdkeySwaps::<T>::take(block) {
+            weight = weight.saturating_add(T::DbWeight::get().reads(1));
+            match ColdkeySwaps::<T>::get(&coldkey) {
+                Some((new_coldkey, execution_block)) if execution_block == block => {
+                    ColdkeySwaps::<T>::remove(&coldkey);
+                    let swapped: u64 = Self::swap_coldkey(&coldkey, &new_coldkey);
+                    Self::deposit_event(Event::ColdkeySwapped(coldkey, new_coldkey));
+                    weight = weight.saturating_add(T::DbWeight::get().reads_writes(2 + 2 * swapped, 2 + 2 * swapped));
+                }
+                _ => {}
+            }
+        }
+        weight
+    }
+
+    /// Re-points every neuron owned by the old coldkey to the new one, and moves the hotkeys
+    /// into the ownership index of the new coldkey. The stake stays on the neurons, so it follows
+    /// them to the new coldkey. Returns the number of neurons swapped.
+    pub fn swap_coldkey(old_coldkey: &T::AccountId, new_coldkey: &T::AccountId) -> u64 {
+        let mut swapped: u64 = 0;
+        for hotkey in OwnedHotkeys::<T>::take(old_coldkey) {
+            if !Self::is_hotkey_active(&hotkey) {
+                continue;
+            }
+            let mut neuron = Self::get_neuron_for_hotkey(&hotkey);
+            neuron.coldkey = new_coldkey.clone();
+            Neurons::<T>::insert(neuron.uid, neuron);
+            Self::add_owned_hotkey(new_coldkey, &hotkey);
+            swapped += 1;
+        }
+        swapped
+    }
+}
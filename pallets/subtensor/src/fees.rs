@@ -0,0 +1,104 @@
+use super::*;
+use frame_support::traits::Get;
+use pallet_transaction_payment::{CurrencyAdapter, OnChargeTransaction};
+use sp_runtime::traits::{PostDispatchInfoOf, SaturatedConversion};
+
+/// Charges transaction fees like CurrencyAdapter, except that the set_weights and serve_axon calls
+/// of a registered hotkey are paid by the coldkey owning it once that coldkey has opted in through
+/// set_coldkey_pays_fees. Refunds after dispatch go back to the account which paid.
+pub struct SubtensorFeeAdapter<C, OU>(PhantomData<(C, OU)>);
+
+impl<T, C, OU> OnChargeTransaction<T> for SubtensorFeeAdapter<C, OU>
+where
+    T: Config + pallet_transaction_payment::Config,
+    <T as frame_system::Config>::Call: IsSubType<Call<T>>,
+    CurrencyAdapter<C, OU>: OnChargeTransaction<T>,
+{
+    type Balance = <CurrencyAdapter<C, OU> as OnChargeTransaction<T>>::Balance;
+    type LiquidityInfo = Option<(T::AccountId, <CurrencyAdapter<C, OU> as OnChargeTransaction<T>>::LiquidityInfo)>;
+
+    fn withdraw_fee(
+        who: &T::AccountId,
+        call: &<T as frame_system::Config>::Call,
+        info: &DispatchInfoOf<<T as frame_system::Config>::Call>,
+        fee: Self::Balance,
+        tip: Self::Balance,
+    ) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+        let payer: T::AccountId = Pallet::<T>::get_fee_payer( who, call );
+        let withdrawn = <CurrencyAdapter<C, OU> as OnChargeTransaction<T>>::withdraw_fee( &payer, call, info, fee, tip )?;
+        Ok( Some( ( payer, withdrawn ) ) )
+    }
+
+    fn correct_and_deposit_fee(
+        _who: &T::AccountId,
+        info: &DispatchInfoOf<<T as frame_system::Config>::Call>,
+        post_info: &PostDispatchInfoOf<<T as frame_system::Config>::Call>,
+        corrected_fee: Self::Balance,
+        tip: Self::Balance,
+        already_withdrawn: Self::LiquidityInfo,
+    ) -> Result<(), TransactionValidityError> {
+        match already_withdrawn {
+            Some( ( payer, withdrawn ) ) => <CurrencyAdapter<C, OU> as OnChargeTransaction<T>>::correct_and_deposit_fee( &payer, info, post_info, corrected_fee, tip, withdrawn ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /***********************************************************
+     * do_set_coldkey_pays_fees() - main function called from parent module
+     ***********************************************************/
+
+    /// Records whether the calling coldkey pays the fees of the set_weights and serve_axon calls
+    /// signed by its hotkey.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotRegistered : The hotkey is not registered
+    /// - NonAssociatedColdKey : The hotkey is not owned by the calling coldkey
+    ///
+    pub fn do_set_coldkey_pays_fees(origin: T::Origin, hotkey: T::AccountId, pays: bool) -> dispatch::DispatchResult {
+
+        // ---- We check the transaction is signed by the caller
+        // and retrieve the T::AccountId pubkey information.
+        let coldkey = ensure_signed(origin)?;
+
+        // ---- We check that the hotkey is registered and owned by the caller.
+        ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotRegistered);
+        let neuron = Self::get_neuron_for_hotkey(&hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+
+        if pays {
+            FeePayingColdkeys::<T>::insert(&hotkey, &coldkey);
+        } else {
+            FeePayingColdkeys::<T>::remove(&hotkey);
+        }
+        Self::deposit_event(Event::ColdkeyPaysFeesSet(coldkey, hotkey, pays));
+        Ok(())
+    }
+
+    /// Returns the account charged for a transaction signed by who. The set_weights and serve_axon
+    /// calls of a registered hotkey are charged to its coldkey when that coldkey has opted in and
+    /// still owns the neuron, so registering or swapping a neuron onto a coldkey never makes it pay.
+    /// Every other transaction is charged to who itself.
+    pub fn get_fee_payer( who: &T::AccountId, call: &<T as frame_system::Config>::Call ) -> T::AccountId
+    where
+        <T as frame_system::Config>::Call: IsSubType<Call<T>>,
+    {
+        match call.is_sub_type() {
+            Some( Call::set_weights{..} ) | Some( Call::serve_axon{..} ) if Self::is_hotkey_active( who ) => {
+                let coldkey: T::AccountId = Self::get_neuron_for_hotkey( who ).coldkey;
+                match FeePayingColdkeys::<T>::get( who ) {
+                    Some( fee_paying_coldkey ) if fee_paying_coldkey == coldkey => coldkey,
+                    _ => who.clone(),
+                }
+            }
+            _ => who.clone(),
+        }
+    }
+
+    /// Returns the length fee charged for a transaction of len bytes as rao.
+    pub fn calculate_transaction_fee( len: u64 ) -> u64 {
+        let byte_fee: u64 = T::TransactionByteFee::get().saturated_into::<u64>();
+        return len.saturating_mul( byte_fee );
+    }
+}
@@ -0,0 +1,270 @@
+use super::*;
+use frame_support::IterableStorageMap;
+use frame_support::weights::Weight;
+use sp_std::convert::TryInto;
+
+impl<T: Config> Pallet<T> {
+    /***********************************************************
+     * do_schedule_hyperparameter_change() - main function called from parent module
+     ***********************************************************/
+
+    pub fn do_schedule_hyperparameter_change(origin: T::Origin, param: Hyperparameter, value: u64, at_block: u64) -> dispatch::DispatchResult
+    {
+        T::AdminOrigin::ensure_origin(origin)?;
+
+        // ---- Changes only apply at the start of a block, so the current block is already too late.
+        ensure!(at_block > Self::get_current_block_as_u64(), Error::<T>::InvalidScheduleBlock);
+
+        // ---- We check the change against the current set so that obviously invalid values fail now.
+        // The merged set is validated again when the change is applied.
+        let update = Self::hyperparameter_update(param, value).ok_or(Error::<T>::StorageValueOutOfRange)?;
+        Self::validate_hyperparameters(&Self::merge_hyperparameters(update))?;
+
+        ScheduledHyperparameterChanges::<T>::append(at_block, (param, value));
+        Self::deposit_event(Event::HyperparameterChangeScheduled(param, value, at_block));
+        Ok(())
+    }
+
+    /// Applies, in scheduling order, the changes scheduled for the current block.
+    /// A change which no longer validates against the set at this block is dropped.
+    pub fn apply_scheduled_hyperparameter_changes() -> Weight {
+        let block: u64 = Self::get_current_block_as_u64();
+        if !ScheduledHyperparameterChanges::<T>::contains_key(block) {
+            return T::DbWeight::get().reads(1);
+        }
+        let changes: Vec<(Hyperparameter, u64)> = ScheduledHyperparameterChanges::<T>::take(block);
+        let mut weight: Weight = T::DbWeight::get().reads_writes(1, 1);
+        for (param, value) in changes.iter() {
+            match Self::hyperparameter_update(*param, *value) {
+                Some(update) if Self::validate_hyperparameters(&Self::merge_hyperparameters(update.clone())).is_ok() => {
+                    weight = weight.saturating_add(Self::hyperparameters_update_weight(&update));
+                    Self::apply_hyperparameters_update(&update);
+                    Self::deposit_event(Event::HyperparameterChangeApplied(*param, *value));
+                }
+                _ => {
+                    weight = weight.saturating_add(T::DbWeight::get().reads(Self::get_hyperparameter_list().len() as u64));
+                    Self::deposit_event(Event::HyperparameterChangeRejected(*param, *value));
+                }
+            }
+        }
+        weight
+    }
+
+    /// Weight of merging, validating and applying an update: the full set is read, only the fields set in
+    /// the update are written, and shrinking the epoch history removes the records which fall out of it.
+    pub fn hyperparameters_update_weight(update: &SubnetHyperparamsUpdate) -> Weight {
+        let removed_records: u64 = match update.epoch_history_length {
+            Some(epoch_history_length) => Self::get_epoch_history_length().saturating_sub(epoch_history_length),
+            None => 0,
+        };
+        let reads: u64 = Self::get_hyperparameter_list().len() as u64 + 1;
+        T::DbWeight::get().reads_writes(reads, update.count().saturating_add(removed_records))
+    }
+
+    /// Returns every pending change ordered by block, changes at the same block in scheduling order.
+    pub fn get_scheduled_hyperparameter_changes() -> Vec<ScheduledHyperparameterChange> {
+        let mut scheduled: Vec<ScheduledHyperparameterChange> = vec![];
+        for (block, changes) in <ScheduledHyperparameterChanges<T> as IterableStorageMap<u64, Vec<(Hyperparameter, u64)>>>::iter() {
+            for (param, value) in changes {
+                scheduled.push(ScheduledHyperparameterChange { block, param, value });
+            }
+        }
+        // Stable sort keeps the scheduling order within a block.
+        scheduled.sort_by_key(|change| change.block);
+        scheduled
+    }
+
+    /// Returns an update setting only the given hyperparameter, or None if the value does not fit its type.
+    pub fn hyperparameter_update(param: Hyperparameter, value: u64) -> Option<SubnetHyperparamsUpdate> {
+        let mut update = SubnetHyperparamsUpdate::default();
+        match param {
+            Hyperparameter::BlocksPerStep => update.blocks_per_step = Some(value),
+            Hyperparameter::BondsMovingAverage => update.bonds_moving_average = Some(value),
+            Hyperparameter::Difficulty => update.difficulty = Some(value),
+            Hyperparameter::MinimumDifficulty => update.minimum_difficulty = Some(value),
+            Hyperparameter::MaximumDifficulty => update.maximum_difficulty = Some(value),
+            Hyperparameter::BlockEmission => update.block_emission = Some(value),
+            Hyperparameter::AdjustmentInterval => update.adjustment_interval = Some(value),
+            Hyperparameter::TargetRegistrationsPerInterval => update.target_registrations_per_interval = Some(value),
+            Hyperparameter::ActivityCutoff => update.activity_cutoff = Some(value),
+            Hyperparameter::Rho => update.rho = Some(value),
+            Hyperparameter::Kappa => update.kappa = Some(value),
+            Hyperparameter::SelfOwnership => update.self_ownership = Some(value),
+            Hyperparameter::ConsensusMode => update.consensus_mode = Some(value.try_into().ok()?),
+            Hyperparameter::MaxAllowedUids => update.max_allowed_uids = Some(value),
+            Hyperparameter::MaxAllowedValidators => update.max_allowed_validators = Some(value),
+            Hyperparameter::EpochChunkSize => update.epoch_chunk_size = Some(value),
+            Hyperparameter::EpochHistoryLength => update.epoch_history_length = Some(value),
+            Hyperparameter::BondsResetChunkSize => update.bonds_reset_chunk_size = Some(value),
+            Hyperparameter::BondsResetRetention => update.bonds_reset_retention = Some(value),
+            Hyperparameter::InactivityHorizon => update.inactivity_horizon = Some(value),
+            Hyperparameter::DeregistrationBatchSize => update.deregistration_batch_size = Some(value),
+            Hyperparameter::MinAllowedWeights => update.min_allowed_weights = Some(value),
+            Hyperparameter::MaxAllowedMaxMinRatio => update.max_allowed_max_min_ratio = Some(value),
+            Hyperparameter::MaxWeightLimit => update.max_weight_limit = Some(value.try_into().ok()?),
+            Hyperparameter::ValidatorBatchSize => update.validator_batch_size = Some(value),
+            Hyperparameter::ValidatorSequenceLength => update.validator_sequence_length = Some(value),
+            Hyperparameter::ValidatorEpochLen => update.validator_epoch_len = Some(value),
+            Hyperparameter::ValidatorEpochsPerReset => update.validator_epochs_per_reset = Some(value),
+            Hyperparameter::IncentivePruningDenominator => update.incentive_pruning_denominator = Some(value),
+            Hyperparameter::StakePruningDenominator => update.stake_pruning_denominator = Some(value),
+            Hyperparameter::StakePruningMin => update.stake_pruning_min = Some(value),
+            Hyperparameter::ImmunityPeriod => update.immunity_period = Some(value),
+            Hyperparameter::ScalingLawPower => update.scaling_law_power = Some(value.try_into().ok()?),
+            Hyperparameter::SynergyScalingLawPower => update.synergy_scaling_law_power = Some(value.try_into().ok()?),
+            Hyperparameter::ValidatorExcludeQuantile => update.validator_exclude_quantile = Some(value.try_into().ok()?),
+            Hyperparameter::ValidatorPruneLen => update.validator_prune_len = Some(value),
+            Hyperparameter::ValidatorLogitsDivergence => update.validator_logits_divergence = Some(value),
+            Hyperparameter::MinStake => update.min_stake = Some(value),
+            Hyperparameter::MinStakeChange => update.min_stake_change = Some(value),
+        }
+        Some(update)
+    }
+
+    /// Returns the declared inclusive bounds of a hyperparameter.
+    pub fn get_hyperparameter_range(param: Hyperparameter) -> HyperparameterRange {
+        let (min, max): (u64, u64) = match param {
+            Hyperparameter::BlocksPerStep => (1, 1_000_000), // Zero would step on no block, the upper bound keeps blocks times emission within u64.
+            Hyperparameter::BondsMovingAverage => (0, 1_000_000), // Parts per million.
+            Hyperparameter::Difficulty => (1, u64::MAX),
+            Hyperparameter::MinimumDifficulty => (1, u64::MAX),
+            Hyperparameter::MaximumDifficulty => (1, u64::MAX),
+            Hyperparameter::BlockEmission => (0, 1_000_000_000_000), // 1000 tao per block.
+            Hyperparameter::AdjustmentInterval => (1, u64::MAX), // Zero would adjust the difficulty on every block.
+            Hyperparameter::TargetRegistrationsPerInterval => (0, u64::MAX),
+            Hyperparameter::ActivityCutoff => (1, u64::MAX),
+            Hyperparameter::Rho => (1, 40), // Keeps the sigmoid exponent within I65F63.
+            Hyperparameter::Kappa => (1, FIXED_ONE), // I32F32 bits, zero centers the sigmoid at no trust.
+            Hyperparameter::SelfOwnership => (0, FIXED_ONE), // I32F32 bits.
+            Hyperparameter::ConsensusMode => (0, 1), // 0 => sigmoid trust, 1 => stake-weighted median clipping.
+            Hyperparameter::MaxAllowedUids => (1, u32::MAX as u64), // Uids are u32.
+            Hyperparameter::MaxAllowedValidators => (1, u32::MAX as u64),
+            Hyperparameter::EpochChunkSize => (0, u32::MAX as u64),
+            Hyperparameter::EpochHistoryLength => (0, u64::MAX),
+            Hyperparameter::BondsResetChunkSize => (1, u32::MAX as u64), // Bounds the work done in on_initialize.
+            Hyperparameter::BondsResetRetention => (0, 1_000_000), // Parts per million.
+            Hyperparameter::InactivityHorizon => (0, u64::MAX),
+            Hyperparameter::DeregistrationBatchSize => (1, 4096), // Bounds the work done in on_initialize.
+            Hyperparameter::MinAllowedWeights => (0, u32::MAX as u64),
+            Hyperparameter::MaxAllowedMaxMinRatio => (0, u32::MAX as u64),
+            Hyperparameter::MaxWeightLimit => (0, u32::MAX as u64),
+            Hyperparameter::ValidatorBatchSize => (1, u64::MAX),
+            Hyperparameter::ValidatorSequenceLength => (1, u64::MAX),
+            Hyperparameter::ValidatorEpochLen => (1, u64::MAX),
+            Hyperparameter::ValidatorEpochsPerReset => (0, u64::MAX), // 0 disables automatic bond resets.
+            Hyperparameter::IncentivePruningDenominator => (1, u64::MAX),
+            Hyperparameter::StakePruningDenominator => (1, u64::MAX),
+            Hyperparameter::StakePruningMin => (0, u64::MAX),
+            Hyperparameter::ImmunityPeriod => (0, u64::MAX),
+            Hyperparameter::ScalingLawPower => (0, 100), // Percent.
+            Hyperparameter::SynergyScalingLawPower => (0, 100), // Percent.
+            Hyperparameter::ValidatorExcludeQuantile => (0, 100), // Percent.
+            Hyperparameter::ValidatorPruneLen => (0, u64::MAX),
+            Hyperparameter::ValidatorLogitsDivergence => (0, u64::MAX),
+            Hyperparameter::MinStake => (0, u64::MAX),
+            Hyperparameter::MinStakeChange => (1, u64::MAX), // Zero-amount stake calls are never allowed.
+        };
+        HyperparameterRange { min, max }
+    }
+
+    /// Returns the declared bounds of every hyperparameter.
+    pub fn get_hyperparameter_ranges() -> Vec<(Hyperparameter, HyperparameterRange)> {
+        Self::get_hyperparameter_list().into_iter().map(|param| (param, Self::get_hyperparameter_range(param))).collect()
+    }
+
+    pub fn ensure_hyperparameter_in_range(param: Hyperparameter, value: u64) -> Result<(), Error<T>> {
+        let range = Self::get_hyperparameter_range(param);
+        ensure!(value >= range.min && value <= range.max, Error::<T>::StorageValueOutOfRange);
+        Ok(())
+    }
+
+    /// Returns every hyperparameter in the order of the SubnetHyperparams fields.
+    pub fn get_hyperparameter_list() -> Vec<Hyperparameter> {
+        vec![
+            Hyperparameter::BlocksPerStep,
+            Hyperparameter::BondsMovingAverage,
+            Hyperparameter::Difficulty,
+            Hyperparameter::MinimumDifficulty,
+            Hyperparameter::MaximumDifficulty,
+            Hyperparameter::BlockEmission,
+            Hyperparameter::AdjustmentInterval,
+            Hyperparameter::TargetRegistrationsPerInterval,
+            Hyperparameter::ActivityCutoff,
+            Hyperparameter::Rho,
+            Hyperparameter::Kappa,
+            Hyperparameter::SelfOwnership,
+            Hyperparameter::ConsensusMode,
+            Hyperparameter::MaxAllowedUids,
+            Hyperparameter::MaxAllowedValidators,
+            Hyperparameter::EpochChunkSize,
+            Hyperparameter::EpochHistoryLength,
+            Hyperparameter::BondsResetChunkSize,
+            Hyperparameter::BondsResetRetention,
+            Hyperparameter::InactivityHorizon,
+            Hyperparameter::DeregistrationBatchSize,
+            Hyperparameter::MinAllowedWeights,
+            Hyperparameter::MaxAllowedMaxMinRatio,
+            Hyperparameter::MaxWeightLimit,
+            Hyperparameter::ValidatorBatchSize,
+            Hyperparameter::ValidatorSequenceLength,
+            Hyperparameter::ValidatorEpochLen,
+            Hyperparameter::ValidatorEpochsPerReset,
+            Hyperparameter::IncentivePruningDenominator,
+            Hyperparameter::StakePruningDenominator,
+            Hyperparameter::StakePruningMin,
+            Hyperparameter::ImmunityPeriod,
+            Hyperparameter::ScalingLawPower,
+            Hyperparameter::SynergyScalingLawPower,
+            Hyperparameter::ValidatorExcludeQuantile,
+            Hyperparameter::ValidatorPruneLen,
+            Hyperparameter::ValidatorLogitsDivergence,
+            Hyperparameter::MinStake,
+            Hyperparameter::MinStakeChange,
+        ]
+    }
+
+    /// Returns the value of a single hyperparameter in the set, widened to u64.
+    pub fn get_hyperparameter_value(hyperparameters: &SubnetHyperparams, param: Hyperparameter) -> u64 {
+        match param {
+            Hyperparameter::BlocksPerStep => hyperparameters.blocks_per_step,
+            Hyperparameter::BondsMovingAverage => hyperparameters.bonds_moving_average,
+            Hyperparameter::Difficulty => hyperparameters.difficulty,
+            Hyperparameter::MinimumDifficulty => hyperparameters.minimum_difficulty,
+            Hyperparameter::MaximumDifficulty => hyperparameters.maximum_difficulty,
+            Hyperparameter::BlockEmission => hyperparameters.block_emission,
+            Hyperparameter::AdjustmentInterval => hyperparameters.adjustment_interval,
+            Hyperparameter::TargetRegistrationsPerInterval => hyperparameters.target_registrations_per_interval,
+            Hyperparameter::ActivityCutoff => hyperparameters.activity_cutoff,
+            Hyperparameter::Rho => hyperparameters.rho,
+            Hyperparameter::Kappa => hyperparameters.kappa,
+            Hyperparameter::SelfOwnership => hyperparameters.self_ownership,
+            Hyperparameter::ConsensusMode => hyperparameters.consensus_mode as u64,
+            Hyperparameter::MaxAllowedUids => hyperparameters.max_allowed_uids,
+            Hyperparameter::MaxAllowedValidators => hyperparameters.max_allowed_validators,
+            Hyperparameter::EpochChunkSize => hyperparameters.epoch_chunk_size,
+            Hyperparameter::EpochHistoryLength => hyperparameters.epoch_history_length,
+            Hyperparameter::BondsResetChunkSize => hyperparameters.bonds_reset_chunk_size,
+            Hyperparameter::BondsResetRetention => hyperparameters.bonds_reset_retention,
+            Hyperparameter::InactivityHorizon => hyperparameters.inactivity_horizon,
+            Hyperparameter::DeregistrationBatchSize => hyperparameters.deregistration_batch_size,
+            Hyperparameter::MinAllowedWeights => hyperparameters.min_allowed_weights,
+            Hyperparameter::MaxAllowedMaxMinRatio => hyperparameters.max_allowed_max_min_ratio,
+            Hyperparameter::MaxWeightLimit => hyperparameters.max_weight_limit as u64,
+            Hyperparameter::ValidatorBatchSize => hyperparameters.validator_batch_size,
+            Hyperparameter::ValidatorSequenceLength => hyperparameters.validator_sequence_length,
+            Hyperparameter::ValidatorEpochLen => hyperparameters.validator_epoch_len,
+            Hyperparameter::ValidatorEpochsPerReset => hyperparameters.validator_epochs_per_reset,
+            Hyperparameter::IncentivePruningDenominator => hyperparameters.incentive_pruning_denominator,
+            Hyperparameter::StakePruningDenominator => hyperparameters.stake_pruning_denominator,
+            Hyperparameter::StakePruningMin => hyperparameters.stake_pruning_min,
+            Hyperparameter::ImmunityPeriod => hyperparameters.immunity_period,
+            Hyperparameter::ScalingLawPower => hyperparameters.scaling_law_power as u64,
+            Hyperparameter::SynergyScalingLawPower => hyperparameters.synergy_scaling_law_power as u64,
+            Hyperparameter::ValidatorExcludeQuantile => hyperparameters.validator_exclude_quantile as u64,
+            Hyperparameter::ValidatorPruneLen => hyperparameters.validator_prune_len,
+            Hyperparameter::ValidatorLogitsDivergence => hyperparameters.validator_logits_divergence,
+            Hyperparameter::MinStake => hyperparameters.min_stake,
+            Hyperparameter::MinStakeChange => hyperparameters.min_stake_change,
+        }
+    }
+}
@@ -33,7 +33,8 @@ use sp_runtime::{
 	},
 	transaction_validity::{
         TransactionValidityError, 
-		TransactionValidity
+		TransactionValidity,
+		InvalidTransaction
     }
 };
 
@@ -52,6 +53,15 @@ mod serving;
 mod step;
 mod registration;
 mod staking;
+mod mechanism;
+mod migration;
+mod hyperparameters;
+mod coldkey_swap;
+mod fees;
+pub mod math;
+
+pub use mechanism::{IncentiveMechanism, DefaultIncentiveMechanism, DefaultScores, EpochSnapshot, EpochOutput};
+pub use fees::SubtensorFeeAdapter;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -74,6 +84,16 @@ pub mod pallet {
 		/// --- The transaction fee in RAO per byte
 		type TransactionByteFee: Get<BalanceOf<Self>>;
 
+		/// --- The mechanism computing ranks, trust, consensus, incentive, dividends and bonds at each step.
+		type IncentiveMechanism: crate::IncentiveMechanism;
+
+		/// --- The origin allowed to set hyperparameters, root or an on-chain collective.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+
+		/// --- Number of blocks between the announcement of a coldkey swap and its execution.
+		#[pallet::constant]
+		type ColdkeySwapDelay: Get<u64>;
+
 		/// Debug is on
 		#[pallet::constant]
 		type SDebug: Get<u64>;
@@ -82,10 +102,14 @@ pub mod pallet {
 		#[pallet::constant]
 		type InitialRho: Get<u64>;
 
-		/// Kappa constant
+		/// Initial kappa, the consensus shift as I32F32 fixed-point bits on range (0, 1].
 		#[pallet::constant]
 		type InitialKappa: Get<u64>;
 
+		/// Initial consensus mode. 0 for sigmoid trust, 1 for stake-weighted median clipping.
+		#[pallet::constant]
+		type InitialConsensusMode: Get<u8>;
+
 		/// Default Batch size.
 		#[pallet::constant]
 		type InitialValidatorBatchSize: Get<u64>;
@@ -106,6 +130,34 @@ pub mod pallet {
 		#[pallet::constant]
 		type InitialMaxAllowedUids: Get<u64>;
 
+		/// Initial max allowed validators.
+		#[pallet::constant]
+		type InitialMaxAllowedValidators: Get<u64>;
+
+		/// Initial number of uids visited per block by each phase of an epoch. 0 runs the whole epoch in one block.
+		#[pallet::constant]
+		type InitialEpochChunkSize: Get<u64>;
+
+		/// Initial number of committed epochs kept in the epoch history.
+		#[pallet::constant]
+		type InitialEpochHistoryLength: Get<u64>;
+
+		/// Initial number of neurons whose bonds are reset per block by an automatic bond reset.
+		#[pallet::constant]
+		type InitialBondsResetChunkSize: Get<u64>;
+
+		/// Initial fraction of bonds kept by an automatic reset, out of 1_000_000. 0 clears all bonds.
+		#[pallet::constant]
+		type InitialBondsResetRetention: Get<u64>;
+
+		/// Initial number of blocks without setting weights after which a neuron is deregistered. 0 disables deregistration.
+		#[pallet::constant]
+		type InitialInactivityHorizon: Get<u64>;
+
+		/// Initial number of uids checked for inactivity each block. 0 checks every uid.
+		#[pallet::constant]
+		type InitialDeregistrationBatchSize: Get<u64>;
+
 		/// Initial min allowed weights.
 		#[pallet::constant]
 		type InitialMinAllowedWeights: Get<u64>;
@@ -146,9 +198,9 @@ pub mod pallet {
 		#[pallet::constant]
 		type InitialBondsMovingAverage: Get<u64>;
 		
-		/// SelfOwnership constant
+		/// Initial self ownership, the share of incentive kept by each peer as I32F32 fixed-point bits on range [0, 1].
 		#[pallet::constant]
-		type SelfOwnership: Get<u64>;
+		type InitialSelfOwnership: Get<u64>;
 
 		/// Activity constant
 		#[pallet::constant]
@@ -162,13 +214,17 @@ pub mod pallet {
 		#[pallet::constant]
 		type InitialDifficulty: Get<u64>;
 
-		/// Minimum registration difficulty
+		/// Initial minimum registration difficulty.
+		#[pallet::constant]
+		type InitialMinimumDifficulty: Get<u64>;
+
+		/// Initial maximum registration difficulty.
 		#[pallet::constant]
-		type MinimumDifficulty: Get<u64>;
+		type InitialMaximumDifficulty: Get<u64>;
 
-		/// Maximum registration difficulty
+		/// Initial number of tokens emitted per block.
 		#[pallet::constant]
-		type MaximumDifficulty: Get<u64>;
+		type InitialBlockEmission: Get<u64>;
 
 		/// Initial adjustment interval.
 		#[pallet::constant]
@@ -202,13 +258,27 @@ pub mod pallet {
 		/// Initial validator logits divergence penalty/threshold.
 		#[pallet::constant]
 		type InitialValidatorLogitsDivergence: Get<u64>;
+
+		/// Initial minimum stake held by a neuron with a non-zero stake.
+		#[pallet::constant]
+		type InitialMinStake: Get<u64>;
+
+		/// Initial minimum amount of a single stake addition or removal.
+		#[pallet::constant]
+		type InitialMinStakeChange: Get<u64>;
+
+		/// Initial refund of the transaction fee paid by successful registrations.
+		#[pallet::constant]
+		type InitialRegistrationFeeRefund: Get<bool>;
 	}
 
 	pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 	pub type NeuronMetadataOf<T> = NeuronMetadata<AccountIdOf<T>>;
 	pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type ScoresOf<T> = <<T as Config>::IncentiveMechanism as crate::IncentiveMechanism>::Scores;
 
 	#[derive(Encode, Decode, Default, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub struct NeuronMetadata<AccountId> {
 
 		/// ---- The endpoint's code version.
@@ -284,9 +354,327 @@ pub mod pallet {
 		pub weights: Vec<(u32,u32)>,
     }
 
+	/// ---- A neuron as served to metagraph clients: its metadata along with the per-uid
+	/// results kept outside of NeuronMetadata.
+	#[derive(Encode, Decode, Default, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct NeuronInfo<AccountId> {
+		/// ---- The neuron metadata as stored in Neurons.
+		pub neuron: NeuronMetadata<AccountId>,
+
+		/// ---- Whether the neuron held a validator permit at the last epoch.
+		pub validator_permit: bool,
+
+		/// ---- The validator trust of the neuron at the last epoch, normalized to u64::MAX.
+		pub vtrust: u64,
+	}
+
+	/// ---- Phases of an epoch executed over several blocks.
+	/// Every phase but Incentive visits EpochChunkSize uids per block: Snapshot copies the neurons,
+	/// Consensus and Ranks score the received and set weights, Incentive runs once over the network,
+	/// Dividends distributes the incentive to bond holders and Commit writes the results back.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub enum EpochPhase {
+		Idle,
+		Snapshot,
+		Consensus,
+		Ranks,
+		Incentive,
+		Dividends,
+		Commit,
+	}
+	impl Default for EpochPhase {
+		fn default() -> Self { EpochPhase::Idle }
+	}
+
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub struct EpochState {
+		/// ---- The phase to run at the next block.
+		pub phase: EpochPhase,
+
+		/// ---- The next uid visited by the current phase.
+		pub next_uid: u32,
+
+		/// ---- The number of neurons when the epoch started.
+		pub n: u32,
+
+		/// ---- The block the epoch started.
+		pub start_block: u64,
+
+		/// ---- The emission distributed by this epoch.
+		pub emission: u64,
+	}
+
+	/// ---- The part of a neuron read into the epoch snapshot.
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub struct EpochSnapshotRow {
+		pub stake: u64,
+		pub active: bool,
+		pub weights: Vec<(u32,u32)>,
+		pub bonds: Vec<(u32,u64)>,
+	}
+
+	/// ---- Network totals and per-uid results of a committed epoch, kept in the epoch history.
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub struct EpochRecord {
+		pub block: u64,
+		pub total_emission: u64,
+		pub total_bonds_purchased: u64,
+		pub ranks: Vec<u64>,
+		pub trust: Vec<u64>,
+		pub consensus: Vec<u64>,
+		pub incentive: Vec<u64>,
+		pub dividends: Vec<u64>,
+		pub emission: Vec<u64>,
+	}
+
+	/// ---- The results of a single uid in a committed epoch.
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub struct NeuronEpochRecord {
+		pub block: u64,
+		pub rank: u64,
+		pub trust: u64,
+		pub consensus: u64,
+		pub incentive: u64,
+		pub dividends: u64,
+		pub emission: u64,
+	}
+
+	/// ---- Mechanism output being committed, with the totals of the uids committed so far.
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub struct EpochResult {
+		pub active: Vec<bool>,
+		pub validator_permit: Vec<bool>,
+		pub output: crate::EpochOutput,
+		pub total_emission: u64,
+		pub total_staked_emission: u64,
+		pub total_active_stake: u64,
+		pub active_neurons: u32,
+	}
+
+	/// Where the emission earned by a hotkey is paid at each epoch.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub enum EmissionDestination {
+		/// ---- Added to the hotkey stake.
+		Stake,
+		/// ---- Paid to the free balance of the owning coldkey.
+		Coldkey,
+		/// ---- The given percentage is paid to the coldkey, the remainder is staked.
+		Split(u8),
+	}
+	impl Default for EmissionDestination {
+		fn default() -> Self { EmissionDestination::Stake }
+	}
+
+	/// ---- Every subnet hyperparameter, as returned by get_hyperparameters.
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct SubnetHyperparams {
+		pub blocks_per_step: u64,
+		pub bonds_moving_average: u64,
+		pub difficulty: u64,
+		pub minimum_difficulty: u64,
+		pub maximum_difficulty: u64,
+		pub block_emission: u64,
+		pub adjustment_interval: u64,
+		pub target_registrations_per_interval: u64,
+		pub activity_cutoff: u64,
+		pub rho: u64,
+		pub kappa: u64,
+		pub self_ownership: u64,
+		pub consensus_mode: u8,
+		pub max_allowed_uids: u64,
+		pub max_allowed_validators: u64,
+		pub epoch_chunk_size: u64,
+		pub epoch_history_length: u64,
+		pub bonds_reset_chunk_size: u64,
+		pub bonds_reset_retention: u64,
+		pub inactivity_horizon: u64,
+		pub deregistration_batch_size: u64,
+		pub min_allowed_weights: u64,
+		pub max_allowed_max_min_ratio: u64,
+		pub max_weight_limit: u32,
+		pub validator_batch_size: u64,
+		pub validator_sequence_length: u64,
+		pub validator_epoch_len: u64,
+		pub validator_epochs_per_reset: u64,
+		pub incentive_pruning_denominator: u64,
+		pub stake_pruning_denominator: u64,
+		pub stake_pruning_min: u64,
+		pub immunity_period: u64,
+		pub scaling_law_power: u8,
+		pub synergy_scaling_law_power: u8,
+		pub validator_exclude_quantile: u8,
+		pub validator_prune_len: u64,
+		pub validator_logits_divergence: u64,
+		pub min_stake: u64,
+		pub min_stake_change: u64,
+	}
+
+	/// ---- A partial set of subnet hyperparameters passed to sudo_set_hyperparameters.
+	/// Fields left as None keep their current value.
+	#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	pub struct SubnetHyperparamsUpdate {
+		pub blocks_per_step: Option<u64>,
+		pub bonds_moving_average: Option<u64>,
+		pub difficulty: Option<u64>,
+		pub minimum_difficulty: Option<u64>,
+		pub maximum_difficulty: Option<u64>,
+		pub block_emission: Option<u64>,
+		pub adjustment_interval: Option<u64>,
+		pub target_registrations_per_interval: Option<u64>,
+		pub activity_cutoff: Option<u64>,
+		pub rho: Option<u64>,
+		pub kappa: Option<u64>,
+		pub self_ownership: Option<u64>,
+		pub consensus_mode: Option<u8>,
+		pub max_allowed_uids: Option<u64>,
+		pub max_allowed_validators: Option<u64>,
+		pub epoch_chunk_size: Option<u64>,
+		pub epoch_history_length: Option<u64>,
+		pub bonds_reset_chunk_size: Option<u64>,
+		pub bonds_reset_retention: Option<u64>,
+		pub inactivity_horizon: Option<u64>,
+		pub deregistration_batch_size: Option<u64>,
+		pub min_allowed_weights: Option<u64>,
+		pub max_allowed_max_min_ratio: Option<u64>,
+		pub max_weight_limit: Option<u32>,
+		pub validator_batch_size: Option<u64>,
+		pub validator_sequence_length: Option<u64>,
+		pub validator_epoch_len: Option<u64>,
+		pub validator_epochs_per_reset: Option<u64>,
+		pub incentive_pruning_denominator: Option<u64>,
+		pub stake_pruning_denominator: Option<u64>,
+		pub stake_pruning_min: Option<u64>,
+		pub immunity_period: Option<u64>,
+		pub scaling_law_power: Option<u8>,
+		pub synergy_scaling_law_power: Option<u8>,
+		pub validator_exclude_quantile: Option<u8>,
+		pub validator_prune_len: Option<u64>,
+		pub validator_logits_divergence: Option<u64>,
+		pub min_stake: Option<u64>,
+		pub min_stake_change: Option<u64>,
+	}
+
+	impl SubnetHyperparamsUpdate {
+		/// Returns the number of hyperparameters the update sets.
+		pub fn count( &self ) -> u64 {
+			[
+			self.blocks_per_step.is_some(),
+			self.bonds_moving_average.is_some(),
+			self.difficulty.is_some(),
+			self.minimum_difficulty.is_some(),
+			self.maximum_difficulty.is_some(),
+			self.block_emission.is_some(),
+			self.adjustment_interval.is_some(),
+			self.target_registrations_per_interval.is_some(),
+			self.activity_cutoff.is_some(),
+			self.rho.is_some(),
+			self.kappa.is_some(),
+			self.self_ownership.is_some(),
+			self.consensus_mode.is_some(),
+			self.max_allowed_uids.is_some(),
+			self.max_allowed_validators.is_some(),
+			self.epoch_chunk_size.is_some(),
+			self.epoch_history_length.is_some(),
+			self.bonds_reset_chunk_size.is_some(),
+			self.bonds_reset_retention.is_some(),
+			self.inactivity_horizon.is_some(),
+			self.deregistration_batch_size.is_some(),
+			self.min_allowed_weights.is_some(),
+			self.max_allowed_max_min_ratio.is_some(),
+			self.max_weight_limit.is_some(),
+			self.validator_batch_size.is_some(),
+			self.validator_sequence_length.is_some(),
+			self.validator_epoch_len.is_some(),
+			self.validator_epochs_per_reset.is_some(),
+			self.incentive_pruning_denominator.is_some(),
+			self.stake_pruning_denominator.is_some(),
+			self.stake_pruning_min.is_some(),
+			self.immunity_period.is_some(),
+			self.scaling_law_power.is_some(),
+			self.synergy_scaling_law_power.is_some(),
+			self.validator_exclude_quantile.is_some(),
+			self.validator_prune_len.is_some(),
+			self.validator_logits_divergence.is_some(),
+			self.min_stake.is_some(),
+			self.min_stake_change.is_some(),
+			].iter().filter( |is_set| **is_set ).count() as u64
+		}
+	}
+
+	/// ---- A single hyperparameter, named by the field of SubnetHyperparams it sets.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum Hyperparameter {
+		BlocksPerStep,
+		BondsMovingAverage,
+		Difficulty,
+		MinimumDifficulty,
+		MaximumDifficulty,
+		BlockEmission,
+		AdjustmentInterval,
+		TargetRegistrationsPerInterval,
+		ActivityCutoff,
+		Rho,
+		Kappa,
+		SelfOwnership,
+		ConsensusMode,
+		MaxAllowedUids,
+		MaxAllowedValidators,
+		EpochChunkSize,
+		EpochHistoryLength,
+		BondsResetChunkSize,
+		BondsResetRetention,
+		InactivityHorizon,
+		DeregistrationBatchSize,
+		MinAllowedWeights,
+		MaxAllowedMaxMinRatio,
+		MaxWeightLimit,
+		ValidatorBatchSize,
+		ValidatorSequenceLength,
+		ValidatorEpochLen,
+		ValidatorEpochsPerReset,
+		IncentivePruningDenominator,
+		StakePruningDenominator,
+		StakePruningMin,
+		ImmunityPeriod,
+		ScalingLawPower,
+		SynergyScalingLawPower,
+		ValidatorExcludeQuantile,
+		ValidatorPruneLen,
+		ValidatorLogitsDivergence,
+		MinStake,
+		MinStakeChange,
+	}
+
+	/// ---- A hyperparameter change waiting in the schedule, as returned by get_scheduled_hyperparameter_changes.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct ScheduledHyperparameterChange {
+		pub block: u64,
+		pub param: Hyperparameter,
+		pub value: u64,
+	}
+
+	/// ---- The inclusive bounds a hyperparameter value must fall within, as returned by get_hyperparameter_ranges.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct HyperparameterRange {
+		pub min: u64,
+		pub max: u64,
+	}
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	/// One as I32F32 fixed-point bits, the unit of kappa and self ownership.
+	pub const FIXED_ONE: u64 = 1 << 32;
+
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// ************************************************************
@@ -342,6 +730,26 @@ pub mod pallet {
 		DefaultKappa<T>
 	>;
 
+	#[pallet::type_value] 
+	pub fn DefaultSelfOwnership<T: Config>() -> u64 { T::InitialSelfOwnership::get() }
+	#[pallet::storage]
+	pub type SelfOwnership<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultSelfOwnership<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultConsensusMode<T: Config>() -> u8 { T::InitialConsensusMode::get() }
+	#[pallet::storage]
+	pub type ConsensusMode<T> = StorageValue<
+		_, 
+		u8, 
+		ValueQuery,
+		DefaultConsensusMode<T>
+	>;
+
 	#[pallet::type_value] 
 	pub fn DefaultIncentivePruningDenominator<T: Config>() -> u64 { T::InitialIncentivePruningDenominator::get() }
 	#[pallet::storage]
@@ -422,6 +830,75 @@ pub mod pallet {
 		DefaultMaxAllowedUids<T>
 	>;
 
+	#[pallet::type_value] 
+	pub fn DefaultMaxAllowedValidators<T: Config>() -> u64 { T::InitialMaxAllowedValidators::get() }
+	#[pallet::storage]
+	pub type MaxAllowedValidators<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultMaxAllowedValidators<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultEpochChunkSize<T: Config>() -> u64 { T::InitialEpochChunkSize::get() }
+	#[pallet::storage]
+	pub type EpochChunkSize<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultEpochChunkSize<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultEpochHistoryLength<T: Config>() -> u64 { T::InitialEpochHistoryLength::get() }
+	#[pallet::storage]
+	pub type EpochHistoryLength<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultEpochHistoryLength<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultBondsResetChunkSize<T: Config>() -> u64 { T::InitialBondsResetChunkSize::get() }
+	#[pallet::storage]
+	pub type BondsResetChunkSize<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultBondsResetChunkSize<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultBondsResetRetention<T: Config>() -> u64 { T::InitialBondsResetRetention::get() }
+	#[pallet::storage]
+	pub type BondsResetRetention<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultBondsResetRetention<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultInactivityHorizon<T: Config>() -> u64 { T::InitialInactivityHorizon::get() }
+	#[pallet::storage]
+	pub type InactivityHorizon<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultInactivityHorizon<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultDeregistrationBatchSize<T: Config>() -> u64 { T::InitialDeregistrationBatchSize::get() }
+	#[pallet::storage]
+	pub type DeregistrationBatchSize<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultDeregistrationBatchSize<T>
+	>;
 
 	#[pallet::type_value] 
 	pub fn DefaultMinAllowedWeights<T: Config>() -> u64 { T::InitialMinAllowedWeights::get() }
@@ -513,6 +990,36 @@ pub mod pallet {
 		DefaultDifficulty<T>
 	>;
 
+	#[pallet::type_value] 
+	pub fn DefaultMinimumDifficulty<T: Config>() -> u64 { T::InitialMinimumDifficulty::get() }
+	#[pallet::storage]
+	pub type MinimumDifficulty<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultMinimumDifficulty<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultMaximumDifficulty<T: Config>() -> u64 { T::InitialMaximumDifficulty::get() }
+	#[pallet::storage]
+	pub type MaximumDifficulty<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultMaximumDifficulty<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultBlockEmission<T: Config>() -> u64 { T::InitialBlockEmission::get() }
+	#[pallet::storage]
+	pub type BlockEmission<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultBlockEmission<T>
+	>;
+
 	#[pallet::type_value] 
 	pub fn DefaultActivityCutoff<T: Config>() -> u64 { T::InitialActivityCutoff::get() }
 	#[pallet::storage]
@@ -613,6 +1120,36 @@ pub mod pallet {
 		DefaultValidatorLogitsDivergence<T>
 	>;
 
+	#[pallet::type_value] 
+	pub fn DefaultMinStake<T: Config>() -> u64 { T::InitialMinStake::get() }
+	#[pallet::storage]
+	pub type MinStake<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultMinStake<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultMinStakeChange<T: Config>() -> u64 { T::InitialMinStakeChange::get() }
+	#[pallet::storage]
+	pub type MinStakeChange<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery,
+		DefaultMinStakeChange<T>
+	>;
+
+	#[pallet::type_value] 
+	pub fn DefaultRegistrationFeeRefund<T: Config>() -> bool { T::InitialRegistrationFeeRefund::get() }
+	#[pallet::storage]
+	pub type RegistrationFeeRefund<T> = StorageValue<
+		_, 
+		bool, 
+		ValueQuery,
+		DefaultRegistrationFeeRefund<T>
+	>;
+
 	/// #[pallet::type_value] 
 	/// pub fn DefaultFoundationAccount<T: Config>() -> u64 { T::InitialFoundationAccount::get() }
 	#[pallet::storage]
@@ -672,6 +1209,17 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+	/// ---- Maps from hotkey to the destination of its emission.
+	#[pallet::storage]
+	#[pallet::getter(fn emission_destination)]
+    pub(super) type EmissionDestinations<T:Config> = StorageMap<
+		_, 
+		Blake2_128Concat, 
+		T::AccountId, 
+		EmissionDestination, 
+		ValueQuery
+	>;
+
 	/// ---- Maps from uid to neuron.
 	#[pallet::storage]
     #[pallet::getter(fn uid)]
@@ -694,6 +1242,85 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// ---- Maps from coldkey to the hotkeys of the neurons it owns, in registration order.
+	#[pallet::storage]
+	#[pallet::getter(fn owned_hotkeys)]
+	pub type OwnedHotkeys<T:Config> = StorageMap<
+		_, 
+		Blake2_128Concat, 
+		T::AccountId, 
+		Vec<T::AccountId>, 
+		ValueQuery,
+	>;
+
+	/// ---- Maps from hotkey to whether its set_weights and serve_axon calls are refunded their fee.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_exempt)]
+	pub type FeeExemptHotkeys<T:Config> = StorageMap<
+		_, 
+		Blake2_128Concat, 
+		T::AccountId, 
+		bool, 
+		ValueQuery,
+	>;
+
+	/// ---- Maps from hotkey to the coldkey which agreed to pay the fees of its set_weights and serve_axon calls.
+	/// The agreement only holds while that coldkey owns the neuron of the hotkey.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_paying_coldkey)]
+	pub type FeePayingColdkeys<T:Config> = StorageMap<
+		_, 
+		Blake2_128Concat, 
+		T::AccountId, 
+		T::AccountId, 
+		OptionQuery,
+	>;
+
+	/// ---- Maps from coldkey to its announced swap, the new coldkey and the block the swap executes at.
+	#[pallet::storage]
+	#[pallet::getter(fn coldkey_swap)]
+	pub type ColdkeySwaps<T:Config> = StorageMap<
+		_, 
+		Blake2_128Concat, 
+		T::AccountId, 
+		(T::AccountId, u64), 
+		OptionQuery,
+	>;
+
+	/// ---- Maps from block number to the coldkeys whose announced swap executes at the start of that block.
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_coldkey_swaps)]
+	pub(super) type ScheduledColdkeySwaps<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u64, 
+		Vec<T::AccountId>, 
+		ValueQuery,
+	>;
+
+	/// ---- Maps from uid to uid as a set of uids freed by deregistration, reused by the next registrations.
+	#[pallet::storage]
+    pub(super) type FreeUids<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u32, 
+		u32, 
+		OptionQuery,
+	>;
+
+	/// ---- The uids in FreeUids in ascending order, so the lowest one is found without iterating the map.
+	/// Bounded by the neuron count since every entry is a distinct registered uid.
+	#[pallet::storage]
+	pub(super) type FreeUidQueue<T> = StorageValue<_, Vec<u32>, ValueQuery>;
+
+	/// ---- The next uid checked for inactivity.
+	#[pallet::storage]
+	pub type DeregistrationCursor<T> = StorageValue<
+		_, 
+		u32, 
+		ValueQuery
+	>;
+
 	#[pallet::type_value] 
 	pub fn DefaultBlockAtRegistration<T: Config>() -> u64 { 0 }
 	#[pallet::storage]
@@ -707,19 +1334,142 @@ pub mod pallet {
 		DefaultBlockAtRegistration<T>
 	>;
 
-	/// ************************************************************
-	///	-Genesis-Configuration
-	/// ************************************************************
-	/// ---- Genesis Configuration (Mostly used for testing.)
-    #[pallet::genesis_config]
-    pub struct GenesisConfig {
-        pub stake: Vec<(u64, u64)>,
-    }
+	/// ---- Maps from uid to validator permit. Recomputed each epoch for the top stakers.
+	#[pallet::storage]
+	#[pallet::getter(fn validator_permit)]
+    pub(super) type ValidatorPermit<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u32, 
+		bool, 
+		ValueQuery,
+	>;
 
-	#[cfg(feature = "std")]
-	impl Default for GenesisConfig {
-		fn default() -> Self {
-			Self {
+	/// ---- Maps from uid to validator trust, the share of its weight which survived consensus at the last epoch.
+	#[pallet::storage]
+	#[pallet::getter(fn validator_trust)]
+    pub(super) type ValidatorTrust<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u32, 
+		u64, 
+		ValueQuery,
+	>;
+
+	/// ---- Maps from block number to the hyperparameter changes applied at the start of that block, in scheduling order.
+	#[pallet::storage]
+	pub(super) type ScheduledHyperparameterChanges<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u64, 
+		Vec<(Hyperparameter, u64)>, 
+		ValueQuery,
+	>;
+
+	/// ---- The state of the epoch currently running across blocks.
+	#[pallet::storage]
+	#[pallet::getter(fn current_epoch)]
+	pub(super) type CurrentEpoch<T:Config> = StorageValue<
+		_, 
+		EpochState, 
+		ValueQuery
+	>;
+
+	/// ---- Maps from uid to the neuron as read at the snapshot phase of the current epoch.
+	#[pallet::storage]
+	pub(super) type EpochSnapshotRows<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u32, 
+		EpochSnapshotRow, 
+		OptionQuery,
+	>;
+
+	/// ---- Maps from uid_j to the weights ( uid_i, w_ij ) it received, as read at the snapshot phase of the current epoch.
+	#[pallet::storage]
+	pub(super) type EpochReceivedWeights<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u32, 
+		Vec<(u32,u32)>, 
+		ValueQuery,
+	>;
+
+	/// ---- Stake and activity of each uid, as read at the snapshot phase of the current epoch.
+	#[pallet::storage]
+	pub(super) type EpochStakes<T:Config> = StorageValue<
+		_, 
+		Vec<(u64,bool)>, 
+		ValueQuery
+	>;
+
+	/// ---- Intermediate scores of the incentive mechanism while the current epoch runs across blocks.
+	#[pallet::storage]
+	pub(super) type EpochScores<T:Config> = StorageValue<
+		_, 
+		ScoresOf<T>, 
+		OptionQuery
+	>;
+
+	/// ---- The number of epochs committed so far.
+	#[pallet::storage]
+	pub type EpochCounter<T> = StorageValue<
+		_, 
+		u64, 
+		ValueQuery
+	>;
+
+	/// ---- The next uid whose bonds are reset while an automatic bond reset is running.
+	#[pallet::storage]
+	pub type BondsResetCursor<T> = StorageValue<
+		_, 
+		u32, 
+		OptionQuery
+	>;
+
+	/// ---- Maps from epoch number to its record, only the last EpochHistoryLength epochs are kept.
+	#[pallet::storage]
+	#[pallet::getter(fn epoch_history)]
+	pub(super) type EpochHistory<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u64, 
+		EpochRecord, 
+		OptionQuery,
+	>;
+
+	/// ---- Results of the current epoch waiting for the commit phase.
+	#[pallet::storage]
+	pub(super) type PendingEpochResult<T:Config> = StorageValue<
+		_, 
+		EpochResult, 
+		OptionQuery
+	>;
+
+	/// ---- Maps from uid to uid as a set of the uids registered while an epoch is running.
+	/// These neurons keep their fresh state when the epoch commits, and their uids earn no emission at it.
+	#[pallet::storage]
+	pub(super) type EpochRegistrations<T:Config> = StorageMap<
+		_, 
+		Identity, 
+		u32, 
+		u32, 
+		ValueQuery,
+	>;
+
+	/// ************************************************************
+	///	-Genesis-Configuration
+	/// ************************************************************
+	/// ---- Genesis Configuration (Mostly used for testing.)
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        pub stake: Vec<(u64, u64)>,
+    }
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			Self {
 				stake: Default::default(),
 			}
 		}
@@ -770,6 +1520,18 @@ pub mod pallet {
 		/// the chain.
 		NeuronRegistered(u32),
 
+		/// --- Event created when an epoch has been committed. 
+		/// [block, active neurons, total emission, total bonds purchased, total active stake]
+		EpochCompleted(u64, u32, u64, u64, u64),
+
+		/// --- Event created when registration evicts a uid, the bonds held in it 
+		/// are cleared at the next epoch. [uid, old_hotkey]
+		NeuronPruned(u32, T::AccountId),
+
+		/// --- Event created when an inactive neuron is deregistered, its stake 
+		/// returned to the coldkey and its uid freed. [uid, hotkey, stake]
+		NeuronDeregistered(u32, T::AccountId, u64),
+
 		/// --- Event created when the axon server information is added to the network.
 		AxonServed(u32),
 
@@ -781,9 +1543,51 @@ pub mod pallet {
 		/// the staking account into the coldkey account.
 		StakeRemoved(T::AccountId, u64),
 
+		/// --- Event created when stake has been moved between two hotkeys
+		/// of the same coldkey, without passing through the coldkey account.
+		StakeMoved(T::AccountId, T::AccountId, u64),
+
+		/// --- Event created when a coldkey announces the swap of its neurons to a new coldkey,
+		/// with the block at which the swap executes.
+		ColdkeySwapAnnounced(T::AccountId, T::AccountId, u64),
+
+		/// --- Event created when a coldkey cancels its announced swap.
+		ColdkeySwapCancelled(T::AccountId, T::AccountId),
+
+		/// --- Event created when the neurons and stake of a coldkey have been moved to the new coldkey.
+		ColdkeySwapped(T::AccountId, T::AccountId),
+
+		/// --- Event created when the coldkey changes where the emission of a hotkey is paid.
+		EmissionDestinationSet(T::AccountId, EmissionDestination),
+
+		/// --- Event created when a coldkey agrees or stops paying the fees of the set_weights
+		/// and serve_axon calls of its hotkey. [coldkey, hotkey, pays]
+		ColdkeyPaysFeesSet(T::AccountId, T::AccountId, bool),
+
 		/// --- Event created when the difficulty has been set.
 		DifficultySet(u64),
 
+		/// --- Event created when the minimum registration difficulty has been set.
+		MinimumDifficultySet(u64),
+
+		/// --- Event created when the maximum registration difficulty has been set.
+		MaximumDifficultySet(u64),
+
+		/// --- Event created when the block emission has been set.
+		BlockEmissionSet(u64),
+
+		/// --- Event created when a set of hyperparameters has been applied at once.
+		HyperparametersSet(SubnetHyperparams),
+
+		/// --- Event created when a hyperparameter change has been scheduled for a future block.
+		HyperparameterChangeScheduled(Hyperparameter, u64, u64),
+
+		/// --- Event created when a scheduled hyperparameter change has been applied.
+		HyperparameterChangeApplied(Hyperparameter, u64),
+
+		/// --- Event created when a scheduled hyperparameter change was dropped because it was out of range at its block.
+		HyperparameterChangeRejected(Hyperparameter, u64),
+
 		/// --- Event created when default blocks per step has been set.
 		BlocksPerStepSet(u64),
 
@@ -805,9 +1609,36 @@ pub mod pallet {
 		/// --- Event created when mechanism kappa has been set.
 		KappaSet(u64),
 
+		/// --- Event created when mechanism self ownership has been set.
+		SelfOwnershipSet(u64),
+
+		/// --- Event created when the mechanism consensus mode has been set.
+		ConsensusModeSet(u8),
+
 		/// --- Event created when max allowed uids has been set.
 		MaxAllowedUidsSet(u64),
 
+		/// --- Event created when max allowed validators has been set.
+		MaxAllowedValidatorsSet(u64),
+
+		/// --- Event created when the epoch chunk size has been set.
+		EpochChunkSizeSet(u64),
+
+		/// --- Event created when the epoch history length has been set.
+		EpochHistoryLengthSet(u64),
+
+		/// --- Event created when the bonds reset chunk size has been set.
+		BondsResetChunkSizeSet(u64),
+
+		/// --- Event created when the bonds reset retention has been set.
+		BondsResetRetentionSet(u64),
+
+		/// --- Event created when the inactivity horizon has been set.
+		InactivityHorizonSet(u64),
+
+		/// --- Event created when the deregistration batch size has been set.
+		DeregistrationBatchSizeSet(u64),
+
 		/// --- Event created when min allowed weights has been set.
 		MinAllowedWeightsSet(u64),
 
@@ -847,6 +1678,19 @@ pub mod pallet {
 		/// --- Event created when the validator logits divergence value has been set.
 		ValidatorLogitsDivergenceSet( u64 ),
 
+		/// --- Event created when the minimum stake of a neuron has been set.
+		MinStakeSet( u64 ),
+
+		/// --- Event created when the minimum amount of a stake addition or removal has been set.
+		MinStakeChangeSet( u64 ),
+
+		/// --- Event created when a hotkey is exempted from, or made to pay again, the fees of its
+		/// set_weights and serve_axon calls.
+		FeeExemptionSet( T::AccountId, bool ),
+
+		/// --- Event created when the refund of the fee paid by successful registrations is turned on or off.
+		RegistrationFeeRefundSet( bool ),
+
 		/// --- Event created when the validator default epoch length has been set.
 		ValidatorEpochLenSet(u64),
 
@@ -954,6 +1798,44 @@ pub mod pallet {
 
 		/// ---- Thrown when the caller attempts to set a storage value outside of its allowed range.
 		StorageValueOutOfRange,
+
+		/// ---- Thrown when a neuron without a validator permit attempts to set non-self weights.
+		NoValidatorPermit,
+
+		/// ---- Thrown when an emission split sends more than 100 percent to the coldkey.
+		InvalidEmissionSplit,
+
+		/// ---- Thrown when a hyperparameter change is scheduled at a block which is not in the future.
+		InvalidScheduleBlock,
+
+		/// ---- Thrown when stake is moved from a hotkey onto itself. See: fn move_stake.
+		MoveStakeToSameHotkey,
+
+		/// ---- Thrown when a coldkey announces a swap onto itself. See: fn announce_coldkey_swap.
+		ColdkeySwapToSameColdkey,
+
+		/// ---- Thrown when a stake addition or removal is smaller than the minimum stake change.
+		StakeChangeBelowMinimum,
+
+		/// ---- Thrown when a stake addition leaves the neuron with less than the minimum stake.
+		StakeBelowMinimum,
+
+		/// ---- Thrown when a partial stake removal leaves less than the minimum stake on the neuron.
+		/// The whole stake must be withdrawn instead.
+		RemainingStakeBelowMinimum,
+
+		/// ---- Thrown when a coldkey which owns no neuron announces a swap.
+		ColdkeyOwnsNoNeurons,
+
+		/// ---- Thrown when a coldkey announces a swap while another one is pending.
+		ColdkeySwapAlreadyAnnounced,
+
+		/// ---- Thrown when a coldkey cancels a swap it has not announced.
+		NoColdkeySwapAnnounced,
+
+		/// ---- Thrown when stake is added, removed or moved while an epoch running across blocks
+		/// reads the stake at its snapshot phase or adds the emission onto it at its commit phase.
+		StakeFrozenByEpoch,
 	}
 
 	impl<T: Config> Printable for Error<T> {
@@ -985,7 +1867,28 @@ pub mod pallet {
 			// Only run the block step every `blocks_per_step`.
 			// Initially `blocks_since_last_step + 1` is 0 but increments until it reaches `blocks_per_step`.
 			// We use the >= here in the event that we lower get_blocks per step and these qualities never meet.
-			if Self::get_blocks_since_last_step() + 1 >= Self::get_blocks_per_step() {
+			let mut weight: Weight = 0;
+
+			// Apply the hyperparameter changes scheduled for this block before anything reads them.
+			weight += Self::apply_scheduled_hyperparameter_changes();
+
+			// Re-point the neurons of the coldkeys whose swap delay ends at this block.
+			weight += Self::execute_scheduled_coldkey_swaps();
+
+			if Self::bonds_reset_in_progress() {
+
+				// An automatic bond reset is running, finish it before the next epoch starts.
+				weight += Self::continue_bonds_reset();
+				Self::set_blocks_since_last_step( Self::get_blocks_since_last_step() + 1 );
+
+			} else if Self::epoch_in_progress() {
+
+				// An epoch spanning several blocks is running. Advance it by one chunk and keep counting
+				// blocks so that the next step emits for every block that passed in the meantime.
+				weight += Self::continue_epoch();
+				Self::set_blocks_since_last_step( Self::get_blocks_since_last_step() + 1 );
+
+			} else if Self::get_blocks_since_last_step() + 1 >= Self::get_blocks_per_step() {
 
 				// Compute the amount of emission we perform this step.
 				// Note that we use blocks_since_last_step here instead of block_per_step incase this is lowered
@@ -993,7 +1896,12 @@ pub mod pallet {
 				let emission_this_step:u64 = ( Self::get_blocks_since_last_step() + 1 ) * Self::get_block_emission();
 
 				// Apply emission step based on mechanism and updates values.
-				Self::mechanism_step( emission_this_step );
+				// With a chunk size the step is spread over the following blocks.
+				if Self::get_epoch_chunk_size() == 0 {
+					Self::mechanism_step( emission_this_step );
+				} else {
+					weight += Self::begin_epoch( emission_this_step );
+				}
 
 				// Reset counter down to 0, this ensures that if `blocks_per_step=1` we will do an emission on every block.
 				// If `blocks_per_step=2` we will skip the next block, since (0+1) !>= 2, add one to the counter, and then apply the next
@@ -1005,10 +1913,21 @@ pub mod pallet {
 				Self::set_blocks_since_last_step( Self::get_blocks_since_last_step() + 1 );
 			}
 
+			// Deregister the next batch of inactive neurons, uids are never freed while an epoch or bond reset is running.
+			if !Self::epoch_in_progress() && !Self::bonds_reset_in_progress() {
+				weight += Self::deregister_inactive_neurons();
+			}
+
 			// Make a difficulty update.
 			Self::update_difficulty();
 			
-			return 0;
+			return weight;
+		}
+
+		/// ---- Called when the runtime is upgraded, before any on_initialize of the upgrade block.
+		/// Runs every storage migration above the on chain storage version in order.
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_v1::<T>() + migration::migrate_to_v2::<T>() + migration::migrate_to_v3::<T>()
 		}
 	}
     
@@ -1057,15 +1976,22 @@ pub mod pallet {
 		/// 		- When the amount to stake exceeds the amount of balance in the
 		/// 		associated colkey account.
 		///
-        #[pallet::weight((0, DispatchClass::Normal, Pays::No))]
+		/// # Fees:
+		/// 	The length and weight fee is charged to the hotkey, or to its coldkey once it has opted
+		/// 	in, and refunded when the hotkey is fee exempt. See: fn set_coldkey_pays_fees,
+		/// 	fn sudo_set_fee_exempt and SubtensorFeeAdapter.
+		///
+        #[pallet::weight((T::DbWeight::get().reads_writes(3, 2).saturating_add(T::DbWeight::get().reads(dests.len() as u64)), DispatchClass::Normal, Pays::Yes))]
 		pub fn set_weights(
 			origin:OriginFor<T>, 
 			dests: Vec<u32>, 
 			weights: Vec<u32>
-		) -> DispatchResult {
-			Self::do_set_weights(origin, dests, weights)
+		) -> DispatchResultWithPostInfo {
+			let hotkey = ensure_signed( origin.clone() )?;
+			Self::do_set_weights(origin, dests, weights)?;
+			Ok( Self::get_fee_for_hotkey( &hotkey ).into() )
 		}
-		
+
 		/// --- Adds stake to a neuron account. The call is made from the
 		/// coldkey account linked in the neurons's NeuronMetadata.
 		/// Only the associated coldkey is allowed to make staking and
@@ -1098,7 +2024,13 @@ pub mod pallet {
 		/// 		- When the amount to stake exceeds the amount of balance in the
 		/// 		associated colkey account.
 		///
-		#[pallet::weight((0, DispatchClass::Normal, Pays::No))]
+		/// 	* 'StakeChangeBelowMinimum':
+		/// 		- When the amount to stake is below the minimum stake change.
+		///
+		/// 	* 'StakeBelowMinimum':
+		/// 		- When the stake of the hotkey after staking is below the minimum stake.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(6, 3), DispatchClass::Normal, Pays::Yes))]
 		pub fn add_stake(
 			origin:OriginFor<T>, 
 			hotkey: T::AccountId, 
@@ -1134,7 +2066,14 @@ pub mod pallet {
 		/// 		- When the amount to unstake exceeds the quantity staked in the
 		/// 		associated hotkey staking account.
 		///
-		#[pallet::weight((0, DispatchClass::Normal, Pays::No))]
+		/// 	* 'StakeChangeBelowMinimum':
+		/// 		- When a partial withdrawal is below the minimum stake change.
+		///
+		/// 	* 'RemainingStakeBelowMinimum':
+		/// 		- When a partial withdrawal leaves less than the minimum stake on the hotkey,
+		/// 		in which case the whole stake must be withdrawn.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(6, 3), DispatchClass::Normal, Pays::Yes))]
 		pub fn remove_stake(
 			origin:OriginFor<T>, 
 			hotkey: T::AccountId, 
@@ -1166,7 +2105,12 @@ pub mod pallet {
 		/// 	* 'AxonServed':
 		/// 		- On subscription of a new neuron to the active set.
 		///
-		#[pallet::weight((0, DispatchClass::Normal, Pays::No))]
+		/// # Fees:
+		/// 	The length and weight fee is charged to the hotkey, or to its coldkey once it has opted
+		/// 	in, and refunded when the hotkey is fee exempt. See: fn set_coldkey_pays_fees,
+		/// 	fn sudo_set_fee_exempt and SubtensorFeeAdapter.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Normal, Pays::Yes))]
 		pub fn serve_axon (
 			origin:OriginFor<T>, 
 			version: u32, 
@@ -1174,8 +2118,10 @@ pub mod pallet {
 			port: u16, 
 			ip_type: u8, 
 			modality: u8 
-		) -> DispatchResult {
-			Self::do_serve_axon( origin, version, ip, port, ip_type, modality )
+		) -> DispatchResultWithPostInfo {
+			let hotkey = ensure_signed( origin.clone() )?;
+			Self::do_serve_axon( origin, version, ip, port, ip_type, modality )?;
+			Ok( Self::get_fee_for_hotkey( &hotkey ).into() )
 		}
 
 		/// ---- Registers a new neuron to the graph. 
@@ -1203,7 +2149,12 @@ pub mod pallet {
 		/// 	* 'NeuronRegistered':
 		/// 		- On subscription of a new neuron to the active set.
 		///
-		#[pallet::weight((0, DispatchClass::Normal, Pays::No))]
+		/// # Fees:
+		/// 	The length and weight fee is charged to the caller unless RegistrationFeeRefund is on.
+		/// 	The caller is usually an unfunded hotkey, so the decision is made before dispatch and
+		/// 	invalid work is rejected by SubtensorSignedExtension::pre_dispatch instead.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(10, 10), DispatchClass::Normal, Pallet::<T>::get_fee_for_registration()))]
 		pub fn register( 
 				origin:OriginFor<T>, 
 				block_number: u64, 
@@ -1214,6 +2165,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			Self::do_registration(origin, block_number, nonce, work, hotkey, coldkey)
 		}
+
 		/// ---- SUDO ONLY FUNCTIONS
 		///
 		/// # Args:
@@ -1237,7 +2189,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			blocks_per_step: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::BlocksPerStep, blocks_per_step )?;
 			BlocksPerStep::<T>::set( blocks_per_step );
 			Self::deposit_event( Event::BlocksPerStepSet( blocks_per_step ) );
 			Ok(())
@@ -1248,7 +2201,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			bonds_moving_average: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::BondsMovingAverage, bonds_moving_average )?;
 			BondsMovingAverage::<T>::set( bonds_moving_average );
 			Self::deposit_event( Event::BondsMovingAverageSet( bonds_moving_average ) );
 			Ok(())
@@ -1259,7 +2213,9 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			difficulty: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::Difficulty, difficulty )?;
+			ensure!( difficulty >= Self::get_minimum_difficulty() && difficulty <= Self::get_maximum_difficulty(), Error::<T>::StorageValueOutOfRange );
 			Difficulty::<T>::set( difficulty );
 			Self::deposit_event( Event::DifficultySet( difficulty ) );
 			Ok(())
@@ -1270,7 +2226,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			adjustment_interval: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::AdjustmentInterval, adjustment_interval )?;
 			AdjustmentInterval::<T>::set( adjustment_interval );
 			Self::deposit_event( Event::AdjustmentIntervalSet( adjustment_interval ) );
 			Ok(())
@@ -1281,7 +2238,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			activity_cutoff: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ActivityCutoff, activity_cutoff )?;
 			ActivityCutoff::<T>::set( activity_cutoff );
 			Self::deposit_event( Event::ActivityCuttoffSet( activity_cutoff ) );
 			Ok(())
@@ -1292,7 +2250,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			target_registrations_per_interval: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::TargetRegistrationsPerInterval, target_registrations_per_interval )?;
 			TargetRegistrationsPerInterval::<T>::set( target_registrations_per_interval );
 			Self::deposit_event( Event::TargetRegistrationsPerIntervalSet( target_registrations_per_interval ) );
 			Ok(())
@@ -1303,7 +2262,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			rho: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::Rho, rho )?;
 			Rho::<T>::set( rho );
 			Self::deposit_event( Event::RhoSet( rho ) );
 			Ok(())
@@ -1314,7 +2274,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			kappa: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::Kappa, kappa )?;
 			Kappa::<T>::set( kappa );
 			Self::deposit_event( Event::KappaSet( kappa ) );
 			Ok(())
@@ -1325,7 +2286,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			max_allowed_uids: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MaxAllowedUids, max_allowed_uids )?;
 			MaxAllowedUids::<T>::set( max_allowed_uids );
 			Self::deposit_event( Event::MaxAllowedUidsSet( max_allowed_uids ) );
 			Ok(())
@@ -1336,7 +2298,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			min_allowed_weights: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MinAllowedWeights, min_allowed_weights )?;
 			MinAllowedWeights::<T>::set( min_allowed_weights );
 			Self::deposit_event( Event::MinAllowedWeightsSet( min_allowed_weights ) );
 			Ok(())
@@ -1347,7 +2310,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			max_allowed_max_min_ratio: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MaxAllowedMaxMinRatio, max_allowed_max_min_ratio )?;
 			MaxAllowedMaxMinRatio::<T>::set( max_allowed_max_min_ratio );
 			Self::deposit_event( Event::MaxAllowedMaxMinRatioSet( max_allowed_max_min_ratio ) );
 			Ok(())
@@ -1358,7 +2322,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			max_weight_limit: u32 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MaxWeightLimit, max_weight_limit as u64 )?;
 			MaxWeightLimit::<T>::set( max_weight_limit );
 			Self::deposit_event( Event::MaxWeightLimitSet( max_weight_limit ) );
 			Ok(())
@@ -1369,7 +2334,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_batch_size: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorBatchSize, validator_batch_size )?;
 			ValidatorBatchSize::<T>::set( validator_batch_size );
 			Self::deposit_event( Event::ValidatorBatchSizeSet( validator_batch_size ) );
 			Ok(())
@@ -1380,7 +2346,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_sequence_length: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorSequenceLength, validator_sequence_length )?;
 			ValidatorSequenceLength::<T>::set( validator_sequence_length );
 			Self::deposit_event( Event::ValidatorSequenceLengthSet( validator_sequence_length ) );
 			Ok(())
@@ -1391,7 +2358,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_epoch_len : u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorEpochLen, validator_epoch_len )?;
 			ValidatorEpochLen::<T>::set( validator_epoch_len );
 			Self::deposit_event( Event::ValidatorEpochLenSet( validator_epoch_len ) );
 			Ok(())
@@ -1402,7 +2370,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_epochs_per_reset : u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorEpochsPerReset, validator_epochs_per_reset )?;
 			ValidatorEpochsPerReset::<T>::set( validator_epochs_per_reset );
 			Self::deposit_event( Event::ValidatorEpochsPerResetSet( validator_epochs_per_reset ) );
 			Ok(())
@@ -1413,18 +2382,20 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			incentive_pruning_denominator: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::IncentivePruningDenominator, incentive_pruning_denominator )?;
 			IncentivePruningDenominator::<T>::set( incentive_pruning_denominator );
 			Self::deposit_event( Event::IncentivePruningDenominatorSet( incentive_pruning_denominator ));
 			Ok(())
 		}
-		
+
 		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
 		pub fn sudo_set_stake_pruning_denominator( 
 			origin:OriginFor<T>, 
 			stake_pruning_denominator: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::StakePruningDenominator, stake_pruning_denominator )?;
 			StakePruningDenominator::<T>::set( stake_pruning_denominator );
 			Self::deposit_event( Event::StakePruningDenominatorSet( stake_pruning_denominator ));
 			Ok(())
@@ -1435,7 +2406,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			stake_pruning_min: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::StakePruningMin, stake_pruning_min )?;
 			StakePruningMin::<T>::set( stake_pruning_min );
 			Self::deposit_event( Event::StakePruningMinSet( stake_pruning_min ));
 			Ok(())
@@ -1446,7 +2418,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			immunity_period: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ImmunityPeriod, immunity_period )?;
 			ImmunityPeriod::<T>::set( immunity_period );
 			Self::deposit_event( Event::ImmunityPeriodSet( immunity_period ) );
 			Ok(())
@@ -1456,7 +2429,7 @@ pub mod pallet {
 		pub fn sudo_reset_bonds ( 
 			origin:OriginFor<T>
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
 			Self::reset_bonds();
 			Self::deposit_event( Event::ResetBonds() );
 			Ok(())
@@ -1467,8 +2440,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			scaling_law_power: u8 
 		) -> DispatchResult {
-			ensure_root( origin )?;
-			ensure!( scaling_law_power <= 100, Error::<T>::StorageValueOutOfRange  ); // The power must be between 0 and 100 => 0% and 100%
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ScalingLawPower, scaling_law_power as u64 )?;
 			ScalingLawPower::<T>::set( scaling_law_power );
 			Self::deposit_event( Event::ScalingLawPowerSet( scaling_law_power ));
 			Ok(())
@@ -1479,8 +2452,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			synergy_scaling_law_power: u8 
 		) -> DispatchResult {
-			ensure_root( origin )?;
-			ensure!( synergy_scaling_law_power <= 100, Error::<T>::StorageValueOutOfRange ); // The power must be between 0 and 100 => 0% and 100%
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::SynergyScalingLawPower, synergy_scaling_law_power as u64 )?;
 		    SynergyScalingLawPower::<T>::set( synergy_scaling_law_power );
 			Self::deposit_event( Event::SynergyScalingLawPowerSet( synergy_scaling_law_power ));
 			Ok(())
@@ -1491,8 +2464,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_exclude_quantile: u8 
 		) -> DispatchResult {
-			ensure_root( origin )?;
-			ensure!( validator_exclude_quantile <= 100, Error::<T>::StorageValueOutOfRange ); // The quantile must be between 0 and 100 => 0% and 100%
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorExcludeQuantile, validator_exclude_quantile as u64 )?;
 		    ValidatorExcludeQuantile::<T>::set( validator_exclude_quantile );
 			Self::deposit_event( Event::ValidatorExcludeQuantileSet( validator_exclude_quantile ));
 			Ok(())
@@ -1503,7 +2476,8 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_prune_len: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorPruneLen, validator_prune_len )?;
 		    ValidatorPruneLen::<T>::set( validator_prune_len );
 			Self::deposit_event( Event::ValidatorPruneLenSet( validator_prune_len ));
 			Ok(())
@@ -1514,11 +2488,544 @@ pub mod pallet {
 			origin:OriginFor<T>, 
 			validator_logits_divergence: u64 
 		) -> DispatchResult {
-			ensure_root( origin )?;
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ValidatorLogitsDivergence, validator_logits_divergence )?;
 		    ValidatorLogitsDivergence::<T>::set( validator_logits_divergence );
 			Self::deposit_event( Event::ValidatorLogitsDivergenceSet( validator_logits_divergence ));
 			Ok(())
 		}
+
+		// ---- Calls added after the initial release. New calls are appended at the end so that the
+		// indices of the existing calls, and the extrinsics encoded with them, never change.
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_max_allowed_validators ( 
+			origin:OriginFor<T>, 
+			max_allowed_validators: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MaxAllowedValidators, max_allowed_validators )?;
+			MaxAllowedValidators::<T>::set( max_allowed_validators );
+			Self::deposit_event( Event::MaxAllowedValidatorsSet( max_allowed_validators ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_consensus_mode ( 
+			origin:OriginFor<T>, 
+			consensus_mode: u8 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::ConsensusMode, consensus_mode as u64 )?;
+			ConsensusMode::<T>::set( consensus_mode );
+			Self::deposit_event( Event::ConsensusModeSet( consensus_mode ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_epoch_chunk_size ( 
+			origin:OriginFor<T>, 
+			epoch_chunk_size: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::EpochChunkSize, epoch_chunk_size )?;
+			EpochChunkSize::<T>::set( epoch_chunk_size );
+			Self::deposit_event( Event::EpochChunkSizeSet( epoch_chunk_size ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_epoch_history_length ( 
+			origin:OriginFor<T>, 
+			epoch_history_length: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::EpochHistoryLength, epoch_history_length )?;
+			Self::set_epoch_history_length( epoch_history_length );
+			Self::deposit_event( Event::EpochHistoryLengthSet( epoch_history_length ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_bonds_reset_chunk_size ( 
+			origin:OriginFor<T>, 
+			bonds_reset_chunk_size: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::BondsResetChunkSize, bonds_reset_chunk_size )?;
+			BondsResetChunkSize::<T>::set( bonds_reset_chunk_size );
+			Self::deposit_event( Event::BondsResetChunkSizeSet( bonds_reset_chunk_size ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_bonds_reset_retention ( 
+			origin:OriginFor<T>, 
+			bonds_reset_retention: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::BondsResetRetention, bonds_reset_retention )?;
+			BondsResetRetention::<T>::set( bonds_reset_retention );
+			Self::deposit_event( Event::BondsResetRetentionSet( bonds_reset_retention ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_inactivity_horizon ( 
+			origin:OriginFor<T>, 
+			inactivity_horizon: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::InactivityHorizon, inactivity_horizon )?;
+			InactivityHorizon::<T>::set( inactivity_horizon );
+			Self::deposit_event( Event::InactivityHorizonSet( inactivity_horizon ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_deregistration_batch_size ( 
+			origin:OriginFor<T>, 
+			deregistration_batch_size: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::DeregistrationBatchSize, deregistration_batch_size )?;
+			DeregistrationBatchSize::<T>::set( deregistration_batch_size );
+			Self::deposit_event( Event::DeregistrationBatchSizeSet( deregistration_batch_size ) );
+			Ok(())
+		}
+
+		/// ---- Sets where the emission of a hotkey is paid at each epoch. The call must be made
+		/// from the coldkey account attached to the neuron metadata.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey whose emission destination is changed.
+		///
+		/// 	* 'destination' (EmissionDestination):
+		/// 		- Stake, Coldkey or Split(percent paid to the coldkey).
+		///
+		/// # Event:
+		/// 	* 'EmissionDestinationSet':
+		/// 		- On successfully setting the destination.
+		///
+		/// # Raises:
+		/// 	* 'NotRegistered':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		/// 	* 'InvalidEmissionSplit':
+		/// 		- When the split percentage exceeds 100.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_emission_destination(
+			origin:OriginFor<T>, 
+			hotkey: T::AccountId, 
+			destination: EmissionDestination
+		) -> DispatchResult {
+			Self::do_set_emission_destination(origin, hotkey, destination)
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_self_ownership ( 
+			origin:OriginFor<T>, 
+			self_ownership: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::SelfOwnership, self_ownership )?;
+			SelfOwnership::<T>::set( self_ownership );
+			Self::deposit_event( Event::SelfOwnershipSet( self_ownership ) );
+			Ok(())
+		}
+
+		/// ---- Sets the minimum registration difficulty, which must not exceed the current or maximum difficulty.
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_minimum_difficulty ( 
+			origin:OriginFor<T>, 
+			minimum_difficulty: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MinimumDifficulty, minimum_difficulty )?;
+			ensure!( minimum_difficulty <= Self::get_difficulty_as_u64() && minimum_difficulty <= Self::get_maximum_difficulty(), Error::<T>::StorageValueOutOfRange );
+			MinimumDifficulty::<T>::set( minimum_difficulty );
+			Self::deposit_event( Event::MinimumDifficultySet( minimum_difficulty ) );
+			Ok(())
+		}
+
+		/// ---- Sets the maximum registration difficulty, which must not be below the current or minimum difficulty.
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_maximum_difficulty ( 
+			origin:OriginFor<T>, 
+			maximum_difficulty: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MaximumDifficulty, maximum_difficulty )?;
+			ensure!( maximum_difficulty >= Self::get_difficulty_as_u64() && maximum_difficulty >= Self::get_minimum_difficulty(), Error::<T>::StorageValueOutOfRange );
+			MaximumDifficulty::<T>::set( maximum_difficulty );
+			Self::deposit_event( Event::MaximumDifficultySet( maximum_difficulty ) );
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_block_emission ( 
+			origin:OriginFor<T>, 
+			block_emission: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::BlockEmission, block_emission )?;
+			BlockEmission::<T>::set( block_emission );
+			Self::deposit_event( Event::BlockEmissionSet( block_emission ) );
+			Ok(())
+		}
+
+		/// ---- Sets any number of hyperparameters in a single root call. The update is merged
+		/// into the current set and the merged set is validated as a whole, then only the fields
+		/// set in the update are written, so either every field is applied or none is.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- Must be the admin origin.
+		///
+		/// 	* 'update' (SubnetHyperparamsUpdate):
+		/// 		- The hyperparameters to change, None fields are left untouched.
+		///
+		/// # Event:
+		/// 	* 'HyperparametersSet':
+		/// 		- Carries the full set after the update.
+		///
+		/// # Raises:
+		/// 	* 'StorageValueOutOfRange':
+		/// 		- A field in the merged set is outside of its allowed range.
+		///
+		#[pallet::weight((Pallet::<T>::hyperparameters_update_weight( update ), DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_hyperparameters ( 
+			origin:OriginFor<T>, 
+			update: SubnetHyperparamsUpdate 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			let hyperparameters: SubnetHyperparams = Self::merge_hyperparameters( update.clone() );
+			Self::validate_hyperparameters( &hyperparameters )?;
+			Self::apply_hyperparameters_update( &update );
+			Self::deposit_event( Event::HyperparametersSet( hyperparameters ) );
+			Ok(())
+		}
+
+		/// ---- Schedules a single hyperparameter change to be applied at the start of a future block,
+		/// giving the network notice before the value moves.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- Must be the admin origin.
+		///
+		/// 	* 'param' (Hyperparameter):
+		/// 		- The hyperparameter to change.
+		///
+		/// 	* 'value' (u64):
+		/// 		- The new value, converted to the type of the hyperparameter.
+		///
+		/// 	* 'at_block' (u64):
+		/// 		- The block at which the change is applied.
+		///
+		/// # Event:
+		/// 	* 'HyperparameterChangeScheduled':
+		/// 		- On successfully scheduling the change.
+		///
+		/// # Raises:
+		/// 	* 'InvalidScheduleBlock':
+		/// 		- The block is not after the current block.
+		///
+		/// 	* 'StorageValueOutOfRange':
+		/// 		- The value is outside of the allowed range of the hyperparameter.
+		///
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn schedule_hyperparameter_change ( 
+			origin:OriginFor<T>, 
+			param: Hyperparameter, 
+			value: u64, 
+			at_block: u64 
+		) -> DispatchResult {
+			Self::do_schedule_hyperparameter_change( origin, param, value, at_block )
+		}
+
+		/// ---- Moves stake from one hotkey staking account to another. Both hotkeys must be
+		/// attached to the calling coldkey. The stake never leaves the staking accounts so the
+		/// total stake is unchanged.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, a coldkey signature associated with both hotkey accounts.
+		///
+		/// 	* 'from_hotkey' (T::AccountId):
+		/// 		- The hotkey account to withdraw stake from.
+		///
+		/// 	* 'to_hotkey' (T::AccountId):
+		/// 		- The hotkey account to add the stake to.
+		///
+		/// 	* 'amount' (u64):
+		/// 		- The ammount of stake to move.
+		///
+		/// # Event:
+		/// 	* 'StakeMoved':
+		/// 		- On the successful move of stake.
+		///
+		/// # Raises:
+		/// 	* 'NotRegistered':
+		/// 		- If either hotkey account is not active.
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with both hotkey accounts.
+		///
+		/// 	* 'MoveStakeToSameHotkey':
+		/// 		- When both hotkeys are the same account.
+		///
+		/// 	* 'NotEnoughStaketoWithdraw':
+		/// 		- When the amount exceeds the stake of the source hotkey.
+		///
+		/// 	* 'StakeChangeBelowMinimum', 'RemainingStakeBelowMinimum', 'StakeBelowMinimum':
+		/// 		- When the move breaks the minimum stake change, or leaves either hotkey
+		/// 		with a non-zero stake below the minimum stake.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(6, 2), DispatchClass::Normal, Pays::Yes))]
+		pub fn move_stake(
+			origin:OriginFor<T>, 
+			from_hotkey: T::AccountId, 
+			to_hotkey: T::AccountId, 
+			amount: u64
+		) -> DispatchResult {
+			Self::do_move_stake(origin, from_hotkey, to_hotkey, amount)
+		}
+
+		/// ---- Adds stake to several hotkey accounts of the calling coldkey in one call.
+		/// Every entry is checked before any balance is withdrawn, so either all entries are
+		/// staked or none is.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, a coldkey signature associated with every hotkey account.
+		///
+		/// 	* 'stakes' (Vec<(T::AccountId, u64)>):
+		/// 		- The hotkey accounts and the ammount to stake on each of them.
+		///
+		/// # Event:
+		/// 	* 'StakeAdded':
+		/// 		- Once for every entry.
+		///
+		/// # Raises:
+		/// 	* 'NotRegistered':
+		/// 		- If a hotkey account is not active.
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with a hotkey account.
+		///
+		/// 	* 'NotEnoughBalanceToStake':
+		/// 		- When the sum of the amounts exceeds the balance of the coldkey.
+		///
+		/// 	* 'StakeChangeBelowMinimum', 'StakeBelowMinimum':
+		/// 		- When an entry, staked after the previous ones, breaks the minimums as add_stake would.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2).saturating_mul(stakes.len() as Weight).saturating_add(T::DbWeight::get().reads_writes(1, 1)), DispatchClass::Normal, Pays::Yes))]
+		pub fn add_stake_multiple(
+			origin:OriginFor<T>, 
+			stakes: Vec<(T::AccountId, u64)>
+		) -> DispatchResult {
+			Self::do_add_stake_multiple(origin, stakes)
+		}
+
+		/// ---- Removes stake from several hotkey accounts of the calling coldkey in one call.
+		/// Every entry is checked before any stake is removed, so either all entries are
+		/// unstaked or none is.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, a coldkey signature associated with every hotkey account.
+		///
+		/// 	* 'stakes' (Vec<(T::AccountId, u64)>):
+		/// 		- The hotkey accounts and the ammount to unstake from each of them.
+		///
+		/// # Event:
+		/// 	* 'StakeRemoved':
+		/// 		- Once for every entry.
+		///
+		/// # Raises:
+		/// 	* 'NotRegistered':
+		/// 		- If a hotkey account is not active.
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with a hotkey account.
+		///
+		/// 	* 'NotEnoughStaketoWithdraw':
+		/// 		- When the amounts of a hotkey, summed over its entries, exceed its stake.
+		///
+		/// 	* 'StakeChangeBelowMinimum', 'RemainingStakeBelowMinimum':
+		/// 		- When an entry, unstaked after the previous ones, breaks the minimums as remove_stake would.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2).saturating_mul(stakes.len() as Weight).saturating_add(T::DbWeight::get().reads_writes(1, 1)), DispatchClass::Normal, Pays::Yes))]
+		pub fn remove_stake_multiple(
+			origin:OriginFor<T>, 
+			stakes: Vec<(T::AccountId, u64)>
+		) -> DispatchResult {
+			Self::do_remove_stake_multiple(origin, stakes)
+		}
+
+		/// ---- Announces the swap of every neuron owned by the calling coldkey, along with its stake,
+		/// to a new coldkey. The swap executes ColdkeySwapDelay blocks later unless the calling coldkey
+		/// cancels it first. This gives the owner of a compromised coldkey a way to move ownership to a
+		/// fresh key, and the delay gives the owner time to cancel a swap announced by someone else.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, the coldkey whose neurons are swapped.
+		///
+		/// 	* 'new_coldkey' (T::AccountId):
+		/// 		- The coldkey which owns the neurons once the swap executes.
+		///
+		/// # Event:
+		/// 	* 'ColdkeySwapAnnounced':
+		/// 		- On the successful announcement, with the block at which the swap executes.
+		///
+		/// 	* 'ColdkeySwapped':
+		/// 		- Once the delay has passed and the neurons have been moved to the new coldkey.
+		///
+		/// # Raises:
+		/// 	* 'ColdkeySwapToSameColdkey':
+		/// 		- When the new coldkey is the calling coldkey.
+		///
+		/// 	* 'ColdkeyOwnsNoNeurons':
+		/// 		- When the calling coldkey owns no neuron.
+		///
+		/// 	* 'ColdkeySwapAlreadyAnnounced':
+		/// 		- When the calling coldkey already has a pending swap.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2), DispatchClass::Normal, Pays::Yes))]
+		pub fn announce_coldkey_swap(
+			origin:OriginFor<T>, 
+			new_coldkey: T::AccountId
+		) -> DispatchResult {
+			Self::do_announce_coldkey_swap(origin, new_coldkey)
+		}
+
+		/// ---- Cancels the pending coldkey swap of the calling coldkey.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, the coldkey which announced the swap.
+		///
+		/// # Event:
+		/// 	* 'ColdkeySwapCancelled':
+		/// 		- On the successful cancellation.
+		///
+		/// # Raises:
+		/// 	* 'NoColdkeySwapAnnounced':
+		/// 		- When the calling coldkey has no pending swap.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Normal, Pays::Yes))]
+		pub fn cancel_coldkey_swap(
+			origin:OriginFor<T>
+		) -> DispatchResult {
+			Self::do_cancel_coldkey_swap(origin)
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_min_stake( 
+			origin:OriginFor<T>, 
+			min_stake: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MinStake, min_stake )?;
+		    MinStake::<T>::set( min_stake );
+			Self::deposit_event( Event::MinStakeSet( min_stake ));
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_min_stake_change( 
+			origin:OriginFor<T>, 
+			min_stake_change: u64 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::ensure_hyperparameter_in_range( Hyperparameter::MinStakeChange, min_stake_change )?;
+		    MinStakeChange::<T>::set( min_stake_change );
+			Self::deposit_event( Event::MinStakeChangeSet( min_stake_change ));
+			Ok(())
+		}
+
+		/// ---- Exempts a hotkey from the fees of its set_weights and serve_axon calls, or makes
+		/// it pay them again. The fee is still withheld when the call is submitted and is refunded
+		/// after dispatch, so an exempt hotkey must hold enough balance to cover it.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- Must be the admin origin.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey to exempt.
+		///
+		/// 	* 'exempt' (bool):
+		/// 		- Whether the fees of the hotkey are refunded.
+		///
+		/// # Event:
+		/// 	* 'FeeExemptionSet':
+		/// 		- On successfully setting the exemption.
+		///
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_fee_exempt( 
+			origin:OriginFor<T>, 
+			hotkey: T::AccountId, 
+			exempt: bool 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+			Self::set_fee_exempt( &hotkey, exempt );
+			Self::deposit_event( Event::FeeExemptionSet( hotkey, exempt ));
+			Ok(())
+		}
+
+		#[pallet::weight((0, DispatchClass::Operational, Pays::No))]
+		pub fn sudo_set_registration_fee_refund( 
+			origin:OriginFor<T>, 
+			refund: bool 
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin( origin )?;
+		    RegistrationFeeRefund::<T>::set( refund );
+			Self::deposit_event( Event::RegistrationFeeRefundSet( refund ));
+			Ok(())
+		}
+
+		/// ---- Sets whether the calling coldkey pays the fees of the set_weights and serve_axon calls
+		/// signed by its hotkey. Hotkeys are usually unfunded, the coldkey must opt in since anyone
+		/// can register a hotkey under any coldkey.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Config>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey whose fees are paid.
+		///
+		/// 	* 'pays' (bool):
+		/// 		- Whether the coldkey pays, otherwise the hotkey pays its own fees.
+		///
+		/// # Event:
+		/// 	* 'ColdkeyPaysFeesSet':
+		/// 		- On successfully setting the fee payer.
+		///
+		/// # Raises:
+		/// 	* 'NotRegistered':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 1), DispatchClass::Normal, Pays::Yes))]
+		pub fn set_coldkey_pays_fees(
+			origin:OriginFor<T>, 
+			hotkey: T::AccountId, 
+			pays: bool
+		) -> DispatchResult {
+			Self::do_set_coldkey_pays_fees(origin, hotkey, pays)
+		}
 	}
 
 	// ---- Subtensor helper functions.
@@ -1588,15 +3095,24 @@ pub mod pallet {
 		}
 		// -- Minimum difficulty
 		pub fn get_minimum_difficulty( ) -> u64 {
-			return T::MinimumDifficulty::get();
+			return MinimumDifficulty::<T>::get();
+		}
+		pub fn set_minimum_difficulty( minimum_difficulty: u64 ) {
+			MinimumDifficulty::<T>::put( minimum_difficulty );
 		}
 		// -- Maximum difficulty
 		pub fn get_maximum_difficulty( ) -> u64 {
-			return T::MaximumDifficulty::get();
+			return MaximumDifficulty::<T>::get();
+		}
+		pub fn set_maximum_difficulty( maximum_difficulty: u64 ) {
+			MaximumDifficulty::<T>::put( maximum_difficulty );
 		}
 		// -- Get Block emission.
 		pub fn get_block_emission( ) -> u64 {
-			return 1000000000;
+			return BlockEmission::<T>::get();
+		}
+		pub fn set_block_emission( block_emission: u64 ) {
+			BlockEmission::<T>::put( block_emission );
 		}
 		// -- Get step consensus temperature (rho)
 		pub fn get_rho( ) -> u64 {
@@ -1684,16 +3200,58 @@ pub mod pallet {
 			ValidatorLogitsDivergence::<T>::put( validator_logits_divergence );
 		}
 
-		// -- Get step consensus shift (1/kappa)
+		pub fn get_min_stake( ) -> u64 {
+			return MinStake::<T>::get();
+		}
+		pub fn set_min_stake( min_stake: u64 ) {
+			MinStake::<T>::put( min_stake );
+		}
+
+		pub fn get_min_stake_change( ) -> u64 {
+			return MinStakeChange::<T>::get();
+		}
+		pub fn set_min_stake_change( min_stake_change: u64 ) {
+			MinStakeChange::<T>::put( min_stake_change );
+		}
+
+		pub fn get_registration_fee_refund( ) -> bool {
+			return RegistrationFeeRefund::<T>::get();
+		}
+		pub fn set_registration_fee_refund( refund: bool ) {
+			RegistrationFeeRefund::<T>::put( refund );
+		}
+
+		pub fn get_fee_exempt( hotkey: &T::AccountId ) -> bool {
+			return FeeExemptHotkeys::<T>::get( hotkey );
+		}
+		pub fn set_fee_exempt( hotkey: &T::AccountId, exempt: bool ) {
+			if exempt {
+				FeeExemptHotkeys::<T>::insert( hotkey, true );
+			} else {
+				FeeExemptHotkeys::<T>::remove( hotkey );
+			}
+		}
+
+		// -- Get step consensus shift as I32F32 bits
 		pub fn get_kappa( ) -> u64 {
 			return Kappa::<T>::get();
 		}
 		pub fn set_kappa( kappa: u64 ) {
 			Kappa::<T>::put( kappa );
 		}
-		// -- Get self ownership proportion denominator
+		// -- Get step consensus mode (0: sigmoid trust, 1: stake-weighted median clipping)
+		pub fn get_consensus_mode( ) -> u8 {
+			return ConsensusMode::<T>::get();
+		}
+		pub fn set_consensus_mode( consensus_mode: u8 ) {
+			ConsensusMode::<T>::put( consensus_mode );
+		}
+		// -- Get self ownership proportion as I32F32 bits
 		pub fn get_self_ownership( ) -> u64 {
-			return T::SelfOwnership::get();
+			return SelfOwnership::<T>::get();
+		}
+		pub fn set_self_ownership( self_ownership: u64 ) {
+			SelfOwnership::<T>::put( self_ownership );
 		}
 		pub fn get_last_mechanism_step_block( ) -> u64 {
 			return LastMechansimStepBlock::<T>::get();
@@ -1704,6 +3262,56 @@ pub mod pallet {
 		pub fn set_max_allowed_uids( max_allowed_uids: u64 ) {
 			MaxAllowedUids::<T>::put( max_allowed_uids );
 		}
+		pub fn get_max_allowed_validators( ) -> u64 {
+			return MaxAllowedValidators::<T>::get();
+		}
+		pub fn set_max_allowed_validators( max_allowed_validators: u64 ) {
+			MaxAllowedValidators::<T>::put( max_allowed_validators );
+		}
+		pub fn get_epoch_chunk_size( ) -> u64 {
+			return EpochChunkSize::<T>::get();
+		}
+		pub fn set_epoch_chunk_size( epoch_chunk_size: u64 ) {
+			EpochChunkSize::<T>::put( epoch_chunk_size );
+		}
+		pub fn get_epoch_history_length( ) -> u64 {
+			return EpochHistoryLength::<T>::get();
+		}
+		pub fn set_epoch_history_length( epoch_history_length: u64 ) {
+			// Drop the records which fall out of a shorter history.
+			let epoch_counter: u64 = Self::get_epoch_counter();
+			for epoch in epoch_counter.saturating_sub( Self::get_epoch_history_length() )..epoch_counter.saturating_sub( epoch_history_length ) {
+				EpochHistory::<T>::remove( epoch );
+			}
+			EpochHistoryLength::<T>::put( epoch_history_length );
+		}
+		pub fn get_epoch_counter( ) -> u64 {
+			return EpochCounter::<T>::get();
+		}
+		pub fn get_bonds_reset_chunk_size( ) -> u64 {
+			return BondsResetChunkSize::<T>::get();
+		}
+		pub fn set_bonds_reset_chunk_size( bonds_reset_chunk_size: u64 ) {
+			BondsResetChunkSize::<T>::put( bonds_reset_chunk_size );
+		}
+		pub fn get_bonds_reset_retention( ) -> u64 {
+			return BondsResetRetention::<T>::get();
+		}
+		pub fn set_bonds_reset_retention( bonds_reset_retention: u64 ) {
+			BondsResetRetention::<T>::put( bonds_reset_retention );
+		}
+		pub fn get_inactivity_horizon( ) -> u64 {
+			return InactivityHorizon::<T>::get();
+		}
+		pub fn set_inactivity_horizon( inactivity_horizon: u64 ) {
+			InactivityHorizon::<T>::put( inactivity_horizon );
+		}
+		pub fn get_deregistration_batch_size( ) -> u64 {
+			return DeregistrationBatchSize::<T>::get();
+		}
+		pub fn set_deregistration_batch_size( deregistration_batch_size: u64 ) {
+			DeregistrationBatchSize::<T>::put( deregistration_batch_size );
+		}
 		pub fn get_min_allowed_weights( ) -> u64 {
 			return MinAllowedWeights::<T>::get();
 		}
@@ -1729,6 +3337,144 @@ pub mod pallet {
 			ImmunityPeriod::<T>::put( immunity_period );
 		}
 
+		// -- Hyperparameters as a single set.
+		pub fn get_hyperparameters( ) -> SubnetHyperparams {
+			SubnetHyperparams {
+				blocks_per_step: Self::get_blocks_per_step(),
+				bonds_moving_average: Self::get_bonds_moving_average(),
+				difficulty: Self::get_difficulty_as_u64(),
+				minimum_difficulty: Self::get_minimum_difficulty(),
+				maximum_difficulty: Self::get_maximum_difficulty(),
+				block_emission: Self::get_block_emission(),
+				adjustment_interval: Self::get_adjustment_interval(),
+				target_registrations_per_interval: Self::get_target_registrations_per_interval(),
+				activity_cutoff: Self::get_activity_cutoff(),
+				rho: Self::get_rho(),
+				kappa: Self::get_kappa(),
+				self_ownership: Self::get_self_ownership(),
+				consensus_mode: Self::get_consensus_mode(),
+				max_allowed_uids: Self::get_max_allowed_uids(),
+				max_allowed_validators: Self::get_max_allowed_validators(),
+				epoch_chunk_size: Self::get_epoch_chunk_size(),
+				epoch_history_length: Self::get_epoch_history_length(),
+				bonds_reset_chunk_size: Self::get_bonds_reset_chunk_size(),
+				bonds_reset_retention: Self::get_bonds_reset_retention(),
+				inactivity_horizon: Self::get_inactivity_horizon(),
+				deregistration_batch_size: Self::get_deregistration_batch_size(),
+				min_allowed_weights: Self::get_min_allowed_weights(),
+				max_allowed_max_min_ratio: Self::get_max_allowed_max_min_ratio(),
+				max_weight_limit: Self::get_max_weight_limit(),
+				validator_batch_size: Self::get_validator_batch_size(),
+				validator_sequence_length: Self::get_validator_sequence_length(),
+				validator_epoch_len: Self::get_validator_epoch_len(),
+				validator_epochs_per_reset: Self::get_validator_epochs_per_reset(),
+				incentive_pruning_denominator: Self::get_incentive_pruning_denominator(),
+				stake_pruning_denominator: Self::get_stake_pruning_denominator(),
+				stake_pruning_min: Self::get_stake_pruning_min(),
+				immunity_period: Self::get_immunity_period(),
+				scaling_law_power: Self::get_scaling_law_power(),
+				synergy_scaling_law_power: Self::get_synergy_scaling_law_power(),
+				validator_exclude_quantile: Self::get_validator_exclude_quantile(),
+				validator_prune_len: Self::get_validator_prune_len(),
+				validator_logits_divergence: Self::get_validator_logits_divergence(),
+				min_stake: Self::get_min_stake(),
+				min_stake_change: Self::get_min_stake_change(),
+			}
+		}
+		pub fn merge_hyperparameters( update: SubnetHyperparamsUpdate ) -> SubnetHyperparams {
+			let current: SubnetHyperparams = Self::get_hyperparameters();
+			SubnetHyperparams {
+				blocks_per_step: update.blocks_per_step.unwrap_or( current.blocks_per_step ),
+				bonds_moving_average: update.bonds_moving_average.unwrap_or( current.bonds_moving_average ),
+				difficulty: update.difficulty.unwrap_or( current.difficulty ),
+				minimum_difficulty: update.minimum_difficulty.unwrap_or( current.minimum_difficulty ),
+				maximum_difficulty: update.maximum_difficulty.unwrap_or( current.maximum_difficulty ),
+				block_emission: update.block_emission.unwrap_or( current.block_emission ),
+				adjustment_interval: update.adjustment_interval.unwrap_or( current.adjustment_interval ),
+				target_registrations_per_interval: update.target_registrations_per_interval.unwrap_or( current.target_registrations_per_interval ),
+				activity_cutoff: update.activity_cutoff.unwrap_or( current.activity_cutoff ),
+				rho: update.rho.unwrap_or( current.rho ),
+				kappa: update.kappa.unwrap_or( current.kappa ),
+				self_ownership: update.self_ownership.unwrap_or( current.self_ownership ),
+				consensus_mode: update.consensus_mode.unwrap_or( current.consensus_mode ),
+				max_allowed_uids: update.max_allowed_uids.unwrap_or( current.max_allowed_uids ),
+				max_allowed_validators: update.max_allowed_validators.unwrap_or( current.max_allowed_validators ),
+				epoch_chunk_size: update.epoch_chunk_size.unwrap_or( current.epoch_chunk_size ),
+				epoch_history_length: update.epoch_history_length.unwrap_or( current.epoch_history_length ),
+				bonds_reset_chunk_size: update.bonds_reset_chunk_size.unwrap_or( current.bonds_reset_chunk_size ),
+				bonds_reset_retention: update.bonds_reset_retention.unwrap_or( current.bonds_reset_retention ),
+				inactivity_horizon: update.inactivity_horizon.unwrap_or( current.inactivity_horizon ),
+				deregistration_batch_size: update.deregistration_batch_size.unwrap_or( current.deregistration_batch_size ),
+				min_allowed_weights: update.min_allowed_weights.unwrap_or( current.min_allowed_weights ),
+				max_allowed_max_min_ratio: update.max_allowed_max_min_ratio.unwrap_or( current.max_allowed_max_min_ratio ),
+				max_weight_limit: update.max_weight_limit.unwrap_or( current.max_weight_limit ),
+				validator_batch_size: update.validator_batch_size.unwrap_or( current.validator_batch_size ),
+				validator_sequence_length: update.validator_sequence_length.unwrap_or( current.validator_sequence_length ),
+				validator_epoch_len: update.validator_epoch_len.unwrap_or( current.validator_epoch_len ),
+				validator_epochs_per_reset: update.validator_epochs_per_reset.unwrap_or( current.validator_epochs_per_reset ),
+				incentive_pruning_denominator: update.incentive_pruning_denominator.unwrap_or( current.incentive_pruning_denominator ),
+				stake_pruning_denominator: update.stake_pruning_denominator.unwrap_or( current.stake_pruning_denominator ),
+				stake_pruning_min: update.stake_pruning_min.unwrap_or( current.stake_pruning_min ),
+				immunity_period: update.immunity_period.unwrap_or( current.immunity_period ),
+				scaling_law_power: update.scaling_law_power.unwrap_or( current.scaling_law_power ),
+				synergy_scaling_law_power: update.synergy_scaling_law_power.unwrap_or( current.synergy_scaling_law_power ),
+				validator_exclude_quantile: update.validator_exclude_quantile.unwrap_or( current.validator_exclude_quantile ),
+				validator_prune_len: update.validator_prune_len.unwrap_or( current.validator_prune_len ),
+				validator_logits_divergence: update.validator_logits_divergence.unwrap_or( current.validator_logits_divergence ),
+				min_stake: update.min_stake.unwrap_or( current.min_stake ),
+				min_stake_change: update.min_stake_change.unwrap_or( current.min_stake_change ),
+			}
+		}
+		pub fn validate_hyperparameters( hyperparameters: &SubnetHyperparams ) -> Result<(), Error<T>> {
+			for param in Self::get_hyperparameter_list() {
+				Self::ensure_hyperparameter_in_range( param, Self::get_hyperparameter_value( hyperparameters, param ) )?;
+			}
+			ensure!( hyperparameters.minimum_difficulty <= hyperparameters.difficulty && hyperparameters.difficulty <= hyperparameters.maximum_difficulty, Error::<T>::StorageValueOutOfRange );
+			Ok(())
+		}
+		/// Writes the hyperparameters set in the update, fields left as None are not touched.
+		pub fn apply_hyperparameters_update( update: &SubnetHyperparamsUpdate ) {
+			if let Some( blocks_per_step ) = update.blocks_per_step { Self::set_blocks_per_step( blocks_per_step ); }
+			if let Some( bonds_moving_average ) = update.bonds_moving_average { Self::set_bonds_moving_average( bonds_moving_average ); }
+			if let Some( difficulty ) = update.difficulty { Self::set_difficulty_from_u64( difficulty ); }
+			if let Some( minimum_difficulty ) = update.minimum_difficulty { Self::set_minimum_difficulty( minimum_difficulty ); }
+			if let Some( maximum_difficulty ) = update.maximum_difficulty { Self::set_maximum_difficulty( maximum_difficulty ); }
+			if let Some( block_emission ) = update.block_emission { Self::set_block_emission( block_emission ); }
+			if let Some( adjustment_interval ) = update.adjustment_interval { Self::set_adjustment_interval( adjustment_interval ); }
+			if let Some( target_registrations_per_interval ) = update.target_registrations_per_interval { Self::set_target_registrations_per_interval( target_registrations_per_interval ); }
+			if let Some( activity_cutoff ) = update.activity_cutoff { Self::set_activity_cutoff( activity_cutoff ); }
+			if let Some( rho ) = update.rho { Self::set_rho( rho ); }
+			if let Some( kappa ) = update.kappa { Self::set_kappa( kappa ); }
+			if let Some( self_ownership ) = update.self_ownership { Self::set_self_ownership( self_ownership ); }
+			if let Some( consensus_mode ) = update.consensus_mode { Self::set_consensus_mode( consensus_mode ); }
+			if let Some( max_allowed_uids ) = update.max_allowed_uids { Self::set_max_allowed_uids( max_allowed_uids ); }
+			if let Some( max_allowed_validators ) = update.max_allowed_validators { Self::set_max_allowed_validators( max_allowed_validators ); }
+			if let Some( epoch_chunk_size ) = update.epoch_chunk_size { Self::set_epoch_chunk_size( epoch_chunk_size ); }
+			if let Some( epoch_history_length ) = update.epoch_history_length { Self::set_epoch_history_length( epoch_history_length ); }
+			if let Some( bonds_reset_chunk_size ) = update.bonds_reset_chunk_size { Self::set_bonds_reset_chunk_size( bonds_reset_chunk_size ); }
+			if let Some( bonds_reset_retention ) = update.bonds_reset_retention { Self::set_bonds_reset_retention( bonds_reset_retention ); }
+			if let Some( inactivity_horizon ) = update.inactivity_horizon { Self::set_inactivity_horizon( inactivity_horizon ); }
+			if let Some( deregistration_batch_size ) = update.deregistration_batch_size { Self::set_deregistration_batch_size( deregistration_batch_size ); }
+			if let Some( min_allowed_weights ) = update.min_allowed_weights { Self::set_min_allowed_weights( min_allowed_weights ); }
+			if let Some( max_allowed_max_min_ratio ) = update.max_allowed_max_min_ratio { Self::set_max_allowed_max_min_ratio( max_allowed_max_min_ratio ); }
+			if let Some( max_weight_limit ) = update.max_weight_limit { Self::set_max_weight_limit( max_weight_limit ); }
+			if let Some( validator_batch_size ) = update.validator_batch_size { Self::set_validator_batch_size( validator_batch_size ); }
+			if let Some( validator_sequence_length ) = update.validator_sequence_length { Self::set_validator_sequence_length( validator_sequence_length ); }
+			if let Some( validator_epoch_len ) = update.validator_epoch_len { Self::set_validator_epoch_len( validator_epoch_len ); }
+			if let Some( validator_epochs_per_reset ) = update.validator_epochs_per_reset { Self::set_validator_epochs_per_reset( validator_epochs_per_reset ); }
+			if let Some( incentive_pruning_denominator ) = update.incentive_pruning_denominator { Self::set_incentive_pruning_denominator( incentive_pruning_denominator ); }
+			if let Some( stake_pruning_denominator ) = update.stake_pruning_denominator { Self::set_stake_pruning_denominator( stake_pruning_denominator ); }
+			if let Some( stake_pruning_min ) = update.stake_pruning_min { Self::set_stake_pruning_min( stake_pruning_min ); }
+			if let Some( immunity_period ) = update.immunity_period { Self::set_immunity_period( immunity_period ); }
+			if let Some( scaling_law_power ) = update.scaling_law_power { Self::set_scaling_law_power( scaling_law_power ); }
+			if let Some( synergy_scaling_law_power ) = update.synergy_scaling_law_power { Self::set_synergy_scaling_law_power( synergy_scaling_law_power ); }
+			if let Some( validator_exclude_quantile ) = update.validator_exclude_quantile { Self::set_validator_exclude_quantile( validator_exclude_quantile ); }
+			if let Some( validator_prune_len ) = update.validator_prune_len { Self::set_validator_prune_len( validator_prune_len ); }
+			if let Some( validator_logits_divergence ) = update.validator_logits_divergence { Self::set_validator_logits_divergence( validator_logits_divergence ); }
+			if let Some( min_stake ) = update.min_stake { Self::set_min_stake( min_stake ); }
+			if let Some( min_stake_change ) = update.min_stake_change { Self::set_min_stake_change( min_stake_change ); }
+		}
+
 		// Variable Parameters
 		pub fn get_registrations_this_interval( ) -> u64 {
 			RegistrationsThisInterval::<T>::get()
@@ -1808,6 +3554,60 @@ pub mod pallet {
 			}
 			return result
 		}
+		pub fn get_validator_permit( ) -> Vec<bool> {
+			let mut result: Vec<bool> = vec![ false; Self::get_neuron_count() as usize ];
+			for ( uid_i, _ ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
+				result[ uid_i as usize ] = Self::has_validator_permit( uid_i );
+			}
+			return result
+		}
+		pub fn get_vtrust( ) -> Vec<u64> {
+			let mut result: Vec<u64> = vec![ 0; Self::get_neuron_count() as usize ];
+			for ( uid_i, _ ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
+				result[ uid_i as usize ] = ValidatorTrust::<T>::get( uid_i );
+			}
+			return result
+		}
+		// --- Returns the neuron at the uid with its validator permit and vtrust, if the uid is registered.
+		pub fn get_neuron_info( uid: u32 ) -> Option<NeuronInfo<T::AccountId>> {
+			let neuron: NeuronMetadataOf<T> = Neurons::<T>::get( uid )?;
+			Some( NeuronInfo {
+				neuron: neuron,
+				validator_permit: Self::has_validator_permit( uid ),
+				vtrust: ValidatorTrust::<T>::get( uid ),
+			})
+		}
+		// --- Returns every registered neuron with its validator permit and vtrust, ordered by uid.
+		pub fn get_neurons_info( ) -> Vec<NeuronInfo<T::AccountId>> {
+			(0..Self::get_neuron_count()).filter_map( |uid_i| Self::get_neuron_info( uid_i ) ).collect()
+		}
+		pub fn get_epoch_history( ) -> Vec<EpochRecord> {
+			let epoch_counter: u64 = Self::get_epoch_counter();
+			let mut result: Vec<EpochRecord> = vec![];
+			for epoch in epoch_counter.saturating_sub( Self::get_epoch_history_length() )..epoch_counter {
+				if let Some( record ) = EpochHistory::<T>::get( epoch ) {
+					result.push( record );
+				}
+			}
+			return result
+		}
+		pub fn get_epoch_history_for_uid( uid: u32 ) -> Vec<NeuronEpochRecord> {
+			let mut result: Vec<NeuronEpochRecord> = vec![];
+			for record in Self::get_epoch_history().iter() {
+				let uid_i: usize = uid as usize;
+				if uid_i >= record.emission.len() { continue } // Not registered at this epoch.
+				result.push( NeuronEpochRecord {
+					block: record.block,
+					rank: record.ranks[ uid_i ],
+					trust: record.trust[ uid_i ],
+					consensus: record.consensus[ uid_i ],
+					incentive: record.incentive[ uid_i ],
+					dividends: record.dividends[ uid_i ],
+					emission: record.emission[ uid_i ],
+				});
+			}
+			return result
+		}
 		pub fn get_bonds_for_neuron( neuron: &NeuronMetadataOf<T> ) -> Vec<u64>  {
 			let mut bonds: Vec<u64> = vec![ 0; Self::get_neuron_count() as usize ];
 			for (uid_j, bonds_ij) in neuron.bonds.iter(){
@@ -1897,6 +3697,35 @@ pub mod pallet {
 			Hotkeys::<T>::insert(&hotkey_id, uid);
 		}
 
+		// --- Records the hotkey under the coldkey which owns its neuron.
+		pub fn add_owned_hotkey(coldkey: &T::AccountId, hotkey: &T::AccountId) {
+			OwnedHotkeys::<T>::mutate(coldkey, |hotkeys| hotkeys.push(hotkey.clone()));
+		}
+
+		// --- Drops the hotkey from the coldkey index, removing the entry once it is empty.
+		pub fn remove_owned_hotkey(coldkey: &T::AccountId, hotkey: &T::AccountId) {
+			let mut hotkeys: Vec<T::AccountId> = OwnedHotkeys::<T>::get(coldkey);
+			hotkeys.retain(|owned| owned != hotkey);
+			if hotkeys.is_empty() {
+				OwnedHotkeys::<T>::remove(coldkey);
+			} else {
+				OwnedHotkeys::<T>::insert(coldkey, hotkeys);
+			}
+		}
+
+		// --- Returns the neurons owned by the coldkey, in registration order.
+		pub fn get_neurons_by_coldkey(coldkey: &T::AccountId) -> Vec<NeuronMetadataOf<T>> {
+			OwnedHotkeys::<T>::get(coldkey).iter()
+				.filter(|hotkey| Self::is_hotkey_active(hotkey))
+				.map(|hotkey| Self::get_neuron_for_hotkey(hotkey))
+				.collect()
+		}
+
+		// --- Returns the stake summed over every neuron owned by the coldkey.
+		pub fn get_total_stake_for_coldkey(coldkey: &T::AccountId) -> u64 {
+			Self::get_neurons_by_coldkey(coldkey).iter().fold(0, |total, neuron| total.saturating_add(neuron.stake))
+		}
+
 		// --- Returns true if the account-id has an active
 		// account on chain.
 		pub fn is_hotkey_active(hotkey_id: &T::AccountId) -> bool {
@@ -1909,11 +3738,26 @@ pub mod pallet {
 			return !Self::is_hotkey_active(hotkey_id);
 		}
 
+		// --- Returns true if the uid was freed by deregistration and has not been registered since.
+		pub fn is_uid_free ( uid:u32 ) -> bool {
+			return FreeUids::<T>::contains_key( uid );
+		}
+
 		// --- Returns true if the uid is to be prunned at the next epoch.
 		pub fn will_be_prunned ( uid:u32 ) -> bool {
 			return NeuronsToPruneAtNextEpoch::<T>::contains_key( uid );
 		}
 
+		// --- Returns true if the uid is allowed to set weights on other neurons.
+		// While the graph holds no more than max_allowed_validators neurons every uid
+		// holds a permit, otherwise the permit computed at the last epoch is used.
+		pub fn has_validator_permit( uid: u32 ) -> bool {
+			if Self::get_neuron_count() as u64 <= Self::get_max_allowed_validators() {
+				return true;
+			}
+			return ValidatorPermit::<T>::get( uid );
+		}
+
 		// --- Returns true if the uid is active, i.e. there
 		// is a staking, last_update, and neuron account associated
 		// with this uid.
@@ -1954,9 +3798,15 @@ pub mod pallet {
 			uid
 		}
 
-		// --- Returns a vanilla transaction fee for transactions as rao.
-		pub fn calculate_transaction_fee(len: u64) -> u64 {
-			return len * 100;
+		// --- Returns whether the fee of a successful set_weights or serve_axon call is kept.
+		// Exempt hotkeys are refunded by the transaction payment pallet after dispatch.
+		pub fn get_fee_for_hotkey( hotkey: &T::AccountId ) -> Pays {
+			if Self::get_fee_exempt( hotkey ) { Pays::No } else { Pays::Yes }
+		}
+
+		// --- Returns whether registrations pay the transaction fee.
+		pub fn get_fee_for_registration() -> Pays {
+			if Self::get_registration_fee_refund() { Pays::No } else { Pays::Yes }
 		}
 
 		// --- Returns the transaction priority for setting weights.
@@ -1964,14 +3814,14 @@ pub mod pallet {
 			if Hotkeys::<T>::contains_key( hotkey ) {
 				let uid = Hotkeys::<T>::get( hotkey );
 				let neuron = Neurons::<T>::get( uid ).unwrap();
-				// Multiply here by 1_000_000 since len may divide all log values to zero.
+				// Multiply here by 1_000_000 since the length fee may divide all log values to zero.
 				// a peer with 1 tao will have priority 29 000 000 000 after 1 epoch.
 				// with 10 tao 33 000 000 000
 				// with 100 tao 36 000 000 000
 				// with 1000 tao 39 000 000 000
 				// with 10000 tao 43 000 000 000
-				// division by len will always return a non zero value with which to differentiate. 
-				return neuron.priority * 1_000_000 / len;
+				// Dividing by the length fee favours the peers with the most priority per rao paid.
+				return neuron.priority * 1_000_000 / Self::calculate_transaction_fee( len ).max( 1 );
 			} else{
 				return 0;
 			}
@@ -2023,6 +3873,22 @@ impl<T: Config + Send + Sync> SubtensorSignedExtension<T> where
 		// Return the non vanilla priority for a set weights call.
         return Pallet::<T>::get_priority_set_weights( who, len );
     }
+	pub fn get_invalid_transaction( error: Error<T> ) -> TransactionValidityError {
+		// Map the pallet errors raised by the pool checks to stable custom codes. 0 is any other error.
+		let code: u8 = match error {
+			Error::<T>::NotRegistered => 1,
+			Error::<T>::WeightVecNotEqualSize => 2,
+			Error::<T>::DuplicateUids => 3,
+			Error::<T>::InvalidUid => 4,
+			Error::<T>::AlreadyRegistered => 5,
+			Error::<T>::InvalidWorkBlock => 6,
+			Error::<T>::WorkRepeated => 7,
+			Error::<T>::InvalidDifficulty => 8,
+			Error::<T>::InvalidSeal => 9,
+			_ => 0,
+		};
+		return InvalidTransaction::Custom( code ).into();
+	}
 }
 
 impl<T: Config + Send + Sync> sp_std::fmt::Debug for SubtensorSignedExtension<T> {
@@ -2045,6 +3911,10 @@ impl<T: Config + Send + Sync> SignedExtension for SubtensorSignedExtension<T>
     type Pre = (CallType, u64, Self::AccountId);
     fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> { Ok(()) }
 
+    // The set_weights and register calls run their cheap checks here so that invalid payloads
+    // never enter the pool. The provides tags allow one pending set_weights per hotkey and one
+    // register per work item. Nothing is required: the nonce check already orders the
+    // transactions of a single account.
     fn validate(
         &self,
         who: &Self::AccountId,
@@ -2053,11 +3923,16 @@ impl<T: Config + Send + Sync> SignedExtension for SubtensorSignedExtension<T>
         len: usize,
     ) -> TransactionValidity {
         match call.is_sub_type() {
-            Some(Call::set_weights{..}) => {
+            Some(Call::set_weights{ dests, weights }) => {
+				if !Pallet::<T>::is_hotkey_active( who ) {
+					return Err( Self::get_invalid_transaction( Error::<T>::NotRegistered ) );
+				}
+				Pallet::<T>::check_weights_shape( dests, weights ).map_err( Self::get_invalid_transaction )?;
 				let priority: u64 = Self::get_priority_set_weights(who, len as u64);
                 Ok(ValidTransaction {
                     priority: priority,
                     longevity: 1,
+                    provides: vec![ ("set_weights", who).encode() ],
                     ..Default::default()
                 })
             }
@@ -2073,9 +3948,14 @@ impl<T: Config + Send + Sync> SignedExtension for SubtensorSignedExtension<T>
                     ..Default::default()
                 })
             }
-            Some(Call::register{..}) => {
+            Some(Call::register{ block_number, nonce, work, hotkey, .. }) => {
+				Pallet::<T>::check_registration_work( *block_number, *nonce, work, hotkey ).map_err( Self::get_invalid_transaction )?;
+				// The work is only valid while its block is within the last 3 blocks.
+				let longevity: u64 = block_number.saturating_add( 3 ).saturating_sub( Pallet::<T>::get_current_block_as_u64() );
                 Ok(ValidTransaction {
                     priority: Self::get_priority_vanilla(),
+                    longevity: longevity,
+                    provides: vec![ ("register", work).encode() ],
                     ..Default::default()
                 })
             }
@@ -2088,14 +3968,16 @@ impl<T: Config + Send + Sync> SignedExtension for SubtensorSignedExtension<T>
         }
     }
 
-    // NOTE: Add later when we put in a pre and post dispatch step.
+    // The pool checks run again before dispatch, registrations may not pay and invalid ones
+    // must never reach the call.
     fn pre_dispatch(
         self,
         who: &Self::AccountId,
         call: &Self::Call,
-        _info: &DispatchInfoOf<Self::Call>,
-        _len: usize,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
     ) -> Result<Self::Pre, TransactionValidityError> {
+        self.validate( who, call, info, len )?;
 
         match call.is_sub_type() {
             Some(Call::add_stake{..}) => {
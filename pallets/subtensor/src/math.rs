@@ -0,0 +1,218 @@
+//! Pure incentive mechanism arithmetic over plain vectors and matrices.
+//! Nothing here reads or writes storage, so the same functions run inside the runtime and in std tools.
+//! Vectors are indexed by uid, weights and sparse bonds are rows of (uid_j, value) pairs.
+use sp_std::vec::Vec;
+use sp_std::vec;
+use substrate_fixed::types::I65F63;
+use substrate_fixed::transcendental::exp;
+
+pub fn fixed( x: u64 ) -> I65F63 {
+    I65F63::from_num( x )
+}
+
+/// Converts I32F32 fixed-point bits, as stored for kappa and self ownership, to I65F63.
+pub fn from_fixed_bits( x: u64 ) -> I65F63 {
+    fixed( x ) / fixed( 1 << 32 )
+}
+
+pub fn sum( x: &Vec<I65F63> ) -> I65F63 {
+    x.iter().fold( I65F63::from_num( 0 ), |acc, x_i| acc + *x_i )
+}
+
+/// Returns x / sum( x ), which sums to one. A vector summing to zero is returned unchanged.
+pub fn normalize( x: &Vec<I65F63> ) -> Vec<I65F63> {
+    let x_sum: I65F63 = sum( x );
+    if x_sum == 0 { return x.clone() }
+    x.iter().map( |x_i| *x_i / x_sum ).collect()
+}
+
+/// Scales a vector on range( 0, 1 ) to u64, as stored on the neuron metadata.
+pub fn to_u64_normalized( x: &Vec<I65F63> ) -> Vec<u64> {
+    let u64_max: I65F63 = fixed( u64::MAX );
+    x.iter().map( |x_i| (*x_i * u64_max).to_num::<u64>() ).collect()
+}
+
+/// Returns the non-zero entries of a dense row as ( j, x_j ).
+pub fn sparse( x: &Vec<u64> ) -> Vec<(u32, u64)> {
+    x.iter().enumerate().filter( |( _, x_j )| **x_j > 0 ).map( |( j, x_j )| ( j as u32, *x_j ) ).collect()
+}
+
+/// Expands a sparse row to n entries, entries past n are dropped.
+pub fn dense( x: &Vec<(u32, u64)>, n: usize ) -> Vec<u64> {
+    let mut dense: Vec<u64> = vec![ 0; n ];
+    for ( j, x_j ) in x.iter() {
+        if ( *j as usize ) < n { dense[ *j as usize ] = *x_j; }
+    }
+    dense
+}
+
+/// Decays every bond by the moving average. Returns the decayed bonds and the bonds held in each peer.
+pub fn decay_bonds( bonds: &Vec<Vec<u64>>, bonds_moving_average: I65F63 ) -> ( Vec<Vec<u64>>, Vec<u64> ) {
+    let mut bond_totals: Vec<u64> = vec![ 0; bonds.len() ];
+    let decayed_bonds: Vec<Vec<u64>> = bonds.iter().map( |bonds_i| decay_bonds_row( bonds_i, bonds_moving_average, &mut bond_totals ) ).collect();
+    ( decayed_bonds, bond_totals )
+}
+
+/// Decays the bonds held by one peer and adds them to the bonds held in each peer.
+pub fn decay_bonds_row( bonds_i: &Vec<u64>, bonds_moving_average: I65F63, bond_totals: &mut Vec<u64> ) -> Vec<u64> {
+    bonds_i.iter().enumerate().map( |( j, bonds_ij )| {
+        let decayed_bond_ij: u64 = (bonds_moving_average * fixed( *bonds_ij )).to_num::<u64>();
+        bond_totals[ j ] += decayed_bond_ij;
+        decayed_bond_ij
+    }).collect()
+}
+
+/// Divides stake by the stake of validating peers. Returns the normalized stake
+/// and the normalized stake held by validating peers (one, unless nobody validates).
+pub fn normalize_active_stake( stake: &Vec<u64>, validating: &Vec<bool> ) -> ( Vec<I65F63>, I65F63 ) {
+    let mut total_active_stake: I65F63 = fixed( 0 );
+    for i in 0..stake.len() {
+        if validating[ i ] { total_active_stake += fixed( stake[ i ] ); }
+    }
+    let mut normalized_stake: Vec<I65F63> = stake.iter().map( |stake_i| fixed( *stake_i ) ).collect();
+    let mut total_normalized_active_stake: I65F63 = fixed( 0 );
+    if total_active_stake != 0 {
+        for i in 0..stake.len() {
+            normalized_stake[ i ] = normalized_stake[ i ] / total_active_stake;
+            if validating[ i ] { total_normalized_active_stake += normalized_stake[ i ]; }
+        }
+    }
+    ( normalized_stake, total_normalized_active_stake )
+}
+
+/// Stake-weighted median clipping: the consensus weight on j is the largest weight c such that validators
+/// holding at least consensus_stake set a weight >= c on j. Received weights are ( uid_i, w_ij ) in increasing uid_i,
+/// self-weights and weights set by non validating peers are ignored.
+pub fn weight_consensus_column( j: usize, received_weights: &Vec<(u32, u32)>, stake: &Vec<I65F63>, validating: &Vec<bool>, consensus_stake: I65F63 ) -> I65F63 {
+    let u32_max: I65F63 = fixed( u32::MAX as u64 );
+    let mut received: Vec<(I65F63, I65F63)> = received_weights.iter()
+        .filter( |( i, _ )| *i as usize != j && stake[ *i as usize ] != 0 && validating[ *i as usize ] )
+        .map( |( i, weight_ij )| ( fixed( *weight_ij as u64 ) / u32_max, stake[ *i as usize ] ) )
+        .collect();
+    received.sort_by( |a, b| b.0.cmp( &a.0 ) ); // Largest weights first.
+    let mut cumulative_stake: I65F63 = fixed( 0 );
+    for ( weight_ij, stake_i ) in received.iter() {
+        cumulative_stake += *stake_i;
+        if cumulative_stake >= consensus_stake {
+            return *weight_ij; // Range( 0, 1 )
+        }
+    }
+    fixed( 0 )
+}
+
+/// Validator trust: the fraction of peer i's non-self weight at or below weight_consensus.
+/// Peers without non-self weights have nothing above consensus and a vtrust of one.
+pub fn vtrust_row( i: usize, weights_i: &Vec<(u32, u32)>, weight_consensus: &Vec<I65F63> ) -> I65F63 {
+    let u32_max: I65F63 = fixed( u32::MAX as u64 );
+    let mut total_weight: I65F63 = fixed( 0 );
+    let mut kept_weight: I65F63 = fixed( 0 );
+    for ( j, weight_ij ) in weights_i.iter() {
+        if i == *j as usize { continue } // Skip self-weight.
+        let weight_ij: I65F63 = fixed( *weight_ij as u64 ) / u32_max; // Range( 0, 1 )
+        total_weight += weight_ij;
+        kept_weight += weight_ij.min( weight_consensus[ *j as usize ] );
+    }
+    if total_weight == 0 { fixed( 1 ) } else { kept_weight / total_weight } // Range( 0, 1 )
+}
+
+/// Accumulates the stake weighted ranks of peer i, clipped to weight_consensus if given, and purchases bonds with them.
+/// Adds to the unnormalized ranks, preranks (the unclipped ranks) and trust (the stake setting non-zero weight)
+/// and returns the bonds purchased. Only validating peers increase scores or bonds, and bond purchases are
+/// scaled by the validator's vtrust.
+pub fn ranks_and_bonds_row(
+    i: usize,
+    stake_i: I65F63,
+    validating_i: bool,
+    weights_i: &Vec<(u32, u32)>,
+    weight_consensus: Option<&Vec<I65F63>>,
+    vtrust_i: I65F63,
+    block_emission: I65F63,
+    bonds_moving_average: I65F63,
+    bonds_i: &mut Vec<u64>,
+    ranks: &mut Vec<I65F63>,
+    preranks: &mut Vec<I65F63>,
+    trust: &mut Vec<I65F63>,
+    bond_totals: &mut Vec<u64>,
+) -> u64 {
+    if stake_i == 0 || !validating_i { return 0 } // Bond increments converge to zero for these validators.
+    let one: I65F63 = fixed( 1 );
+    let u32_max: I65F63 = fixed( u32::MAX as u64 );
+    let mut bonds_purchased: u64 = 0;
+    for ( j, weight_ij ) in weights_i.iter() {
+        let j: usize = *j as usize;
+        if i == j { continue } // Skip self-weight.
+
+        // === Increment neuron scores ===
+        let weight_ij: I65F63 = fixed( *weight_ij as u64 ) / u32_max; // Range( 0, 1 )
+        let clipped_weight_ij: I65F63 = match weight_consensus { Some( weight_consensus ) => weight_ij.min( weight_consensus[ j ] ), None => weight_ij }; // Range( 0, 1 )
+        let rank_increment_ij: I65F63 = stake_i * clipped_weight_ij; // Range( 0, total_active_stake )
+        ranks[ j ] += rank_increment_ij;
+        preranks[ j ] += stake_i * weight_ij;
+        trust[ j ] += stake_i;
+
+        // === Compute bonding moving averages ===
+        let moving_bond_increment_ij: I65F63 = ( one - bonds_moving_average ) * ( rank_increment_ij * block_emission ) * vtrust_i;
+        bonds_i[ j ] = ( fixed( bonds_i[ j ] ) + moving_bond_increment_ij ).to_num::<u64>(); // Range( 0, block_emission )
+        bond_totals[ j ] += moving_bond_increment_ij.to_num::<u64>();
+        bonds_purchased += moving_bond_increment_ij.to_num::<u64>();
+    }
+    bonds_purchased
+}
+
+/// Sigmoid of trust, centered at kappa with temperature rho.
+pub fn sigmoid_consensus( trust: &Vec<I65F63>, rho: I65F63, kappa: I65F63 ) -> Vec<I65F63> {
+    let one: I65F63 = fixed( 1 );
+    trust.iter().map( |trust_i| {
+        let temperatured_trust: I65F63 = ( *trust_i - kappa ) * rho; // Range( -rho * kappa, rho ( 1 - kappa ) )
+        let exponentiated_trust: I65F63 = exp( -temperatured_trust ).expect( "temperatured_trust is on range( -rho * kappa, rho ( 1 - kappa ) )");
+        one / ( one + exponentiated_trust ) // Range( 0, 1 )
+    }).collect()
+}
+
+/// Peer i keeps self_ownership of its incentive (all of it when nobody holds bonds in it) and receives the remainder
+/// of the incentive of the peers it holds bonds in pro rata, scaled by its vtrust. Returns the unnormalized dividends
+/// of peer i and the sparse non-zero bonds it holds in bonded peers.
+pub fn dividends_row( i: usize, incentive: &Vec<I65F63>, bonds_i: &Vec<u64>, bond_totals: &Vec<u64>, self_ownership: I65F63, vtrust_i: I65F63 ) -> ( I65F63, Vec<(u32, u64)> ) {
+    let one: I65F63 = fixed( 1 );
+    let mut sparse_bonds_i: Vec<(u32, u64)> = vec![];
+
+    // Distribute dividends from self-ownership.
+    let mut dividends_i: I65F63 = incentive[ i ] * self_ownership;
+    if bond_totals[ i ] == 0 {
+        dividends_i += incentive[ i ] * ( one - self_ownership ); // Add the other half.
+    }
+    // Distribute dividends from other-ownership.
+    for ( j, bonds_ij ) in bonds_i.iter().enumerate() {
+        if bond_totals[ j ] == 0 { continue; } // No bond ownership in this neuron.
+        if *bonds_ij == 0 { continue; } // No need to distribute dividends for zero bonds.
+        let bond_fraction_ij: I65F63 = fixed( *bonds_ij ) / fixed( bond_totals[ j ] ); // Range( 0, 1 );
+        dividends_i += incentive[ j ] * ( ( one - self_ownership ) * bond_fraction_ij ) * vtrust_i; // Range( 0, 1 );
+        sparse_bonds_i.push( ( j as u32, *bonds_ij ) );
+    }
+    ( dividends_i, sparse_bonds_i )
+}
+
+/// Splits the block emission by dividends with largest remainder rounding: each share is rounded down, then the
+/// tokens lost to rounding go one each to the largest remainders, ties broken by lower uid. The shares sum exactly
+/// to block_emission unless every dividend is zero, in which case nothing is emitted.
+pub fn emission( dividends: &Vec<I65F63>, block_emission: u64 ) -> Vec<u64> {
+    let n: usize = dividends.len();
+    let bits: Vec<u128> = dividends.iter().map( |dividends_i| dividends_i.to_bits().max( 0 ) as u128 ).collect();
+    let total_bits: u128 = bits.iter().sum();
+    if total_bits == 0 { return vec![ 0; n ] }
+    let mut emission: Vec<u64> = vec![ 0; n ];
+    let mut remainders: Vec<(u128, usize)> = Vec::with_capacity( n );
+    let mut distributed: u64 = 0;
+    for i in 0..n {
+        // Dividends are normalized, so bits_i <= 2^63 and the product fits in u128.
+        let share_i: u128 = block_emission as u128 * bits[ i ];
+        emission[ i ] = ( share_i / total_bits ) as u64;
+        distributed += emission[ i ];
+        remainders.push( ( share_i % total_bits, i ) );
+    }
+    remainders.sort_by( |a, b| b.0.cmp( &a.0 ).then( a.1.cmp( &b.1 ) ) );
+    for ( _, i ) in remainders.iter().take( ( block_emission - distributed ) as usize ) {
+        emission[ *i ] += 1;
+    }
+    emission
+}
@@ -0,0 +1,310 @@
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+use sp_std::vec;
+use substrate_fixed::types::I65F63;
+use crate::math;
+use crate::math::fixed;
+
+const LOG_TARGET: &'static str = "runtime::subtensor::mechanism";
+
+/// Read-only view of the network handed to the incentive mechanism at each step.
+/// All vectors are indexed by uid and have length n.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct EpochSnapshot {
+	/// ---- Number of neurons in the graph.
+	pub n: u32,
+
+	/// ---- The uids present in the graph.
+	pub uids: Vec<u32>,
+
+	/// ---- Tokens released by this step.
+	pub block_emission: u64,
+
+	/// ---- Tokens staked by each peer.
+	pub stake: Vec<u64>,
+
+	/// ---- True if the peer has set weights within the activity cutoff.
+	pub active: Vec<bool>,
+
+	/// ---- True if the peer is allowed to increase ranks, trust and bonds.
+	pub validator_permit: Vec<bool>,
+
+	/// ---- Sparse weights set by each peer, w_ij normalized to u32::MAX.
+	pub weights: Vec<Vec<(u32, u32)>>,
+
+	/// ---- Dense bonds held by each peer, entries for pruned uids are already zeroed.
+	pub bonds: Vec<Vec<u64>>,
+
+	/// ---- Hyperparameters, as stored on chain. Kappa and self ownership are I32F32 bits.
+	pub rho: u64,
+	pub kappa: u64,
+	pub self_ownership: u64,
+	pub bonds_moving_average: u64,
+	pub consensus_mode: u8,
+}
+
+/// Scores returned by the incentive mechanism. Score vectors are normalized to u64::MAX
+/// as stored on the neuron metadata.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct EpochOutput {
+	pub ranks: Vec<u64>,
+	pub trust: Vec<u64>,
+	pub consensus: Vec<u64>,
+	pub incentive: Vec<u64>,
+	pub dividends: Vec<u64>,
+
+	/// ---- Share of each validator's weight which survived consensus, scales its bond purchases and dividends.
+	pub vtrust: Vec<u64>,
+
+	/// ---- Tokens emitted to each peer this step.
+	pub emission: Vec<u64>,
+
+	/// ---- New sparse bonds held by each peer.
+	pub bonds: Vec<Vec<(u32, u64)>>,
+
+	/// ---- Total bonds purchased this step.
+	pub bonds_purchased: u64,
+}
+
+/// Computes ranks, trust, consensus, incentive, dividends and new bonds from an epoch snapshot.
+/// Implementations must be pure: all state is read from the snapshot and the pallet applies the output.
+///
+/// The mechanism runs in stages so the pallet can spread an epoch over several blocks, keeping the
+/// intermediate Scores in storage between blocks. Each column and row stage is called once per uid in
+/// increasing uid order, columns before rows.
+pub trait IncentiveMechanism {
+	/// ---- Intermediate scores carried between the stages.
+	type Scores: Encode + Decode + Clone + PartialEq + TypeInfo + 'static;
+
+	/// Starts the epoch from the network-wide part of the snapshot: n, block emission, stake, activity,
+	/// validator permits and hyperparameters. Weights and bonds are passed to the later stages and may be empty.
+	fn begin( snapshot: &EpochSnapshot ) -> Self::Scores;
+
+	/// Reads the weights ( uid_i, w_ij ) received by uid_j, in increasing uid_i.
+	fn score_column( scores: &mut Self::Scores, uid_j: u32, received_weights: &Vec<(u32, u32)> );
+
+	/// Reads the weights set by uid_i and updates the sparse bonds it holds.
+	fn score_row( scores: &mut Self::Scores, uid_i: u32, weights_i: &Vec<(u32, u32)>, bonds_i: &mut Vec<(u32, u64)> );
+
+	/// Called once every row has been scored.
+	fn end_scoring( scores: &mut Self::Scores );
+
+	/// Distributes dividends to uid_i and sets the new sparse bonds it holds.
+	fn distribute_row( scores: &mut Self::Scores, uid_i: u32, bonds_i: &mut Vec<(u32, u64)> );
+
+	/// Returns the output of the epoch. Bonds are left empty, the pallet keeps the rows returned by distribute_row.
+	fn finish( scores: Self::Scores ) -> EpochOutput;
+
+	/// Runs every stage over the snapshot within a single call.
+	fn run( snapshot: &EpochSnapshot ) -> EpochOutput {
+		let n: usize = snapshot.n as usize;
+		let mut scores: Self::Scores = Self::begin( snapshot );
+
+		let mut received_weights: Vec<Vec<(u32, u32)>> = vec![ vec![]; n ];
+		for ( uid_i, weights_i ) in snapshot.weights.iter().enumerate() {
+			for ( uid_j, weight_ij ) in weights_i.iter() {
+				received_weights[ *uid_j as usize ].push( ( uid_i as u32, *weight_ij ) );
+			}
+		}
+		for ( uid_j, received_weights_j ) in received_weights.iter().enumerate() {
+			Self::score_column( &mut scores, uid_j as u32, received_weights_j );
+		}
+
+		let mut bonds: Vec<Vec<(u32, u64)>> = snapshot.bonds.iter().map( |bonds_i| math::sparse( bonds_i ) ).collect();
+		for uid_i in 0..n {
+			Self::score_row( &mut scores, uid_i as u32, &snapshot.weights[ uid_i ], &mut bonds[ uid_i ] );
+		}
+		Self::end_scoring( &mut scores );
+		for uid_i in 0..n {
+			Self::distribute_row( &mut scores, uid_i as u32, &mut bonds[ uid_i ] );
+		}
+
+		let mut output: EpochOutput = Self::finish( scores );
+		output.bonds = bonds;
+		output
+	}
+}
+
+/// Intermediate scores of the default mechanism, all vectors are indexed by uid.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DefaultScores {
+	pub n: u32,
+	pub block_emission: u64,
+	pub rho: I65F63,
+	pub kappa: I65F63,
+	pub self_ownership: I65F63,
+	pub bonds_moving_average: I65F63,
+	pub consensus_mode: u8, // 0: sigmoid trust, 1: stake-weighted median clipping.
+
+	/// ---- Active peers holding a validator permit.
+	pub validating: Vec<bool>,
+
+	/// ---- Stake normalized by the stake of validating peers.
+	pub stake: Vec<I65F63>,
+	pub total_normalized_active_stake: I65F63,
+
+	/// ---- Stake-weighted median weight on each peer, vtrust is measured against it in every mode
+	/// and weights above it are clipped in mode 1.
+	pub weight_consensus: Vec<I65F63>,
+
+	pub vtrust: Vec<I65F63>,
+	pub ranks: Vec<I65F63>,
+	pub preranks: Vec<I65F63>,
+	pub trust: Vec<I65F63>,
+
+	/// ---- Bonds held in each peer, after decay and purchases.
+	pub bond_totals: Vec<u64>,
+	pub bonds_purchased: u64,
+
+	pub consensus: Vec<I65F63>,
+	pub incentive: Vec<I65F63>,
+	pub dividends: Vec<I65F63>,
+}
+
+/// The default subtensor mechanism: stake weighted ranks, sigmoid trust consensus
+/// (or stake-weighted median clipping) and bond based dividends.
+pub struct DefaultIncentiveMechanism;
+
+impl IncentiveMechanism for DefaultIncentiveMechanism {
+
+    type Scores = DefaultScores;
+
+    fn begin( snapshot: &EpochSnapshot ) -> DefaultScores {
+
+        // Number of peers.
+        let n: usize = snapshot.n as usize;
+        let validating: Vec<bool> = (0..n).map( |i| snapshot.active[ i ] && snapshot.validator_permit[ i ] ).collect();
+
+        // Normalize stake based on activity.
+        let ( stake, total_normalized_active_stake ) = math::normalize_active_stake( &snapshot.stake, &validating );
+		log::trace!(
+			target: LOG_TARGET,
+			"stake: {:?}",
+			stake
+		);
+
+        DefaultScores {
+            n: snapshot.n,
+            block_emission: snapshot.block_emission,
+            rho: fixed( snapshot.rho ),
+            kappa: math::from_fixed_bits( snapshot.kappa ),
+            self_ownership: math::from_fixed_bits( snapshot.self_ownership ),
+            bonds_moving_average: fixed( snapshot.bonds_moving_average ) / fixed( 1_000_000 ),
+            consensus_mode: snapshot.consensus_mode,
+            validating: validating,
+            stake: stake,
+            total_normalized_active_stake: total_normalized_active_stake,
+            weight_consensus: vec![ fixed( 0 ); n ],
+            vtrust: vec![ fixed( 0 ); n ],
+            ranks: vec![ fixed( 0 ); n ],
+            preranks: vec![ fixed( 0 ); n ],
+            trust: vec![ fixed( 0 ); n ],
+            bond_totals: vec![ 0; n ],
+            bonds_purchased: 0,
+            consensus: vec![ fixed( 0 ); n ],
+            incentive: vec![ fixed( 0 ); n ],
+            dividends: vec![ fixed( 0 ); n ],
+        }
+    }
+
+    fn score_column( scores: &mut DefaultScores, uid_j: u32, received_weights: &Vec<(u32, u32)> ) {
+        // Compute weight consensus, vtrust is measured against it in every mode.
+        let consensus_stake: I65F63 = scores.kappa * scores.total_normalized_active_stake;
+        scores.weight_consensus[ uid_j as usize ] = math::weight_consensus_column( uid_j as usize, received_weights, &scores.stake, &scores.validating, consensus_stake );
+    }
+
+    fn score_row( scores: &mut DefaultScores, uid_i: u32, weights_i: &Vec<(u32, u32)>, bonds_i: &mut Vec<(u32, u64)> ) {
+        let i: usize = uid_i as usize;
+
+        // Compute validator trust, the share of the validator's weight within the weight consensus.
+        scores.vtrust[ i ] = math::vtrust_row( i, weights_i, &scores.weight_consensus );
+
+        // Decay bonds, then compute ranks, trust and bond purchases. Ranks are only clipped with median clipping.
+        let clipping: Option<&Vec<I65F63>> = if scores.consensus_mode == 1 { Some( &scores.weight_consensus ) } else { None };
+        let mut decayed_bonds_i: Vec<u64> = math::decay_bonds_row( &math::dense( bonds_i, scores.n as usize ), scores.bonds_moving_average, &mut scores.bond_totals );
+        scores.bonds_purchased += math::ranks_and_bonds_row(
+            i,
+            scores.stake[ i ],
+            scores.validating[ i ],
+            weights_i,
+            clipping,
+            scores.vtrust[ i ],
+            fixed( scores.block_emission ),
+            scores.bonds_moving_average,
+            &mut decayed_bonds_i,
+            &mut scores.ranks,
+            &mut scores.preranks,
+            &mut scores.trust,
+            &mut scores.bond_totals,
+        );
+        *bonds_i = math::sparse( &decayed_bonds_i );
+    }
+
+    fn end_scoring( scores: &mut DefaultScores ) {
+        let n: usize = scores.n as usize;
+        let zero: I65F63 = fixed( 0 );
+
+        // Normalize ranks and trust.
+        let total_ranks: I65F63 = math::sum( &scores.ranks );
+        let total_trust: I65F63 = math::sum( &scores.trust );
+        if total_trust > 0 && total_ranks > 0 {
+            for i in 0..n {
+                if scores.consensus_mode == 1 {
+                    // Trust is the fraction of received weight which survived clipping.
+                    scores.trust[ i ] = if scores.preranks[ i ] > 0 { scores.ranks[ i ] / scores.preranks[ i ] } else { zero }; // Range( 0, 1 )
+                } else {
+                    scores.trust[ i ] = scores.trust[ i ] / scores.total_normalized_active_stake; // Range( 0, 1 )
+                }
+            }
+            scores.ranks = math::normalize( &scores.ranks ); // Vector will sum to one.
+        }
+		 log::trace!(target: LOG_TARGET, "ranks: {:?}", scores.ranks);
+		 log::trace!(target: LOG_TARGET, "trust: {:?}", scores.trust);
+		 log::trace!(target: LOG_TARGET, "bonds: {:?}, {:?}", scores.bond_totals, scores.bonds_purchased);
+
+        // Compute consensus, incentive.
+        if total_ranks != 0 && total_trust != 0 {
+            if scores.consensus_mode == 1 {
+                // Ranks are already clipped to consensus, incentive follows ranks directly.
+                scores.consensus = scores.weight_consensus.clone();
+                scores.incentive = scores.ranks.clone();
+            } else {
+                scores.consensus = math::sigmoid_consensus( &scores.trust, scores.rho, scores.kappa );
+                scores.incentive = scores.ranks.iter().zip( scores.consensus.iter() ).map( |( ranks_i, consensus_i )| *ranks_i * *consensus_i ).collect();
+            }
+            scores.incentive = math::normalize( &scores.incentive ); // Vector will sum to one.
+        }
+        log::trace!(target: LOG_TARGET, "incentive: {:?}, consensus: {:?}", scores.incentive, scores.consensus);
+    }
+
+    fn distribute_row( scores: &mut DefaultScores, uid_i: u32, bonds_i: &mut Vec<(u32, u64)> ) {
+        let i: usize = uid_i as usize;
+        let ( dividends_i, sparse_bonds_i ) = math::dividends_row( i, &scores.incentive, &math::dense( bonds_i, scores.n as usize ), &scores.bond_totals, scores.self_ownership, scores.vtrust[ i ] );
+        scores.dividends[ i ] = dividends_i;
+        *bonds_i = sparse_bonds_i;
+    }
+
+    fn finish( scores: DefaultScores ) -> EpochOutput {
+
+        // Compute dividends and emission.
+        let dividends: Vec<I65F63> = math::normalize( &scores.dividends ); // Vector will sum to one.
+        let emission: Vec<u64> = math::emission( &dividends, scores.block_emission );
+		 log::trace!(target: LOG_TARGET, "dividends: {:?}, emission: {:?}", dividends, emission);
+
+        EpochOutput {
+            ranks: math::to_u64_normalized( &scores.ranks ),
+            trust: math::to_u64_normalized( &scores.trust ),
+            consensus: math::to_u64_normalized( &scores.consensus ),
+            incentive: math::to_u64_normalized( &scores.incentive ),
+            dividends: math::to_u64_normalized( &dividends ),
+            vtrust: math::to_u64_normalized( &scores.vtrust ),
+            emission: emission,
+            bonds: vec![],
+            bonds_purchased: scores.bonds_purchased,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+use frame_support::IterableStorageMap;
+use frame_support::traits::{Get, GetStorageVersion, StorageVersion};
+use frame_support::weights::Weight;
+use sp_std::collections::btree_map::BTreeMap;
+
+const LOG_TARGET: &'static str = "runtime::subtensor::migration";
+
+/// The kappa denominator chains used before v1 when the key was never written.
+const PRE_V1_DEFAULT_KAPPA_DENOMINATOR: u64 = 2;
+
+/// Kappa used to be stored as the denominator of 1/kappa. From v1 it is stored as I32F32 bits
+/// like self ownership, which moved from a Config constant into storage with its initial value.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+    let onchain_version: StorageVersion = Pallet::<T>::on_chain_storage_version();
+    if onchain_version >= 1 {
+        return T::DbWeight::get().reads( 1 );
+    }
+    // An unset key held the pre v1 default, which get() would now report as the new I32F32 default.
+    let kappa_denominator: u64 = Kappa::<T>::try_get().unwrap_or( PRE_V1_DEFAULT_KAPPA_DENOMINATOR );
+    let kappa: u64 = if kappa_denominator == 0 { T::InitialKappa::get() } else { FIXED_ONE / kappa_denominator };
+    Kappa::<T>::put( kappa );
+    StorageVersion::new( 1 ).put::<Pallet<T>>();
+    log::info!(
+        target: LOG_TARGET,
+        "migrated kappa from 1/{:?} to {:?} I32F32 bits",
+        kappa_denominator,
+        kappa
+    );
+    T::DbWeight::get().reads_writes( 2, 2 )
+}
+
+/// Writes the registration difficulty bounds and the block emission, previously Config constants and a literal,
+/// into storage so that later changes to the Config defaults do not move live values. The difficulty is
+/// clamped into the new bounds.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+    let onchain_version: StorageVersion = Pallet::<T>::on_chain_storage_version();
+    if onchain_version >= 2 {
+        return T::DbWeight::get().reads( 1 );
+    }
+    let minimum_difficulty: u64 = T::InitialMinimumDifficulty::get();
+    let maximum_difficulty: u64 = T::InitialMaximumDifficulty::get();
+    MinimumDifficulty::<T>::put( minimum_difficulty );
+    MaximumDifficulty::<T>::put( maximum_difficulty );
+    BlockEmission::<T>::put( T::InitialBlockEmission::get() );
+    let difficulty: u64 = Difficulty::<T>::get().max( minimum_difficulty ).min( maximum_difficulty );
+    Difficulty::<T>::put( difficulty );
+    StorageVersion::new( 2 ).put::<Pallet<T>>();
+    log::info!(
+        target: LOG_TARGET,
+        "migrated difficulty bounds [{:?}, {:?}], difficulty {:?} and block emission to storage",
+        minimum_difficulty,
+        maximum_difficulty,
+        difficulty
+    );
+    T::DbWeight::get().reads_writes( 2, 5 )
+}
+
+/// Builds the OwnedHotkeys index from the neurons registered before it existed.
+///
+/// This runs in the single upgrade block. The neurons are read once and grouped in memory, then each
+/// coldkey is written once, so the work is one read per neuron and at most one write per neuron, with
+/// the neuron count bounded by MaxAllowedUids (2000 on the live runtime). With the RocksDb weights
+/// (25us per read, 100us per write) even 4096 neurons cost about half a second, within the two second
+/// block the upgrade may fill.
+/// Building the index over several blocks would leave coldkey swaps and ownership queries reading
+/// an incomplete index in the meantime.
+pub fn migrate_to_v3<T: Config>() -> Weight {
+    let onchain_version: StorageVersion = Pallet::<T>::on_chain_storage_version();
+    if onchain_version >= 3 {
+        return T::DbWeight::get().reads( 1 );
+    }
+    let mut neuron_count: u64 = 0;
+    let mut owned_hotkeys: BTreeMap<T::AccountId, Vec<T::AccountId>> = BTreeMap::new();
+    for ( _, neuron ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
+        owned_hotkeys.entry( neuron.coldkey ).or_insert_with( Vec::new ).push( neuron.hotkey );
+        neuron_count += 1;
+    }
+    let coldkey_count: u64 = owned_hotkeys.len() as u64;
+    for ( coldkey, hotkeys ) in owned_hotkeys {
+        OwnedHotkeys::<T>::insert( coldkey, hotkeys );
+    }
+    StorageVersion::new( 3 ).put::<Pallet<T>>();
+    log::info!(
+        target: LOG_TARGET,
+        "indexed the hotkeys of {:?} neurons under {:?} coldkeys",
+        neuron_count,
+        coldkey_count
+    );
+    T::DbWeight::get().reads_writes( neuron_count + 1, coldkey_count + 1 )
+}
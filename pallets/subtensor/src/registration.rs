@@ -6,9 +6,19 @@ use sp_core::{H256, U256};
 use sp_io::hashing::sha2_256;
 use sp_io::hashing::keccak_256;
 use frame_system::{ensure_signed};
+use frame_support::weights::Weight;
+use frame_support::traits::Get;
 
 const LOG_TARGET: &'static str = "runtime::subtensor::registration";
 
+/// The reads done by deregister_neuron: the coldkey account, TotalStake, OwnedHotkeys and FreeUidQueue.
+const DEREGISTER_NEURON_READS: u64 = 4;
+
+/// The writes done by deregister_neuron: the coldkey account, TotalStake, Hotkeys, EmissionDestinations,
+/// FeePayingColdkeys, OwnedHotkeys, Neurons, BlockAtRegistration, ValidatorPermit, ValidatorTrust,
+/// NeuronsToPruneAtNextEpoch, FreeUids, the whole FreeUidQueue vector and the event.
+const DEREGISTER_NEURON_WRITES: u64 = 14;
+
 impl<T: Config> Pallet<T> {
 
     pub fn do_registration ( 
@@ -26,27 +36,10 @@ impl<T: Config> Pallet<T> {
         // --- Check that registrations per block and hotkey.
         let registrations_this_block: u64 = Self::get_registrations_this_block();
         ensure! ( registrations_this_block < Self::get_max_registratations_per_block(), Error::<T>::ToManyRegistrationsThisBlock ); // Number of registrations this block exceeded.
-        ensure!( !Hotkeys::<T>::contains_key(&hotkey), Error::<T>::AlreadyRegistered );  // Hotkey has already registered.
 
-        // --- Check block number validity.
+        // --- Check the hotkey, the work block, repeat work, difficulty and seal.
+        Self::check_registration_work( block_number, nonce, &work, &hotkey )?;
         let current_block_number: u64 = Self::get_current_block_as_u64_here();
-        ensure! ( block_number <= current_block_number, Error::<T>::InvalidWorkBlock ); // Can't work on future block.
-        ensure! ( current_block_number - block_number < 3, Error::<T>::InvalidWorkBlock ); // Work must have been done within 3 blocks (stops long range attacks).
-
-        // --- Check for repeat work,
-        ensure!( !UsedWork::<T>::contains_key( &work.clone() ), Error::<T>::WorkRepeated );  // Work has not been used before.
-
-        // --- Check difficulty.
-        let difficulty: U256 = Self::get_difficulty();
-        let work_hash: H256 = Self::vec_to_hash( work.clone() );
-        ensure! ( Self::hash_meets_difficulty( &work_hash, difficulty ), Error::<T>::InvalidDifficulty ); // Check that the work meets difficulty.
-
-        // --- Check work.
-        let seal: H256 = Self::create_seal_hash( block_number, nonce );
-        ensure! ( seal == work_hash, Error::<T>::InvalidSeal ); // Check that this work matches hash and nonce.
-        
-        // Check that the hotkey has not already been registered.
-        ensure!( !Hotkeys::<T>::contains_key(&hotkey), Error::<T>::AlreadyRegistered );
         
         // Above this line all relevant checks that the registration is legitimate have been met. 
         // --- registration does not exceed limit.
@@ -60,7 +53,10 @@ impl<T: Config> Pallet<T> {
         let neuron_count: u64 = Self::get_neuron_count() as u64; // Current number of uids.
         let current_block: u64 = Self::get_current_block_as_u64();
         let immunity_period: u64 = Self::get_immunity_period(); // Num blocks uid cannot be pruned since registration.
-        if neuron_count < max_allowed_uids {
+        if let Some( free_uid ) = Self::take_free_uid() {
+            // --- A uid freed by deregistration is reused before the graph grows or a peer is pruned.
+            uid_to_set_in_metagraph = free_uid;
+        } else if neuron_count < max_allowed_uids {
             // --- The metagraph is not full and we simply increment the uid.
             uid_to_set_in_metagraph = Self::get_next_uid();
         } else {
@@ -113,6 +109,9 @@ impl<T: Config> Pallet<T> {
             // NOTE(const): In further iterations it will be beneficial to build bonds as a double
             // iterable set so that deletions become easier. 
             NeuronsToPruneAtNextEpoch::<T>::insert( uid_to_set_in_metagraph, uid_to_set_in_metagraph ); // Subtrate does not contain a set storage item.
+            ValidatorPermit::<T>::remove( uid_to_set_in_metagraph ); // The new neuron must earn its own permit at the next epoch.
+            ValidatorTrust::<T>::remove( uid_to_set_in_metagraph );
+            Self::deposit_event( Event::NeuronPruned( uid_to_set_in_metagraph, hotkey_to_prune.clone() ) );
             // Finally, we need to unstake all the funds that this peer had staked. 
             // These funds are deposited back into the coldkey account so that no funds are destroyed. 
             let stake_to_be_added_on_coldkey = Self::u64_to_balance( neuron_to_prune.stake );
@@ -122,6 +121,9 @@ impl<T: Config> Pallet<T> {
             // Remove hotkey from hotkeys set, 
             // and to clean up and prune whatever extra hotkeys there are on top of the existing max_allowed_uids
             if Hotkeys::<T>::contains_key(&hotkey_to_prune) {
+                EmissionDestinations::<T>::remove( &hotkey_to_prune );
+                FeePayingColdkeys::<T>::remove( &hotkey_to_prune );
+                Self::remove_owned_hotkey( &neuron_to_prune.coldkey, &hotkey_to_prune );
                 Hotkeys::<T>::remove( hotkey_to_prune );
             }
         }
@@ -158,12 +160,127 @@ impl<T: Config> Pallet<T> {
         BlockAtRegistration::<T>::insert( uid_to_set_in_metagraph, current_block ); // Set immunity momment.
         Neurons::<T>::insert( uid_to_set_in_metagraph, neuron ); // Insert neuron info under uid.
         Hotkeys::<T>::insert( &hotkey, uid_to_set_in_metagraph ); // Add hotkey into hotkey set.
+        Self::add_owned_hotkey( &coldkey, &hotkey ); // Index the hotkey under its coldkey.
+        if Self::epoch_in_progress() {
+            EpochRegistrations::<T>::insert( uid_to_set_in_metagraph, uid_to_set_in_metagraph ); // Keeps its fresh state at the running epoch's commit.
+        }
         UsedWork::<T>::insert( &work.clone(), current_block ); // Add the work to current + block. So we can prune at a later date.
         Self::deposit_event(Event::NeuronRegistered( uid_to_set_in_metagraph ));
 
         Ok(())
     }
 
+    /// Runs the checks on a registration which do not depend on the rest of the block. They are
+    /// shared with the transaction pool, see: SubtensorSignedExtension::validate.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - AlreadyRegistered : The hotkey is already registered
+    /// - InvalidWorkBlock : The work block is in the future or more than 3 blocks old
+    /// - WorkRepeated : The work has already been used by another registration
+    /// - InvalidDifficulty : The work does not meet the current difficulty
+    /// - InvalidSeal : The work is not the seal of the block hash and nonce
+    ///
+    pub fn check_registration_work( block_number: u64, nonce: u64, work: &Vec<u8>, hotkey: &T::AccountId ) -> Result<(), Error<T>> {
+
+        ensure!( !Hotkeys::<T>::contains_key( hotkey ), Error::<T>::AlreadyRegistered );  // Hotkey has already registered.
+
+        // --- Check block number validity.
+        let current_block_number: u64 = Self::get_current_block_as_u64_here();
+        ensure! ( block_number <= current_block_number, Error::<T>::InvalidWorkBlock ); // Can't work on future block.
+        ensure! ( current_block_number - block_number < 3, Error::<T>::InvalidWorkBlock ); // Work must have been done within 3 blocks (stops long range attacks).
+
+        // --- Check for repeat work,
+        ensure!( !UsedWork::<T>::contains_key( work ), Error::<T>::WorkRepeated );  // Work has not been used before.
+
+        // --- Work which is not a 32 byte hash can never match a seal.
+        ensure!( work.len() == 32, Error::<T>::InvalidSeal );
+
+        // --- Check difficulty.
+        let difficulty: U256 = Self::get_difficulty();
+        let work_hash: H256 = Self::vec_to_hash( work.clone() );
+        ensure! ( Self::hash_meets_difficulty( &work_hash, difficulty ), Error::<T>::InvalidDifficulty ); // Check that the work meets difficulty.
+
+        // --- Check work.
+        let seal: H256 = Self::create_seal_hash( block_number, nonce );
+        ensure! ( seal == work_hash, Error::<T>::InvalidSeal ); // Check that this work matches hash and nonce.
+        Ok(())
+    }
+
+    /// Checks the next DeregistrationBatchSize uids and deregisters every neuron which has not set weights
+    /// within the inactivity horizon and is past its immunity period. Returns the weight consumed, each
+    /// deregistration adding the reads and writes of deregister_neuron.
+    pub fn deregister_inactive_neurons( ) -> Weight {
+        let inactivity_horizon: u64 = Self::get_inactivity_horizon();
+        let n: u32 = Self::get_neuron_count();
+        if inactivity_horizon == 0 || n == 0 { return 0 }
+        let current_block: u64 = Self::get_current_block_as_u64();
+        let immunity_period: u64 = Self::get_immunity_period();
+        let batch_size: u32 = Self::get_deregistration_batch_size().max( 1 ).min( n as u64 ) as u32;
+        let mut uid_i: u32 = DeregistrationCursor::<T>::get() % n;
+        let mut reads: u64 = 2 * batch_size as u64 + 4;
+        let mut writes: u64 = 1;
+        for _ in 0..batch_size {
+            if let Some( neuron_i ) = Neurons::<T>::get( uid_i ) {
+                let block_at_registration: u64 = BlockAtRegistration::<T>::get( uid_i );
+                if current_block.saturating_sub( neuron_i.last_update ) >= inactivity_horizon && current_block.saturating_sub( block_at_registration ) >= immunity_period {
+                    Self::deregister_neuron( neuron_i );
+                    reads += DEREGISTER_NEURON_READS;
+                    writes += DEREGISTER_NEURON_WRITES;
+                }
+            }
+            uid_i = ( uid_i + 1 ) % n;
+        }
+        DeregistrationCursor::<T>::put( uid_i );
+        T::DbWeight::get().reads_writes( reads, writes )
+    }
+
+    /// Removes a neuron from the graph. As when a peer is pruned, its stake is returned to the coldkey 
+    /// and the bonds held in its uid are cleared at the next epoch. The uid is freed for the next registration.
+    pub fn deregister_neuron( neuron: NeuronMetadataOf<T> ) {
+        let uid: u32 = neuron.uid;
+        let stake_to_be_added_on_coldkey = Self::u64_to_balance( neuron.stake );
+        Self::add_balance_to_coldkey_account( &neuron.coldkey, stake_to_be_added_on_coldkey.unwrap() );
+        Self::decrease_total_stake( neuron.stake );
+        Hotkeys::<T>::remove( &neuron.hotkey );
+        EmissionDestinations::<T>::remove( &neuron.hotkey );
+        FeePayingColdkeys::<T>::remove( &neuron.hotkey );
+        Self::remove_owned_hotkey( &neuron.coldkey, &neuron.hotkey );
+        Neurons::<T>::remove( uid );
+        BlockAtRegistration::<T>::remove( uid );
+        ValidatorPermit::<T>::remove( uid );
+        ValidatorTrust::<T>::remove( uid );
+        NeuronsToPruneAtNextEpoch::<T>::insert( uid, uid );
+        Self::insert_free_uid( uid );
+        Self::deposit_event( Event::NeuronDeregistered( uid, neuron.hotkey, neuron.stake ) );
+    }
+
+    /// Returns the lowest uid freed by deregistration, if any.
+    pub fn get_free_uid( ) -> Option<u32> {
+        FreeUidQueue::<T>::get().first().copied()
+    }
+
+    /// Marks the uid as free, keeping FreeUidQueue sorted.
+    pub fn insert_free_uid( uid: u32 ) {
+        FreeUids::<T>::insert( uid, uid );
+        FreeUidQueue::<T>::mutate( |queue| {
+            if let Err( index ) = queue.binary_search( &uid ) {
+                queue.insert( index, uid );
+            }
+        });
+    }
+
+    /// Removes and returns the lowest free uid, if any.
+    pub fn take_free_uid( ) -> Option<u32> {
+        let mut queue: Vec<u32> = FreeUidQueue::<T>::get();
+        if queue.is_empty() {
+            return None;
+        }
+        let free_uid: u32 = queue.remove( 0 );
+        FreeUidQueue::<T>::put( queue );
+        FreeUids::<T>::remove( free_uid );
+        Some( free_uid )
+    }
+
     pub fn get_current_block_as_u64_here( ) -> u64 {
         let block_as_u64: u64 = TryInto::try_into( system::Pallet::<T>::block_number() ).ok().expect("blockchain will not exceed 2^64 blocks; QED.");
         block_as_u64
@@ -1,4 +1,5 @@
 use super::*;
+use sp_std::collections::btree_map::BTreeMap;
 
 impl<T: Config> Pallet<T> {
     /***********************************************************
@@ -10,6 +11,9 @@ impl<T: Config> Pallet<T> {
         // ---- We check the transaction is signed by the caller
         // and retrieve the T::AccountId pubkey information.
         let coldkey = ensure_signed(origin)?;
+
+        // ---- We check the epoch is not reading stake or adding emission onto it, stake is frozen until it is done.
+        ensure!(!Self::stake_frozen_by_epoch(), Error::<T>::StakeFrozenByEpoch);
         //debug(&("--- Called add_stake with coldkey id {:?}, hotkey {:?} and amount_staked {:?}", coldkey, hotkey, stake_to_be_added));
 
         // Check if the hotkey is active
@@ -23,6 +27,9 @@ impl<T: Config> Pallet<T> {
         // cold key, otherwise throw a NonAssociatedColdKey error.
         ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
 
+        // ---- We check the amount against the minimum stake change and minimum stake.
+        Self::ensure_stake_addition_allowed(neuron.stake, stake_to_be_added)?;
+
         // ---- We check that the calling coldkey contains enough funds to
         // create the staking transaction.
         let stake_as_balance = Self::u64_to_balance(stake_to_be_added);
@@ -54,6 +61,8 @@ impl<T: Config> Pallet<T> {
     /// - NotRegistered : The suplied hotkey is not in use. This ususally means a node that uses this key has not subscribed yet, or has unsubscribed
     /// - NonAssociatedColdKey : The supplied hotkey account id is not subscribed using the supplied cold key
     /// - NotEnoughStaketoWithdraw : The ammount of stake available in the hotkey account is lower than the requested amount
+    /// - StakeChangeBelowMinimum : A partial withdrawal is smaller than the minimum stake change
+    /// - RemainingStakeBelowMinimum : A partial withdrawal leaves less than the minimum stake on the neuron
     /// - CouldNotConvertToBalance : A conversion error occured while converting stake from u64 to Balance
     ///
     pub fn do_remove_stake(origin: T::Origin, hotkey: T::AccountId, stake_to_be_removed: u64) -> dispatch::DispatchResult {
@@ -62,6 +71,9 @@ impl<T: Config> Pallet<T> {
         // and retrieve the T::AccountId pubkey information.
         let coldkey = ensure_signed(origin)?;
 
+        // ---- We check the epoch is not reading stake or adding emission onto it, stake is frozen until it is done.
+        ensure!(!Self::stake_frozen_by_epoch(), Error::<T>::StakeFrozenByEpoch);
+
         // ---- We query the Neuron set for the NeuronMetadata stored under
         // the passed hotkey.
         ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotRegistered);
@@ -77,6 +89,7 @@ impl<T: Config> Pallet<T> {
         // ---- We check that the hotkey has enough stake to withdraw
         // and then withdraw from the account.
         ensure!(Self::has_enough_stake(&neuron, stake_to_be_removed), Error::<T>::NotEnoughStaketoWithdraw);
+        Self::ensure_stake_removal_allowed(neuron.stake, stake_to_be_removed)?;
         let stake_to_be_added_as_currency = Self::u64_to_balance(stake_to_be_removed);
         ensure!(stake_to_be_added_as_currency.is_some(), Error::<T>::CouldNotConvertToBalance);
 
@@ -94,10 +107,218 @@ impl<T: Config> Pallet<T> {
     }
 
 
+    /// This function moves stake between two hotkey accounts of the calling coldkey.
+    /// The stake is taken from one neuron and added to the other in the same call, so
+    /// there is no block in which neither neuron holds it and TotalStake is untouched.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotRegistered : One of the hotkeys is not in use
+    /// - NonAssociatedColdKey : One of the hotkeys is not subscribed using the calling cold key
+    /// - MoveStakeToSameHotkey : Both hotkeys are the same account
+    /// - NotEnoughStaketoWithdraw : The stake of the source hotkey is lower than the requested amount
+    /// - StakeChangeBelowMinimum : The amount is smaller than the minimum stake change
+    /// - RemainingStakeBelowMinimum : The move leaves less than the minimum stake on the source hotkey
+    /// - StakeBelowMinimum : The move leaves less than the minimum stake on the destination hotkey
+    /// - StakeFrozenByEpoch : The epoch is reading stake or adding emission onto it
+    ///
+    pub fn do_move_stake(origin: T::Origin, from_hotkey: T::AccountId, to_hotkey: T::AccountId, amount: u64) -> dispatch::DispatchResult {
+
+        // ---- We check the transaction is signed by the caller
+        // and retrieve the T::AccountId pubkey information.
+        let coldkey = ensure_signed(origin)?;
+
+        // ---- We check the epoch is not reading stake or adding emission onto it, stake is frozen until it is done.
+        ensure!(!Self::stake_frozen_by_epoch(), Error::<T>::StakeFrozenByEpoch);
+        ensure!(from_hotkey != to_hotkey, Error::<T>::MoveStakeToSameHotkey);
+
+        // ---- Both hotkeys must be registered, active and linked to the calling cold key.
+        let mut from_neuron = Self::ensure_hotkey_owned_by_coldkey(&from_hotkey, &coldkey)?;
+        let mut to_neuron = Self::ensure_hotkey_owned_by_coldkey(&to_hotkey, &coldkey)?;
+        ensure!(Self::has_enough_stake(&from_neuron, amount), Error::<T>::NotEnoughStaketoWithdraw);
+        Self::ensure_stake_removal_allowed(from_neuron.stake, amount)?;
+        Self::ensure_stake_addition_allowed(to_neuron.stake, amount)?;
+
+        // ---- We move the stake directly between the neurons, the total stake does not change.
+        from_neuron.stake = from_neuron.stake.saturating_sub(amount);
+        to_neuron.stake = to_neuron.stake.saturating_add(amount);
+        Neurons::<T>::insert(from_neuron.uid, from_neuron);
+        Neurons::<T>::insert(to_neuron.uid, to_neuron);
+
+        // ---- Emit the move event.
+        Self::deposit_event(Event::StakeMoved(from_hotkey, to_hotkey, amount));
+
+        // --- Done and ok.
+        Ok(())
+    }
+
+    /// This function stakes on several hotkey accounts of the calling coldkey at once.
+    /// All entries are validated first and the summed amount is withdrawn from the coldkey
+    /// in a single withdrawal, so a failing entry leaves every account untouched.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotRegistered : One of the hotkeys is not in use
+    /// - NonAssociatedColdKey : One of the hotkeys is not subscribed using the calling cold key
+    /// - StakeChangeBelowMinimum : One of the amounts is smaller than the minimum stake change
+    /// - StakeBelowMinimum : One of the entries leaves its neuron with less than the minimum stake
+    /// - StakeFrozenByEpoch : The epoch is reading stake or adding emission onto it
+    /// - NotEnoughBalanceToStake : The summed amount is more than the coldkey can withdraw
+    /// - CouldNotConvertToBalance : A conversion error occured while converting stake from u64 to Balance
+    ///
+    pub fn do_add_stake_multiple(origin: T::Origin, stakes: Vec<(T::AccountId, u64)>) -> dispatch::DispatchResult {
+
+        // ---- We check the transaction is signed by the caller
+        // and retrieve the T::AccountId pubkey information.
+        let coldkey = ensure_signed(origin)?;
+
+        // ---- We check the epoch is not reading stake or adding emission onto it, stake is frozen until it is done.
+        ensure!(!Self::stake_frozen_by_epoch(), Error::<T>::StakeFrozenByEpoch);
+
+        // ---- We check every entry and sum the amounts before touching any account. The minimums
+        // are checked as if the entries were staked one after the other.
+        let mut uids: Vec<u32> = Vec::with_capacity(stakes.len());
+        let mut added: BTreeMap<u32, u64> = BTreeMap::new();
+        let mut total_to_be_added: u64 = 0;
+        for (hotkey, amount) in stakes.iter() {
+            let neuron = Self::ensure_hotkey_owned_by_coldkey(hotkey, &coldkey)?;
+            let added_to_uid: u64 = added.get(&neuron.uid).copied().unwrap_or(0);
+            Self::ensure_stake_addition_allowed(neuron.stake.saturating_add(added_to_uid), *amount)?;
+            added.insert(neuron.uid, added_to_uid.saturating_add(*amount));
+            total_to_be_added = total_to_be_added.checked_add(*amount).ok_or(Error::<T>::NotEnoughBalanceToStake)?;
+            uids.push(neuron.uid);
+        }
+        let total_as_balance = Self::u64_to_balance(total_to_be_added);
+        ensure!(total_as_balance.is_some(), Error::<T>::CouldNotConvertToBalance);
+        ensure!(Self::can_remove_balance_from_coldkey_account(&coldkey, total_as_balance.unwrap()), Error::<T>::NotEnoughBalanceToStake);
+        ensure!(Self::remove_balance_from_coldkey_account(&coldkey, total_as_balance.unwrap()) == true, Error::<T>::BalanceWithdrawalError);
+
+        // ---- The balance is withdrawn, stake every entry.
+        for ((hotkey, amount), uid) in stakes.into_iter().zip(uids.into_iter()) {
+            Self::add_stake_to_neuron_hotkey_account(uid, amount);
+            Self::deposit_event(Event::StakeAdded(hotkey, amount));
+        }
+
+        // --- ok and return.
+        Ok(())
+    }
+
+    /// This function unstakes from several hotkey accounts of the calling coldkey at once.
+    /// All entries are validated first, entries for the same hotkey are summed against its
+    /// stake, and the total is deposited into the coldkey once every entry is unstaked.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotRegistered : One of the hotkeys is not in use
+    /// - NonAssociatedColdKey : One of the hotkeys is not subscribed using the calling cold key
+    /// - NotEnoughStaketoWithdraw : The summed amount of a hotkey is more than its stake
+    /// - StakeChangeBelowMinimum : One of the partial withdrawals is smaller than the minimum stake change
+    /// - RemainingStakeBelowMinimum : One of the partial withdrawals leaves less than the minimum stake on its neuron
+    /// - StakeFrozenByEpoch : The epoch is reading stake or adding emission onto it
+    /// - CouldNotConvertToBalance : A conversion error occured while converting stake from u64 to Balance
+    ///
+    pub fn do_remove_stake_multiple(origin: T::Origin, stakes: Vec<(T::AccountId, u64)>) -> dispatch::DispatchResult {
+
+        // ---- We check the transaction is signed by the caller
+        // and retrieve the T::AccountId pubkey information.
+        let coldkey = ensure_signed(origin)?;
+
+        // ---- We check the epoch is not reading stake or adding emission onto it, stake is frozen until it is done.
+        ensure!(!Self::stake_frozen_by_epoch(), Error::<T>::StakeFrozenByEpoch);
+
+        // ---- We check every entry, summing the requested amounts per uid so that
+        // repeated hotkeys cannot withdraw more than their stake between them.
+        let mut uids: Vec<u32> = Vec::with_capacity(stakes.len());
+        let mut requested: BTreeMap<u32, u64> = BTreeMap::new();
+        let mut total_to_be_removed: u64 = 0;
+        for (hotkey, amount) in stakes.iter() {
+            let neuron = Self::ensure_hotkey_owned_by_coldkey(hotkey, &coldkey)?;
+            let requested_before: u64 = requested.get(&neuron.uid).copied().unwrap_or(0);
+            let requested_for_uid: u64 = requested_before.checked_add(*amount).ok_or(Error::<T>::NotEnoughStaketoWithdraw)?;
+            ensure!(Self::has_enough_stake(&neuron, requested_for_uid), Error::<T>::NotEnoughStaketoWithdraw);
+            Self::ensure_stake_removal_allowed(neuron.stake - requested_before, *amount)?;
+            requested.insert(neuron.uid, requested_for_uid);
+            total_to_be_removed = total_to_be_removed.checked_add(*amount).ok_or(Error::<T>::NotEnoughStaketoWithdraw)?;
+            uids.push(neuron.uid);
+        }
+        let total_as_balance = Self::u64_to_balance(total_to_be_removed);
+        ensure!(total_as_balance.is_some(), Error::<T>::CouldNotConvertToBalance);
+
+        // ---- Unstake every entry and deposit the total into the coldkey account.
+        for ((hotkey, amount), uid) in stakes.into_iter().zip(uids.into_iter()) {
+            Self::remove_stake_from_neuron_hotkey_account(uid, amount);
+            Self::deposit_event(Event::StakeRemoved(hotkey, amount));
+        }
+        Self::add_balance_to_coldkey_account(&coldkey, total_as_balance.unwrap());
+
+        // --- Done and ok.
+        Ok(())
+    }
+
+    pub fn do_set_emission_destination(origin: T::Origin, hotkey: T::AccountId, destination: EmissionDestination) -> dispatch::DispatchResult {
+
+        // ---- We check the transaction is signed by the caller
+        // and retrieve the T::AccountId pubkey information.
+        let coldkey = ensure_signed(origin)?;
+
+        // ---- We check that the hotkey is registered and owned by the caller.
+        ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotRegistered);
+        let neuron = Self::get_neuron_for_hotkey(&hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+
+        // ---- A split can send at most all of the emission to the coldkey.
+        if let EmissionDestination::Split(percent) = destination {
+            ensure!(percent <= 100, Error::<T>::InvalidEmissionSplit);
+        }
+
+        EmissionDestinations::<T>::insert(&hotkey, destination);
+        Self::deposit_event(Event::EmissionDestinationSet(hotkey, destination));
+        Ok(())
+    }
+
     /********************************
     --==[[  Helper functions   ]]==--
     *********************************/
 
+    /// Returns the part of the emission which is paid to the coldkey of the hotkey rather than staked.
+    ///
+    pub fn get_emission_to_coldkey(hotkey: &T::AccountId, emission: u64) -> u64 {
+        match EmissionDestinations::<T>::get(hotkey) {
+            EmissionDestination::Stake => 0,
+            EmissionDestination::Coldkey => emission,
+            EmissionDestination::Split(percent) => ( emission as u128 * percent.min(100) as u128 / 100 ) as u64,
+        }
+    }
+
+    /// Checks an addition of stake against the minimums. The amount must reach the minimum
+    /// stake change and the stake of the neuron after the addition must reach the minimum stake.
+    ///
+    pub fn ensure_stake_addition_allowed(stake: u64, amount: u64) -> Result<(), Error<T>> {
+        ensure!(amount >= Self::get_min_stake_change(), Error::<T>::StakeChangeBelowMinimum);
+        ensure!(stake.saturating_add(amount) >= Self::get_min_stake(), Error::<T>::StakeBelowMinimum);
+        Ok(())
+    }
+
+    /// Checks a removal of stake against the minimums. Withdrawing the whole stake is always
+    /// allowed so that dust below the minimums can be cleared. A partial withdrawal must reach
+    /// the minimum stake change and leave at least the minimum stake on the neuron.
+    ///
+    pub fn ensure_stake_removal_allowed(stake: u64, amount: u64) -> Result<(), Error<T>> {
+        if amount > 0 && amount == stake {
+            return Ok(());
+        }
+        ensure!(amount >= Self::get_min_stake_change(), Error::<T>::StakeChangeBelowMinimum);
+        ensure!(stake.saturating_sub(amount) >= Self::get_min_stake(), Error::<T>::RemainingStakeBelowMinimum);
+        Ok(())
+    }
+
+    /// Returns the neuron of an active hotkey if it is subscribed with the given cold key.
+    ///
+    pub fn ensure_hotkey_owned_by_coldkey(hotkey: &T::AccountId, coldkey: &T::AccountId) -> Result<NeuronMetadataOf<T>, Error<T>> {
+        ensure!(Self::is_hotkey_active(hotkey), Error::<T>::NotRegistered);
+        let neuron = Self::get_neuron_for_hotkey(hotkey);
+        ensure!(Self::is_uid_active(neuron.uid), Error::<T>::NotRegistered);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, coldkey), Error::<T>::NonAssociatedColdKey);
+        Ok(neuron)
+    }
+
     pub fn get_stake_of_neuron_hotkey_account_by_uid(uid: u32) -> u64 {
         return Self::get_neuron_for_uid(uid).stake
     }
@@ -1,9 +1,10 @@
 use super::*;
 use sp_std::convert::TryInto;
 use substrate_fixed::types::I65F63;
-use substrate_fixed::transcendental::exp;
 use substrate_fixed::transcendental::log2;
 use frame_support::IterableStorageMap;
+use frame_support::weights::Weight;
+use frame_support::traits::Get;
 
 const LOG_TARGET: &'static str = "runtime::subtensor::step";
 
@@ -159,267 +160,403 @@ impl<T: Config> Pallet<T> {
     ///
     /// 
     /// Note, operations 1 and 2 are computed together. 
+    ///
+    /// The computation itself is performed by T::IncentiveMechanism over a snapshot of the graph.
     ////
     pub fn mechanism_step ( emission_this_step: u64 ) {
-
-        // The amount this mechanism step emits on this block.
-        let block_emission: I65F63 = I65F63::from_num( emission_this_step );
         log::trace!(
             target: LOG_TARGET,
             "step"
         );
-      
-        // Number of peers.
-        let n: usize = Self::get_neuron_count() as usize;
-        let block: u64 = Self::get_current_block_as_u64();
-        
-        // Constants.
-        let activity_cutoff: u64 = Self::get_activity_cutoff();
-        let bonds_moving_average:I65F63 = I65F63::from_num( Self::get_bonds_moving_average() ) / I65F63::from_num( 1_000_000 );
-        let u64_max: I65F63 = I65F63::from_num( u64::MAX );
-        let u32_max: I65F63 = I65F63::from_num( u32::MAX );
-        let one: I65F63 = I65F63::from_num( 1.0 );
-        let zero: I65F63 = I65F63::from_num( 0.0 );
-        let rho: I65F63 = I65F63::from_num( Self::get_rho() );
-        let kappa: I65F63 = one / I65F63::from_num( Self::get_kappa() );
-        let self_ownership: I65F63 = one / I65F63::from_num( Self::get_self_ownership()  );
 
-        // To be filled.
-        let mut uids: Vec<u32> = vec![];
-        let mut active: Vec<u32> = vec![0; n];
-        let mut priority: Vec<u64> = vec![0;n];
-        let mut bond_totals: Vec<u64> = vec![0; n];
-        let mut bonds: Vec<Vec<u64>> = vec![vec![0;n]; n];
-        let mut weights: Vec<Vec<(u32,u32)>> = vec![ vec![]; n ];
-        let mut total_stake: I65F63 = I65F63::from_num( 0.0 );
-        let mut total_active_stake: I65F63 = I65F63::from_num( 0.0 );
-        let mut total_normalized_active_stake: I65F63 = I65F63::from_num( 0.0 );
-        let mut stake: Vec<I65F63> = vec![ I65F63::from_num( 0.0 ) ; n];
-        for ( uid_i, neuron_i ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
+        // Read the graph into a snapshot, run the configured mechanism over it and write back the results.
+        let snapshot: EpochSnapshot = Self::get_epoch_snapshot( emission_this_step );
+        let output: EpochOutput = T::IncentiveMechanism::run( &snapshot );
+        Self::apply_epoch_output( snapshot.active, snapshot.validator_permit, output );
+    }
 
-            // Append a set of uids.
-            uids.push( uid_i );
-            if block - neuron_i.last_update >= activity_cutoff {
-                active [ uid_i as usize ] = 0;
-            } else {
-                active [ uid_i as usize ] = 1;
-                total_active_stake += I65F63::from_num( neuron_i.stake );
-            }
-            total_stake += I65F63::from_num( neuron_i.stake );
-            stake [ uid_i as usize ] = I65F63::from_num( neuron_i.stake );
-
-            // Priority increments by the log of the stake and is drained everytime the account sets weights. 
-            let log_stake:I65F63 = log2( I65F63::from_num( neuron_i.stake + 1 ) ).expect( "stake + 1 is positive and greater than 1.");
-            priority [ uid_i as usize ] = neuron_i.priority + log_stake.to_num::<u64>();
-
-            weights [ uid_i as usize ] = neuron_i.weights;             
-            let mut bonds_row: Vec<u64> = vec![0; n];
-            for (uid_j, bonds_ij) in neuron_i.bonds.iter() {
-                
-                // Prunning occurs here. We simply to do fill this bonds matrix 
-                // with entries that contain the uids to prune. 
-                if !NeuronsToPruneAtNextEpoch::<T>::contains_key(uid_j) {
-                    // Otherwise, we add the entry into the stack based bonds array. We decay here as an optimization.
-                    let decayed_bond_ij: u64 = (bonds_moving_average * I65F63::from_num( *bonds_ij )).to_num::<u64>();
-                    bonds_row [ *uid_j as usize ] = decayed_bond_ij;
-                    bond_totals [ *uid_j as usize ] += decayed_bond_ij;
-                }
+    /// Starts an epoch which runs over the following blocks. Each phase visits EpochChunkSize uids per block:
+    /// the snapshot copies the neurons, the mechanism scores the received weights, the set weights and distributes
+    /// dividends, then the results are committed. Incentive is computed in a single block between the scoring and
+    /// the dividends phases. Stake can not change while the snapshot is read.
+    pub fn begin_epoch ( emission_this_step: u64 ) -> Weight {
+        log::trace!(
+            target: LOG_TARGET,
+            "begin epoch"
+        );
+        CurrentEpoch::<T>::put( EpochState {
+            phase: EpochPhase::Snapshot,
+            next_uid: 0,
+            n: Self::get_neuron_count(),
+            start_block: Self::get_current_block_as_u64(),
+            emission: emission_this_step,
+        });
+        Self::continue_epoch()
+    }
 
-            }
-            bonds[ uid_i as usize ] = bonds_row;
+    pub fn epoch_in_progress( ) -> bool {
+        CurrentEpoch::<T>::get().phase != EpochPhase::Idle
+    }
+
+    /// Returns true while stake cannot change: the snapshot phase reads it and the commit phase adds the
+    /// emission onto it. The scoring phases in between only use the snapshot.
+    pub fn stake_frozen_by_epoch( ) -> bool {
+        match CurrentEpoch::<T>::get().phase {
+            EpochPhase::Snapshot | EpochPhase::Commit => true,
+            _ => false,
         }
-        // Normalize stake based on activity.
-        if total_active_stake != 0 {
-            for uid_i in uids.iter() {
-                let normalized_active_stake:I65F63 = stake[ *uid_i as usize ] / total_active_stake;
-                stake[ *uid_i as usize ] = normalized_active_stake;
-                if active[ *uid_i as usize ] == 1 {
-                    total_normalized_active_stake += normalized_active_stake;
+    }
+
+    /// Runs the next chunk of the current epoch and returns the weight consumed.
+    pub fn continue_epoch( ) -> Weight {
+        let mut state: EpochState = CurrentEpoch::<T>::get();
+        let db_weight = T::DbWeight::get();
+        if state.phase == EpochPhase::Idle { return 0 }
+
+        // The uids visited by this block, a chunk size of zero visits all remaining uids.
+        let chunk_size: u32 = match Self::get_epoch_chunk_size() { 0 => state.n, chunk_size => chunk_size.min( state.n as u64 ) as u32 };
+        let end_uid: u32 = state.n.min( state.next_uid.saturating_add( chunk_size ) );
+        let chunk: u64 = ( end_uid - state.next_uid ) as u64;
+        let mut reads: u64 = 1;
+        let mut writes: u64 = 1;
+
+        if state.phase == EpochPhase::Snapshot {
+            // Copy the next chunk of neurons, weights are also stored by the uid receiving them.
+            let activity_cutoff: u64 = Self::get_activity_cutoff();
+            for uid_i in state.next_uid..end_uid {
+                let mut row_i: EpochSnapshotRow = EpochSnapshotRow::default();
+                if let Some( neuron_i ) = Neurons::<T>::get( uid_i ) {
+                    reads += 1 + neuron_i.bonds.len() as u64 + neuron_i.weights.len() as u64;
+                    row_i = Self::get_epoch_snapshot_row( &neuron_i, state.start_block, activity_cutoff );
+                }
+                // Weights and bonds on uids registered after the epoch started are dropped.
+                row_i.weights.retain( |( uid_j, _ )| *uid_j < state.n );
+                row_i.bonds.retain( |( uid_j, _ )| *uid_j < state.n );
+                for ( uid_j, weight_ij ) in row_i.weights.iter() {
+                    EpochReceivedWeights::<T>::append( uid_j, ( uid_i, *weight_ij ) );
                 }
+                EpochStakes::<T>::append( ( row_i.stake, row_i.active ) );
+                writes += 2 + row_i.weights.len() as u64;
+                EpochSnapshotRows::<T>::insert( uid_i, row_i );
             }
+            state.next_uid = end_uid;
+            if state.next_uid >= state.n {
+                // Every stake has been read, start the mechanism and grant validator permits.
+                let rows: Vec<EpochSnapshotRow> = EpochStakes::<T>::take().into_iter().map( |( stake, active )| EpochSnapshotRow { stake: stake, active: active, ..Default::default() } ).collect();
+                let snapshot: EpochSnapshot = Self::assemble_epoch_snapshot( rows, state.emission );
+                EpochScores::<T>::put( T::IncentiveMechanism::begin( &snapshot ) );
+                PendingEpochResult::<T>::put( EpochResult { active: snapshot.active, validator_permit: snapshot.validator_permit, ..Default::default() } );
+                reads += 1;
+                writes += 2;
+                state.phase = EpochPhase::Consensus;
+                state.next_uid = 0;
+            }
+            CurrentEpoch::<T>::put( state );
+            return db_weight.reads_writes( reads, writes );
         }
-		log::trace!(
-			target: LOG_TARGET,
-			"stake: {:?}",
-			stake
-		);
-
-        // Computational aspect starts here.
-        
-        // Compute ranks and trust.
-        let mut total_bonds_purchased: u64 = 0;
-        let mut total_ranks: I65F63 = I65F63::from_num( 0.0 );
-        let mut total_trust: I65F63 = I65F63::from_num( 0.0 );
-        let mut ranks: Vec<I65F63> = vec![ I65F63::from_num( 0.0 ) ; n];
-        let mut trust: Vec<I65F63> = vec![ I65F63::from_num( 0.0 ) ; n];
-        for uid_i in uids.iter() {
-
-            // === Get vars for uid_i ===
-            let stake_i: I65F63 = stake[ *uid_i as usize ];
-            let weights_i: &Vec<(u32, u32)> = &weights[ *uid_i as usize ];
-            if stake_i == zero { continue } // Skip zeros stake.
-
-            // === Iterate over weights ===
-            for ( uid_j, weight_ij ) in weights_i.iter() {
-
-                if *uid_i == *uid_j { continue } // Skip self-weight.
-
-                // === Compute score increments ===
-                // Non active validators dont have the ability to increase ranks.
-                // & bond increments converge to zero for non active validators.
-                let mut rank_increment_ij: I65F63 = I65F63::from_num(0.0);
-                let mut bond_increment_ij: I65F63 = I65F63::from_num(0.0);
-                let mut trust_increment_ij: I65F63 = I65F63::from_num(0.0);
-                if active[ *uid_i as usize ] == 1 { 
-                    let weight_ij: I65F63 = I65F63::from_num( *weight_ij ) / u32_max; // Range( 0, 1 )
-                    trust_increment_ij = stake_i; // Range( 0, 1 )                
-                    rank_increment_ij = stake_i * weight_ij; // Range( 0, total_active_stake )
-                    bond_increment_ij = rank_increment_ij * block_emission;
-                }
-                
-                // === Increment neuron scores ===
-                ranks[ *uid_j as usize ] += rank_increment_ij;  // Range( 0, total_active_stake )
-                trust[ *uid_j as usize ] += trust_increment_ij;  // Range( 0, total_active_stake )
-                total_ranks += rank_increment_ij;  // Range( 0, total_active_stake )
-                total_trust += trust_increment_ij;  // Range( 0, total_active_stake )
-                
-                // === Compute bonding moving averages ===
-                let moving_bonds_previous_ij: I65F63 = I65F63::from_num( bonds[ *uid_i as usize  ][ *uid_j as usize ] );
-                let moving_bond_increment_ij: I65F63 = ( one - bonds_moving_average ) * bond_increment_ij;
-                let moving_bond_next_ij: I65F63 = moving_bonds_previous_ij + moving_bond_increment_ij;
-                bonds [ *uid_i as usize  ][ *uid_j as usize ] = moving_bond_next_ij.to_num::<u64>(); // Range( 0, block_emission )
-                bond_totals [ *uid_j as usize ] += moving_bond_increment_ij.to_num::<u64>();
-                total_bonds_purchased += moving_bond_increment_ij.to_num::<u64>();
 
+        if state.phase == EpochPhase::Commit {
+            // The totals follow each committed chunk so that they always match the stake on the neurons. Until the
+            // last chunk the metagraph holds the new scores below next_uid and the previous ones above it.
+            let mut result: EpochResult = match PendingEpochResult::<T>::get() { Some( result ) => result, None => { return Self::drop_epoch() } };
+            let staked_emission_before: u64 = result.total_staked_emission;
+            let emission_before: u64 = result.total_emission;
+            for uid_i in state.next_uid..end_uid {
+                let row_i: EpochSnapshotRow = EpochSnapshotRows::<T>::take( uid_i ).unwrap_or_default();
+                Self::commit_epoch_neuron( &mut result, uid_i, row_i.bonds );
             }
-        }
-        // === Normalize ranks + trust ===
-        if total_trust > 0 && total_ranks > 0 {
-            for uid_i in uids.iter() {
-                ranks[ *uid_i as usize ] = ranks[ *uid_i as usize ] / total_ranks; // Vector will sum to u64_max
-                trust[ *uid_i as usize ] = trust[ *uid_i as usize ] / total_normalized_active_stake; // Vector will sum to u64_max
+            Self::add_committed_emission( result.total_staked_emission - staked_emission_before, result.total_emission - emission_before );
+            state.next_uid = end_uid;
+            if state.next_uid >= state.n {
+                PendingEpochResult::<T>::kill();
+                Self::finish_epoch( result );
+                state = EpochState::default();
+            } else {
+                PendingEpochResult::<T>::put( result );
             }
+            CurrentEpoch::<T>::put( state );
+            return db_weight.reads_writes( 4 * chunk + 4, 4 * chunk + 10 );
         }
-		 log::trace!(target: LOG_TARGET, "ranks: {:?}", ranks);
-		 log::trace!(target: LOG_TARGET, "trust: {:?}", trust);
-		 log::trace!(target: LOG_TARGET, "bonds: {:?}, {:?}, {:?}", bonds, bond_totals, total_bonds_purchased);
-
-        // Compute consensus, incentive.
-        let mut total_incentive: I65F63 = I65F63::from_num( 0.0 );
-        let mut consensus: Vec<I65F63> = vec![ I65F63::from_num( 0.0 ) ; n];
-        let mut incentive: Vec<I65F63> = vec![ I65F63::from_num( 0.0 ) ; n];
-        if total_ranks != 0 && total_trust != 0 {
-            for uid_i in uids.iter() {                    
-                // Get exponentiated trust score.
-                let trust_i: I65F63 = trust[ *uid_i as usize ];
-                let shifted_trust: I65F63 = trust_i - kappa; // Range( -kappa, 1 - kappa )
-                let temperatured_trust: I65F63 = shifted_trust * rho; // Range( -rho * kappa, rho ( 1 - kappa ) )
-                let exponentiated_trust: I65F63 = exp( -temperatured_trust ).expect( "temperatured_trust is on range( -rho * kappa, rho ( 1 - kappa ) )"); // Range( exp(-rho * kappa), exp(rho ( 1 - kappa )) )
-                  
-                // Compute consensus.
-                let ranks_i: I65F63 = ranks[ *uid_i as usize ];
-                let consensus_i: I65F63 = one / (one + exponentiated_trust); // Range( 0, 1 )
-                let incentive_i: I65F63 = ranks_i * consensus_i; // Range( 0, 1 )
-                consensus[ *uid_i as usize ] = consensus_i; // Range( 0, 1 )
-                incentive[ *uid_i as usize ] = incentive_i; // Range( 0, 1 )
-                total_incentive += incentive_i;
+
+        // The scoring phases update the mechanism scores kept in storage.
+        let mut scores: ScoresOf<T> = match EpochScores::<T>::get() { Some( scores ) => scores, None => { return Self::drop_epoch() } };
+        reads += 1;
+        writes += 1;
+        match state.phase {
+            EpochPhase::Consensus => {
+                for uid_j in state.next_uid..end_uid {
+                    T::IncentiveMechanism::score_column( &mut scores, uid_j, &EpochReceivedWeights::<T>::take( uid_j ) );
+                }
+                reads += chunk;
+                writes += chunk;
+                state.next_uid = end_uid;
+                if state.next_uid >= state.n {
+                    state.phase = EpochPhase::Ranks;
+                    state.next_uid = 0;
+                }
             }
-        }
-        // Normalize Incentive.
-        if total_incentive > 0 {
-            for uid_i in uids.iter() {
-                incentive[ *uid_i as usize ] = incentive[ *uid_i as usize ] / total_incentive; // Vector will sum to u64_max
+            EpochPhase::Ranks => {
+                for uid_i in state.next_uid..end_uid {
+                    let mut row_i: EpochSnapshotRow = EpochSnapshotRows::<T>::get( uid_i ).unwrap_or_default();
+                    T::IncentiveMechanism::score_row( &mut scores, uid_i, &row_i.weights, &mut row_i.bonds );
+                    row_i.weights = vec![]; // Weights are no longer needed.
+                    EpochSnapshotRows::<T>::insert( uid_i, row_i );
+                }
+                reads += chunk;
+                writes += chunk;
+                state.next_uid = end_uid;
+                if state.next_uid >= state.n {
+                    state.phase = EpochPhase::Incentive;
+                    state.next_uid = 0;
+                }
             }
-        }
-        log::trace!(target: LOG_TARGET, "incentive: {:?}, consensus: {:?}", incentive, consensus);
-
-        // Compute dividends.
-        let mut total_dividends: I65F63 = I65F63::from_num( 0.0 );
-        let mut dividends: Vec<I65F63> = vec![ I65F63::from_num( 0.0 ) ; n];
-        let mut sparse_bonds: Vec<Vec<(u32,u64)>> = vec![vec![]; n];
-        for uid_i in uids.iter() {
-
-            // To be filled: Sparsified bonds.
-            let mut sparse_bonds_row: Vec<(u32, u64)> = vec![];
-
-            // Distribute dividends from self-ownership.
-            let incentive_i: I65F63 = incentive[ *uid_i as usize ];
-            let total_bonds_i: u64 = bond_totals[ *uid_i as usize ]; // Range( 0, total_emission );
-            let mut dividends_ii: I65F63 = incentive_i * self_ownership;
-            if total_bonds_i == 0 {
-                dividends_ii += incentive_i * ( one - self_ownership ); // Add the other half.
+            EpochPhase::Incentive => {
+                T::IncentiveMechanism::end_scoring( &mut scores );
+                state.phase = EpochPhase::Dividends;
             }
-            dividends[ *uid_i as usize ] += dividends_ii; // Range( 0, block_emission / 2 );
-            total_dividends += dividends_ii; // Range( 0, block_emission / 2 );
-
-            // Distribute dividends from other-ownership.
-            for uid_j in uids.iter() {
-                
-                // Get i -> j bonds.
-                let bonds_ij: u64 = bonds[ *uid_i as usize ][ *uid_j as usize ]; // Range( 0, total_emission );
-                let total_bonds_j: u64 = bond_totals[ *uid_j as usize ]; // Range( 0, total_emission );
-                if total_bonds_j == 0 { continue; } // No bond ownership in this neuron.
-                if bonds_ij == 0 { continue; } // No need to distribute dividends for zero bonds.
-
-                // Compute bond fraction.
-                let bond_fraction_ij: I65F63 = I65F63::from_num( bonds_ij ) / I65F63::from_num( total_bonds_j ); // Range( 0, 1 );
-
-                // Compute incentive owenership fraction.
-                let mut ownership_ji: I65F63 = one - self_ownership; // Range( 0, 1 );
-                ownership_ji = ownership_ji * bond_fraction_ij; // Range( 0, 1 );
-
-                // Compute dividends
-                let dividends_ji: I65F63 = incentive[ *uid_j as usize ] * ownership_ji; // Range( 0, 1 );
-                dividends[ *uid_i as usize ] += dividends_ji; // Range( 0, block_emission / 2 );
-                total_dividends += dividends_ji; // Range( 0, block_emission / 2 );
-                sparse_bonds_row.push( (*uid_j as u32, bonds_ij) );
+            EpochPhase::Dividends => {
+                for uid_i in state.next_uid..end_uid {
+                    let mut row_i: EpochSnapshotRow = EpochSnapshotRows::<T>::get( uid_i ).unwrap_or_default();
+                    T::IncentiveMechanism::distribute_row( &mut scores, uid_i, &mut row_i.bonds );
+                    EpochSnapshotRows::<T>::insert( uid_i, row_i );
+                }
+                reads += chunk;
+                writes += chunk;
+                state.next_uid = end_uid;
+                if state.next_uid >= state.n {
+                    // Every row has been scored, keep the output for the commit phase.
+                    let output: EpochOutput = T::IncentiveMechanism::finish( scores );
+                    EpochScores::<T>::kill();
+                    PendingEpochResult::<T>::mutate( |result| if let Some( result ) = result { result.output = output } );
+                    reads += 1;
+                    writes += 1;
+                    state.phase = EpochPhase::Commit;
+                    state.next_uid = 0;
+                    CurrentEpoch::<T>::put( state );
+                    return db_weight.reads_writes( reads, writes );
+                }
             }
-            sparse_bonds[ *uid_i as usize ] = sparse_bonds_row;
+            EpochPhase::Idle | EpochPhase::Snapshot | EpochPhase::Commit => {}
         }
-        // Normalize dividends. Sanity check.
-        let mut total_emission: u64 = 0;
-        let mut emission: Vec<u64> = vec![ 0; n];
-        if total_dividends != 0 {
-            for uid_i in uids.iter() {
-                let dividends_i: I65F63 = dividends[ *uid_i as usize ] / total_dividends;
-                let emission_i: u64 = (block_emission * dividends_i).to_num::<u64>();
-                dividends[ *uid_i as usize ] = dividends_i;
-                emission[ *uid_i as usize ] = emission_i;
-                total_emission += emission_i;
-            }
+        EpochScores::<T>::put( scores );
+        CurrentEpoch::<T>::put( state );
+        db_weight.reads_writes( reads, writes )
+    }
+
+    /// Abandons the current epoch when its intermediate state is missing. No emission is distributed.
+    fn drop_epoch( ) -> Weight {
+        let _ = EpochSnapshotRows::<T>::remove_all( None );
+        let _ = EpochReceivedWeights::<T>::remove_all( None );
+        EpochStakes::<T>::kill();
+        EpochScores::<T>::kill();
+        PendingEpochResult::<T>::kill();
+        CurrentEpoch::<T>::kill();
+        <T as frame_system::Config>::BlockWeights::get().max_block
+    }
+
+    /// Writes the mechanism output back onto the neurons and updates the totals.
+    pub fn apply_epoch_output( active: Vec<bool>, validator_permit: Vec<bool>, mut output: EpochOutput ) {
+        let bonds: Vec<Vec<(u32,u64)>> = sp_std::mem::take( &mut output.bonds );
+        let mut result: EpochResult = EpochResult { active: active, validator_permit: validator_permit, output: output, ..Default::default() };
+        for ( uid_i, bonds_i ) in bonds.into_iter().enumerate() {
+            Self::commit_epoch_neuron( &mut result, uid_i as u32, bonds_i );
         }
+        Self::add_committed_emission( result.total_staked_emission, result.total_emission );
+        Self::finish_epoch( result );
+    }
 
-		 log::trace!(target: LOG_TARGET, "dividends: {:?}, emission: {:?}", dividends, emission);
-
-        for ( uid_i, mut neuron_i ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
-            // Update table entry.
-            neuron_i.active = active[ uid_i as usize ];
-            neuron_i.priority = priority[ uid_i as usize ];
-            neuron_i.emission = emission[ uid_i as usize ];
-            neuron_i.stake = neuron_i.stake + emission[ uid_i as usize ];
-            neuron_i.rank = (ranks[ uid_i as usize ] * u64_max).to_num::<u64>();
-            neuron_i.trust = (trust[ uid_i as usize ] * u64_max).to_num::<u64>();
-            neuron_i.consensus = (consensus[ uid_i as usize ] * u64_max).to_num::<u64>();
-            neuron_i.incentive = (incentive[ uid_i as usize ] * u64_max).to_num::<u64>();
-            neuron_i.dividends = (dividends[ uid_i as usize ] * u64_max).to_num::<u64>();
-            neuron_i.bonds = sparse_bonds[ uid_i as usize ].clone();
-            Neurons::<T>::insert( neuron_i.uid, neuron_i );
+    /// Adds the emission committed onto neurons to the issuance, and the part of it which was staked to the total stake.
+    fn add_committed_emission( staked_emission: u64, emission: u64 ) {
+        TotalIssuance::<T>::mutate( |val| *val += emission );
+        TotalStake::<T>::mutate( |val| *val += staked_emission );
+    }
 
-            // This where we remove the neurons to prune (clearing the table.)
-            if NeuronsToPruneAtNextEpoch::<T>::contains_key( uid_i ) {
-                NeuronsToPruneAtNextEpoch::<T>::remove ( uid_i );
+    /// Writes the output of a single uid back onto its neuron and adds it to the result totals.
+    /// The emission of a uid is settled when it is committed. A uid whose neuron was deregistered or replaced before
+    /// then earns nothing at this epoch: the scored neuron has left the graph with its stake returned to its coldkey,
+    /// and a new owner did no work at this epoch. Its emission is not issued, and a new owner keeps its fresh scores.
+    pub fn commit_epoch_neuron( result: &mut EpochResult, uid_i: u32, bonds_i: Vec<(u32,u64)> ) {
+        let i: usize = uid_i as usize;
+        let mut neuron_i: NeuronMetadataOf<T> = match Neurons::<T>::get( uid_i ) {
+            Some( neuron_i ) if !EpochRegistrations::<T>::contains_key( uid_i ) => neuron_i,
+            _ => {
+                result.output.emission[ i ] = 0;
+                Self::drop_epoch_scores( &mut result.output, i );
+                return
             }
+        };
+
+        // Priority increments by the log of the stake and is drained everytime the account sets weights. 
+        let log_stake:I65F63 = log2( I65F63::from_num( neuron_i.stake + 1 ) ).expect( "stake + 1 is positive and greater than 1.");
+
+        if result.active[ i ] {
+            result.active_neurons += 1;
+            result.total_active_stake += neuron_i.stake;
+        }
+        neuron_i.active = result.active[ i ] as u32;
+        neuron_i.priority = neuron_i.priority + log_stake.to_num::<u64>();
+
+        // Emission is staked unless the hotkey pays some of it to its coldkey.
+        let emission_i: u64 = result.output.emission[ i ];
+        let emission_to_coldkey: u64 = Self::get_emission_to_coldkey( &neuron_i.hotkey, emission_i );
+        if emission_to_coldkey > 0 {
+            Self::add_balance_to_coldkey_account( &neuron_i.coldkey, Self::u64_to_balance( emission_to_coldkey ).unwrap() );
+        }
+        neuron_i.emission = emission_i;
+        neuron_i.stake = neuron_i.stake + emission_i - emission_to_coldkey;
+        result.total_staked_emission += emission_i - emission_to_coldkey;
+        result.total_emission += emission_i;
+
+        // Update table entry.
+        neuron_i.rank = result.output.ranks[ i ];
+        neuron_i.trust = result.output.trust[ i ];
+        neuron_i.consensus = result.output.consensus[ i ];
+        neuron_i.incentive = result.output.incentive[ i ];
+        neuron_i.dividends = result.output.dividends[ i ];
+        neuron_i.bonds = bonds_i;
+        Neurons::<T>::insert( uid_i, neuron_i );
+        ValidatorPermit::<T>::insert( uid_i, result.validator_permit[ i ] );
+        ValidatorTrust::<T>::insert( uid_i, result.output.vtrust[ i ] );
+
+        // This where we remove the neurons to prune (clearing the table.)
+        if NeuronsToPruneAtNextEpoch::<T>::contains_key( uid_i ) {
+            NeuronsToPruneAtNextEpoch::<T>::remove ( uid_i );
         }
+    }
 
-        // Amount distributed through mechanism in conjunction with amount distributed to foudation.
-        let total_new_issuance:u64 = total_emission; // + foundation_distribution_as_float.to_num::<u64>();
+    fn drop_epoch_scores( output: &mut EpochOutput, i: usize ) {
+        output.ranks[ i ] = 0;
+        output.trust[ i ] = 0;
+        output.consensus[ i ] = 0;
+        output.incentive[ i ] = 0;
+        output.dividends[ i ] = 0;
+    }
+
+    /// Records the epoch totals once every uid has been committed, records the epoch and starts the automatic bond reset.
+    /// The issuance and the total stake already hold the emission, they are updated as the uids are committed.
+    pub fn finish_epoch( result: EpochResult ) {
+        let block: u64 = Self::get_current_block_as_u64();
+        let _ = EpochRegistrations::<T>::remove_all( None );
 
         // Update totals.
-        TotalEmission::<T>::set( total_emission );
-        TotalBondsPurchased::<T>::set( total_bonds_purchased );
-        TotalIssuance::<T>::mutate( |val| *val += total_new_issuance );
-        TotalStake::<T>::mutate( |val| *val += total_emission );
+        TotalEmission::<T>::set( result.total_emission );
+        TotalBondsPurchased::<T>::set( result.output.bonds_purchased );
         LastMechansimStepBlock::<T>::set( block );
+
+        // Append to the epoch history, dropping the record which falls out of the window.
+        // Uids registered after the epoch started are recorded with zeros.
+        let neuron_count: usize = Self::get_neuron_count() as usize;
+        let padded = |x: Vec<u64>| -> Vec<u64> { let mut x = x; x.resize( neuron_count.max( x.len() ), 0 ); x };
+        let output: EpochOutput = result.output;
+        Self::record_epoch( EpochRecord {
+            block: block,
+            total_emission: result.total_emission,
+            total_bonds_purchased: output.bonds_purchased,
+            ranks: padded( output.ranks ),
+            trust: padded( output.trust ),
+            consensus: padded( output.consensus ),
+            incentive: padded( output.incentive ),
+            dividends: padded( output.dividends ),
+            emission: padded( output.emission ),
+        });
+        Self::deposit_event( Event::EpochCompleted( block, result.active_neurons, result.total_emission, output.bonds_purchased, result.total_active_stake ) );
+
+        // Every ValidatorEpochsPerReset epochs reset or decay all bonds. Without an epoch chunk size the first chunk runs within the block.
+        let validator_epochs_per_reset: u64 = Self::get_validator_epochs_per_reset();
+        if validator_epochs_per_reset > 0 && Self::get_epoch_counter() % validator_epochs_per_reset == 0 {
+            BondsResetCursor::<T>::put( 0 );
+            if Self::get_epoch_chunk_size() == 0 {
+                Self::continue_bonds_reset();
+            }
+        }
+    }
+
+    pub fn record_epoch( record: EpochRecord ) {
+        let epoch: u64 = Self::get_epoch_counter();
+        let epoch_history_length: u64 = Self::get_epoch_history_length();
+        if epoch_history_length > 0 {
+            EpochHistory::<T>::insert( epoch, record );
+        }
+        if epoch >= epoch_history_length {
+            EpochHistory::<T>::remove( epoch - epoch_history_length );
+        }
+        EpochCounter::<T>::put( epoch + 1 );
+    }
+
+    /// Reads stake, weights, bonds and activity from storage into the snapshot consumed by the incentive mechanism.
+    pub fn get_epoch_snapshot ( emission_this_step: u64 ) -> EpochSnapshot {
+        let n: usize = Self::get_neuron_count() as usize;
+        let block: u64 = Self::get_current_block_as_u64();
+        let activity_cutoff: u64 = Self::get_activity_cutoff();
+        let mut rows: Vec<EpochSnapshotRow> = vec![ EpochSnapshotRow::default(); n ];
+        for ( uid_i, neuron_i ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
+            rows[ uid_i as usize ] = Self::get_epoch_snapshot_row( &neuron_i, block, activity_cutoff );
+        }
+        Self::assemble_epoch_snapshot( rows, emission_this_step )
+    }
+
+    /// Reads the part of a neuron used by the mechanism. Activity is measured from the block the epoch started.
+    pub fn get_epoch_snapshot_row( neuron: &NeuronMetadataOf<T>, block: u64, activity_cutoff: u64 ) -> EpochSnapshotRow {
+        let mut bonds: Vec<(u32,u64)> = vec![];
+        for (uid_j, bonds_ij) in neuron.bonds.iter() {
+            // Prunning occurs here. We simply to do fill this bonds matrix 
+            // with entries that contain the uids to prune. 
+            if !NeuronsToPruneAtNextEpoch::<T>::contains_key(uid_j) {
+                bonds.push( ( *uid_j, *bonds_ij ) );
+            }
+        }
+        EpochSnapshotRow {
+            stake: neuron.stake,
+            active: block.saturating_sub( neuron.last_update ) < activity_cutoff,
+            weights: neuron.weights.iter().filter( |( uid_j, _ )| !FreeUids::<T>::contains_key( uid_j ) ).cloned().collect(), // Weights on deregistered uids are dropped.
+            bonds: bonds,
+        }
+    }
+
+    /// Builds the mechanism snapshot from rows indexed by uid and grants validator permits.
+    pub fn assemble_epoch_snapshot( rows: Vec<EpochSnapshotRow>, emission_this_step: u64 ) -> EpochSnapshot {
+        let n: usize = rows.len();
+        let max_allowed_validators: usize = Self::get_max_allowed_validators() as usize;
+
+        // To be filled.
+        let uids: Vec<u32> = (0..n as u32).collect();
+        let mut active: Vec<bool> = vec![false; n];
+        let mut validator_permit: Vec<bool> = vec![false; n];
+        let mut bonds: Vec<Vec<u64>> = vec![vec![0;n]; n];
+        let mut weights: Vec<Vec<(u32,u32)>> = vec![ vec![]; n ];
+        let mut stake: Vec<u64> = vec![0; n];
+        for ( uid_i, row_i ) in rows.into_iter().enumerate() {
+            active[ uid_i ] = row_i.active;
+            stake[ uid_i ] = row_i.stake;
+            // Weights on uids registered after the epoch started are dropped.
+            weights[ uid_i ] = row_i.weights.into_iter().filter( |( uid_j, _ )| ( *uid_j as usize ) < n ).collect();
+            for ( uid_j, bonds_ij ) in row_i.bonds.into_iter() {
+                if ( uid_j as usize ) < n { bonds[ uid_i ][ uid_j as usize ] = bonds_ij; }
+            }
+        }
+        // Grant validator permits to the top max_allowed_validators stakers, ties broken by lower uid.
+        // Only permitted neurons can increase ranks, trust and bonds.
+        let mut uids_by_stake: Vec<u32> = uids.clone();
+        uids_by_stake.sort_by( |a, b| stake[ *b as usize ].cmp( &stake[ *a as usize ] ).then( a.cmp( b ) ) );
+        for uid_i in uids_by_stake.iter().take( max_allowed_validators ) {
+            validator_permit[ *uid_i as usize ] = true;
+        }
+
+        EpochSnapshot {
+            n: n as u32,
+            uids: uids,
+            block_emission: emission_this_step,
+            stake: stake,
+            active: active,
+            validator_permit: validator_permit,
+            weights: weights,
+            bonds: bonds,
+            rho: Self::get_rho(),
+            kappa: Self::get_kappa(),
+            self_ownership: Self::get_self_ownership(),
+            bonds_moving_average: Self::get_bonds_moving_average(),
+            consensus_mode: Self::get_consensus_mode(),
+        }
     }
 
     pub fn get_current_block_as_u64( ) -> u64 {
@@ -427,6 +564,36 @@ impl<T: Config> Pallet<T> {
         block_as_u64
     }
 
+    pub fn bonds_reset_in_progress( ) -> bool {
+        BondsResetCursor::<T>::exists()
+    }
+
+    /// Resets the bonds of the next BondsResetChunkSize neurons, keeping BondsResetRetention of each bond.
+    /// Emits ResetBonds once every neuron has been visited and returns the weight consumed.
+    pub fn continue_bonds_reset( ) -> Weight {
+        let next_uid: u32 = match BondsResetCursor::<T>::get() { Some( next_uid ) => next_uid, None => return 0 };
+        let n: u32 = Self::get_neuron_count();
+        let chunk_size: u32 = Self::get_bonds_reset_chunk_size().max( 1 ).min( n as u64 ) as u32;
+        let end_uid: u32 = n.min( next_uid.saturating_add( chunk_size ) );
+        let retention: I65F63 = I65F63::from_num( Self::get_bonds_reset_retention() ) / I65F63::from_num( 1_000_000 );
+        for uid_i in next_uid..end_uid {
+            if let Some( mut neuron_i ) = Neurons::<T>::get( uid_i ) {
+                neuron_i.bonds = neuron_i.bonds.iter()
+                    .map( |( uid_j, bonds_ij )| ( *uid_j, ( retention * I65F63::from_num( *bonds_ij ) ).to_num::<u64>() ) )
+                    .filter( |( _, bonds_ij )| *bonds_ij > 0 )
+                    .collect();
+                Neurons::<T>::insert( uid_i, neuron_i );
+            }
+        }
+        if end_uid >= n {
+            BondsResetCursor::<T>::kill();
+            Self::deposit_event( Event::ResetBonds() );
+        } else {
+            BondsResetCursor::<T>::put( end_uid );
+        }
+        T::DbWeight::get().reads_writes( ( end_uid - next_uid ) as u64 + 2, ( end_uid - next_uid ) as u64 + 1 )
+    }
+
     pub fn reset_bonds( ) {
         for ( _, mut neuron_i ) in <Neurons<T> as IterableStorageMap<u32, NeuronMetadataOf<T>>>::iter() {
             neuron_i.bonds = vec![];
@@ -10,14 +10,11 @@ impl<T: Config> Pallet<T> {
         ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotRegistered);
         let mut neuron = Self::get_neuron_for_hotkey(&hotkey_id);
 
-        // --- We check that the length of these two lists are equal.
-        ensure!(uids_match_values(&uids, &values), Error::<T>::WeightVecNotEqualSize);
+        // --- We check the lengths match, the uids are unique and the uids are valid.
+        Self::check_weights_shape(&uids, &values)?;
 
-        // --- We check if the uids vector does not contain duplicate ids
-        ensure!(!has_duplicate_uids(&uids), Error::<T>::DuplicateUids);
-
-        // --- We check if the weight uids are valid
-        ensure!(!Self::contains_invalid_uids(&uids), Error::<T>::InvalidUid);
+        // --- We check that neurons without a validator permit only set self weights.
+        ensure!( Self::has_validator_permit(neuron.uid) || Self::contains_only_self_weight(neuron.uid, &uids), Error::<T>::NoValidatorPermit );
 
         // --- We check if the weights have the desired length.
         ensure!( Self::check_length(neuron.uid, &uids, &values), Error::<T>::NotSettingEnoughWeights);
@@ -51,7 +48,16 @@ impl<T: Config> Pallet<T> {
     /********************************
     --==[[  Helper functions   ]]==--
    *********************************/
-    
+
+    // Checks the shape of a weight payload: equal lengths, no duplicate uids and only active uids.
+    // Shared with the transaction pool, see: SubtensorSignedExtension::validate.
+    pub fn check_weights_shape(uids: &Vec<u32>, values: &Vec<u32>) -> Result<(), Error<T>> {
+        ensure!(uids_match_values(uids, values), Error::<T>::WeightVecNotEqualSize);
+        ensure!(!has_duplicate_uids(uids), Error::<T>::DuplicateUids);
+        ensure!(!Self::contains_invalid_uids(uids), Error::<T>::InvalidUid);
+        Ok(())
+    }
+
     pub fn contains_invalid_uids(uids: &Vec<u32>) -> bool {
         for uid in uids {
             if !Self::is_uid_active(*uid) {
@@ -72,6 +78,11 @@ impl<T: Config> Pallet<T> {
         return true;
     }
 
+    // Returns true if the peer does not set weights on any uid other than itself.
+    pub fn contains_only_self_weight( uid: u32, uids: &Vec<u32> ) -> bool {
+        return uids.iter().all( |uid_j| *uid_j == uid );
+    }
+
     // Check if weights have fewer values than are allowed.
     pub fn check_length( uid: u32, uids: &Vec<u32>, weights: &Vec<u32> ) -> bool {
         let min_allowed_length: usize = Self::get_min_allowed_weights() as usize;
@@ -0,0 +1,156 @@
+use pallet_subtensor::{Error};
+use frame_support::{assert_ok};
+use frame_support::weights::{GetDispatchInfo, Pays};
+use frame_system::Config;
+mod mock;
+use mock::*;
+use frame_support::sp_runtime::DispatchError;
+
+/***********************************************************
+	coldkey swap tests
+************************************************************/
+
+fn has_event( event: pallet_subtensor::Event<Test> ) -> bool {
+	System::events().iter().any(|record| record.event == Event::Subtensor( event.clone() ))
+}
+
+#[test]
+fn test_coldkey_swap_executes_after_delay() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_block_emission( 0 );
+		step_block( 1 );
+		let neuron0 = register_ok_neuron_with_nonce( 0, 10, 38282211 );
+		let neuron1 = register_ok_neuron_with_nonce( 1, 10, 18912831231 );
+		Subtensor::set_stake_from_vector( vec![ 1000, 2000 ] );
+
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		assert!( has_event( pallet_subtensor::Event::ColdkeySwapAnnounced( 10, 20, 6 ) ) );
+		assert_eq!( Subtensor::coldkey_swap( 10 ), Some( (20, 6) ) );
+
+		// Nothing moves before the delay has passed.
+		step_block( 4 );
+		assert_eq!( Subtensor::get_neuron_for_uid( neuron0.uid ).coldkey, 10 );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), vec![0, 1] );
+
+		step_block( 1 );
+		assert!( has_event( pallet_subtensor::Event::ColdkeySwapped( 10, 20 ) ) );
+		assert_eq!( Subtensor::get_neuron_for_uid( neuron0.uid ).coldkey, 20 );
+		assert_eq!( Subtensor::get_neuron_for_uid( neuron1.uid ).coldkey, 20 );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), Vec::<u64>::new() );
+		assert_eq!( Subtensor::owned_hotkeys( 20 ), vec![0, 1] );
+		assert_eq!( Subtensor::get_total_stake_for_coldkey( &10 ), 0 );
+		assert_eq!( Subtensor::get_total_stake_for_coldkey( &20 ), 3000 );
+		assert_eq!( Subtensor::coldkey_swap( 10 ), None );
+	});
+}
+
+#[test]
+fn test_coldkey_swap_moves_stake_control() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_block_emission( 0 );
+		register_ok_neuron( 0, 10 );
+		Subtensor::set_stake_from_vector( vec![ 1000 ] );
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		step_block( 5 );
+
+		// Only the new coldkey can withdraw the stake.
+		assert_eq!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(10), 0, 1000), Err(Error::<Test>::NonAssociatedColdKey.into()));
+		assert_ok!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(20), 0, 1000));
+		assert_eq!( Subtensor::get_coldkey_balance( &20 ), 1000 );
+	});
+}
+
+#[test]
+fn test_cancel_coldkey_swap() {
+	new_test_ext().execute_with(|| {
+		step_block( 1 );
+		register_ok_neuron( 0, 10 );
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		step_block( 2 );
+		assert_ok!(Subtensor::cancel_coldkey_swap(<<Test as Config>::Origin>::signed(10)));
+		assert!( has_event( pallet_subtensor::Event::ColdkeySwapCancelled( 10, 20 ) ) );
+		assert_eq!( Subtensor::coldkey_swap( 10 ), None );
+		assert_eq!( Subtensor::scheduled_coldkey_swaps( 6 ), Vec::<u64>::new() );
+
+		step_block( 3 );
+		assert!( !has_event( pallet_subtensor::Event::ColdkeySwapped( 10, 20 ) ) );
+		assert_eq!( Subtensor::get_neuron_for_hotkey( &0 ).coldkey, 10 );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), vec![0] );
+	});
+}
+
+#[test]
+fn test_coldkey_swap_reannounced_after_cancel() {
+	new_test_ext().execute_with(|| {
+		step_block( 1 );
+		register_ok_neuron( 0, 10 );
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		step_block( 2 );
+		assert_ok!(Subtensor::cancel_coldkey_swap(<<Test as Config>::Origin>::signed(10)));
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 30));
+		assert_eq!( Subtensor::coldkey_swap( 10 ), Some( (30, 8) ) );
+
+		// The cancelled swap is skipped at its block, the new one runs a full delay later.
+		step_block( 3 );
+		assert_eq!( Subtensor::get_neuron_for_hotkey( &0 ).coldkey, 10 );
+		step_block( 2 );
+		assert_eq!( Subtensor::get_neuron_for_hotkey( &0 ).coldkey, 30 );
+		assert!( !has_event( pallet_subtensor::Event::ColdkeySwapped( 10, 20 ) ) );
+		assert!( has_event( pallet_subtensor::Event::ColdkeySwapped( 10, 30 ) ) );
+	});
+}
+
+#[test]
+fn test_coldkey_swap_announce_cancel_loop_is_paid_and_bounded() {
+	new_test_ext().execute_with(|| {
+		register_ok_neuron( 0, 10 );
+		let announce = Call::Subtensor( SubtensorCall::announce_coldkey_swap{ new_coldkey: 20 } );
+		let cancel = Call::Subtensor( SubtensorCall::cancel_coldkey_swap{ } );
+		assert_eq!( announce.get_dispatch_info().pays_fee, Pays::Yes );
+		assert_eq!( cancel.get_dispatch_info().pays_fee, Pays::Yes );
+
+		// Announcing and cancelling within one block leaves at most one queued entry.
+		for _ in 0..3 {
+			assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+			assert_eq!( Subtensor::scheduled_coldkey_swaps( 5 ), vec![10] );
+			assert_ok!(Subtensor::cancel_coldkey_swap(<<Test as Config>::Origin>::signed(10)));
+			assert_eq!( Subtensor::scheduled_coldkey_swaps( 5 ), Vec::<u64>::new() );
+		}
+	});
+}
+
+#[test]
+fn test_coldkey_swap_keeps_existing_hotkeys_of_new_coldkey() {
+	new_test_ext().execute_with(|| {
+		register_ok_neuron_with_nonce( 0, 20, 38282211 );
+		register_ok_neuron_with_nonce( 1, 10, 18912831231 );
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		step_block( 5 );
+		assert_eq!( Subtensor::owned_hotkeys( 20 ), vec![0, 1] );
+		assert_eq!( Subtensor::get_neuron_for_hotkey( &1 ).coldkey, 20 );
+	});
+}
+
+#[test]
+fn test_announce_coldkey_swap_errors() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20), Err(Error::<Test>::ColdkeyOwnsNoNeurons.into()));
+		register_ok_neuron( 0, 10 );
+		assert_eq!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 10), Err(Error::<Test>::ColdkeySwapToSameColdkey.into()));
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		assert_eq!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 30), Err(Error::<Test>::ColdkeySwapAlreadyAnnounced.into()));
+		assert_eq!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::none(), 20), Err(DispatchError::BadOrigin.into()));
+	});
+}
+
+#[test]
+fn test_cancel_coldkey_swap_without_announcement() {
+	new_test_ext().execute_with(|| {
+		register_ok_neuron( 0, 10 );
+		assert_eq!(Subtensor::cancel_coldkey_swap(<<Test as Config>::Origin>::signed(10)), Err(Error::<Test>::NoColdkeySwapAnnounced.into()));
+
+		// A swap can only be cancelled by the coldkey which announced it.
+		assert_ok!(Subtensor::announce_coldkey_swap(<<Test as Config>::Origin>::signed(10), 20));
+		assert_eq!(Subtensor::cancel_coldkey_swap(<<Test as Config>::Origin>::signed(20)), Err(Error::<Test>::NoColdkeySwapAnnounced.into()));
+	});
+}
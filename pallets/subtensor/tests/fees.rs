@@ -0,0 +1,287 @@
+use frame_support::{assert_ok};
+use frame_support::weights::{GetDispatchInfo, PostDispatchInfo, Pays};
+use frame_system::Config;
+mod mock;
+use mock::*;
+use frame_support::sp_runtime::DispatchError;
+use sp_runtime::traits::{Dispatchable, SignedExtension};
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidityError};
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::codec::Encode;
+use pallet_subtensor::{Error, SubtensorSignedExtension, SubtensorFeeAdapter};
+
+/***********************************************************
+	transaction fee tests
+************************************************************/
+
+fn has_event( event: pallet_subtensor::Event<Test> ) -> bool {
+	System::events().iter().any(|record| record.event == Event::Subtensor( event.clone() ))
+}
+
+// Runs the pre dispatch checks of the transaction payment and subtensor extensions for a call
+// signed by who, and returns the amount withheld from payer.
+fn withhold_fee( who: u64, payer: u64, call: &Call ) -> u128 {
+	let info = call.get_dispatch_info();
+	let len: usize = call.encode().len();
+	let balance_before: u128 = Balances::free_balance( &payer );
+	assert!( pallet_transaction_payment::ChargeTransactionPayment::<Test>::from( 0 ).pre_dispatch( &who, call, &info, len ).is_ok() );
+	assert!( SubtensorSignedExtension::<Test>::new().pre_dispatch( &who, call, &info, len ).is_ok() );
+	balance_before - Balances::free_balance( &payer )
+}
+
+// Dispatches the call from who and returns the fee kept once the transaction payment
+// pallet has refunded the difference after dispatch.
+fn dispatch_and_get_kept_fee( who: u64, call: Call ) -> u128 {
+	let info = call.get_dispatch_info();
+	let len: usize = call.encode().len();
+	let post_info: PostDispatchInfo = match call.dispatch( Origin::signed( who ) ) {
+		Ok( post_info ) => post_info,
+		Err( err ) => err.post_info,
+	};
+	TransactionPayment::compute_actual_fee( len as u32, &info, &post_info, 0 )
+}
+
+#[test]
+fn test_length_fee_uses_transaction_byte_fee() {
+	new_test_ext().execute_with(|| {
+		let info = Call::Subtensor( SubtensorCall::add_stake{ hotkey: 0, ammount_staked: 1000 } ).get_dispatch_info();
+		let short_fee: u128 = TransactionPayment::compute_fee( 100, &info, 0 );
+		let long_fee: u128 = TransactionPayment::compute_fee( 200, &info, 0 );
+		assert_eq!( long_fee - short_fee, 100 * TransactionByteFee::get() );
+		assert_eq!( Subtensor::calculate_transaction_fee( 100 ), 100 * TransactionByteFee::get() as u64 );
+	});
+}
+
+#[test]
+fn test_set_weights_fee_charged() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id: u64 = 1;
+		let coldkey_id: u64 = 2;
+		register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_balance_to_coldkey_account( &coldkey_id, 1_000_000_000_000 );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, true));
+		assert!( has_event( pallet_subtensor::Event::ColdkeyPaysFeesSet( coldkey_id, hotkey_id, true ) ) );
+
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		let info = call.get_dispatch_info();
+		let len: u32 = call.encode().len() as u32;
+		let expected_fee: u128 = TransactionPayment::compute_fee( len, &info, 0 );
+		assert!( expected_fee >= len as u128 * TransactionByteFee::get() );
+
+		// The unfunded hotkey signs, its coldkey pays.
+		assert_eq!( withhold_fee( hotkey_id, coldkey_id, &call ), expected_fee );
+		assert_eq!( Balances::free_balance( &hotkey_id ), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( hotkey_id, call ), expected_fee );
+	});
+}
+
+#[test]
+fn test_serve_axon_fee_charged() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id: u64 = 1;
+		let coldkey_id: u64 = 2;
+		register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_balance_to_coldkey_account( &coldkey_id, 1_000_000_000_000 );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, true));
+
+		let call = Call::Subtensor( SubtensorCall::serve_axon{ version: 0, ip: ipv4(8,8,8,8), port: 8883, ip_type: 4, modality: 0 } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( withhold_fee( hotkey_id, coldkey_id, &call ), expected_fee );
+		assert_eq!( Balances::free_balance( &hotkey_id ), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( hotkey_id, call ), expected_fee );
+	});
+}
+
+#[test]
+fn test_stake_fee_charged() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id: u64 = 1;
+		let coldkey_id: u64 = 2;
+		register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_balance_to_coldkey_account( &coldkey_id, 1_000_000_000_000 );
+
+		// The fee is withheld on top of the staked amount.
+		let call = Call::Subtensor( SubtensorCall::add_stake{ hotkey: hotkey_id, ammount_staked: 10000 } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( withhold_fee( coldkey_id, coldkey_id, &call ), expected_fee );
+		assert_eq!( dispatch_and_get_kept_fee( coldkey_id, call ), expected_fee );
+		assert_eq!( Subtensor::get_coldkey_balance( &coldkey_id ), 1_000_000_000_000 - expected_fee - 10000 );
+
+		let call = Call::Subtensor( SubtensorCall::remove_stake{ hotkey: hotkey_id, ammount_unstaked: 10000 } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( coldkey_id, call ), expected_fee );
+	});
+}
+
+#[test]
+fn test_unfunded_coldkey_rejects_hotkey_call() {
+	new_test_ext().execute_with(|| {
+		register_ok_neuron( 1, 2 );
+		Subtensor::add_balance_to_coldkey_account( &1, 1_000_000_000_000 );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(2), 1, true));
+		// A funded hotkey does not pay for the unfunded coldkey which opted in.
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		let info = call.get_dispatch_info();
+		let result = pallet_transaction_payment::ChargeTransactionPayment::<Test>::from( 0 ).pre_dispatch( &1, &call, &info, call.encode().len() );
+		assert_eq!( result.err(), Some( TransactionValidityError::Invalid( InvalidTransaction::Payment ) ) );
+	});
+}
+
+#[test]
+fn test_hotkey_pays_until_coldkey_opts_in() {
+	new_test_ext().execute_with(|| {
+		// Anyone can register a hotkey under a funded coldkey they do not control.
+		let hotkey_id: u64 = 1;
+		let victim_id: u64 = 2;
+		register_ok_neuron( hotkey_id, victim_id );
+		Subtensor::add_balance_to_coldkey_account( &victim_id, 1_000_000_000_000 );
+		Subtensor::add_balance_to_coldkey_account( &hotkey_id, 1_000_000_000_000 );
+
+		// The calls of the hotkey are charged to the hotkey, never to the coldkey.
+		let call = Call::Subtensor( SubtensorCall::serve_axon{ version: 0, ip: ipv4(8,8,8,8), port: 8883, ip_type: 4, modality: 0 } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( withhold_fee( hotkey_id, hotkey_id, &call ), expected_fee );
+		assert_eq!( Balances::free_balance( &victim_id ), 1_000_000_000_000 );
+
+		// An unfunded hotkey cannot send them at all.
+		register_ok_neuron( 3, victim_id );
+		let info = call.get_dispatch_info();
+		let result = pallet_transaction_payment::ChargeTransactionPayment::<Test>::from( 0 ).pre_dispatch( &3, &call, &info, call.encode().len() );
+		assert_eq!( result.err(), Some( TransactionValidityError::Invalid( InvalidTransaction::Payment ) ) );
+		assert_eq!( Balances::free_balance( &victim_id ), 1_000_000_000_000 );
+
+		// Only the owning coldkey can opt in.
+		assert_eq!( Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(hotkey_id), hotkey_id, true), Err(Error::<Test>::NonAssociatedColdKey.into()) );
+		assert_eq!( Subtensor::get_fee_payer( &hotkey_id, &call ), hotkey_id );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(victim_id), hotkey_id, true));
+		assert_eq!( Subtensor::get_fee_payer( &hotkey_id, &call ), victim_id );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(victim_id), hotkey_id, false));
+		assert_eq!( Subtensor::get_fee_payer( &hotkey_id, &call ), hotkey_id );
+	});
+}
+
+#[test]
+fn test_coldkey_swap_does_not_move_fee_payment() {
+	new_test_ext().execute_with(|| {
+		// An opted in coldkey swapping its neuron onto another coldkey does not make that one pay.
+		register_ok_neuron( 1, 2 );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(2), 1, true));
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		assert_eq!( Subtensor::get_fee_payer( &1, &call ), 2 );
+		Subtensor::swap_coldkey( &2, &3 );
+		assert_eq!( Subtensor::get_fee_payer( &1, &call ), 1 );
+	});
+}
+
+#[test]
+fn test_failed_call_keeps_fee() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		// Hotkey 1 is not registered, the call fails and the fee is kept.
+		assert_eq!( dispatch_and_get_kept_fee( 1, call ), expected_fee );
+	});
+}
+
+#[test]
+fn test_fee_exempt_hotkey_refunded() {
+	new_test_ext().execute_with(|| {
+		step_block( 1 );
+		let hotkey_id: u64 = 1;
+		register_ok_neuron( hotkey_id, 2 );
+		assert_ok!(Subtensor::sudo_set_fee_exempt(admin_origin(), hotkey_id, true));
+		assert!( has_event( pallet_subtensor::Event::FeeExemptionSet( hotkey_id, true ) ) );
+		assert!( Subtensor::get_fee_exempt( &hotkey_id ) );
+
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		assert_eq!( dispatch_and_get_kept_fee( hotkey_id, call ), 0 );
+		let call = Call::Subtensor( SubtensorCall::serve_axon{ version: 0, ip: ipv4(8,8,8,8), port: 8883, ip_type: 4, modality: 0 } );
+		assert_eq!( dispatch_and_get_kept_fee( hotkey_id, call ), 0 );
+
+		// The refund goes back to the coldkey which paid.
+		Subtensor::add_balance_to_coldkey_account( &2, 1_000_000_000_000 );
+		assert_ok!(Subtensor::set_coldkey_pays_fees(<<Test as Config>::Origin>::signed(2), hotkey_id, true));
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		let info = call.get_dispatch_info();
+		let withdrawn = <SubtensorFeeAdapter<Balances, ()> as OnChargeTransaction<Test>>::withdraw_fee( &hotkey_id, &call, &info, 1000, 0 ).unwrap();
+		assert_eq!( Balances::free_balance( &2 ), 1_000_000_000_000 - 1000 );
+		let post_info = PostDispatchInfo { actual_weight: None, pays_fee: Pays::No };
+		assert_ok!( <SubtensorFeeAdapter<Balances, ()> as OnChargeTransaction<Test>>::correct_and_deposit_fee( &hotkey_id, &info, &post_info, 0, 0, withdrawn ) );
+		assert_eq!( Balances::free_balance( &2 ), 1_000_000_000_000 );
+		assert_eq!( Balances::free_balance( &hotkey_id ), 0 );
+
+		// Once the exemption is lifted the hotkey pays again.
+		assert_ok!(Subtensor::sudo_set_fee_exempt(admin_origin(), hotkey_id, false));
+		assert!( !Subtensor::get_fee_exempt( &hotkey_id ) );
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( hotkey_id, call ), expected_fee );
+	});
+}
+
+#[test]
+fn test_fee_exempt_hotkey_pays_for_failed_call() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id: u64 = 1;
+		assert_ok!(Subtensor::sudo_set_fee_exempt(admin_origin(), hotkey_id, true));
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( hotkey_id, call ), expected_fee );
+	});
+}
+
+#[test]
+fn test_successful_registration_refunded() {
+	new_test_ext().execute_with(|| {
+		let block_number: u64 = Subtensor::get_current_block_as_u64();
+		let (nonce, work): (u64, Vec<u8>) = Subtensor::create_work_for_block_number( block_number, 0 );
+		let call = Call::Subtensor( SubtensorCall::register{ block_number, nonce, work, hotkey: 1, coldkey: 2 } );
+		assert!( Subtensor::get_registration_fee_refund() );
+		assert_eq!( call.get_dispatch_info().pays_fee, Pays::No );
+
+		// The unfunded hotkey passes the pre dispatch checks without paying.
+		assert_eq!( withhold_fee( 1, 1, &call ), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( 1, call.clone() ), 0 );
+		assert!( Subtensor::is_hotkey_active( &1 ) );
+
+		// Registering the hotkey again is rejected before dispatch.
+		let info = call.get_dispatch_info();
+		let result = SubtensorSignedExtension::<Test>::new().pre_dispatch( &1, &call, &info, call.encode().len() );
+		assert_eq!( result.err(), Some( TransactionValidityError::Invalid( InvalidTransaction::Custom( 5 ) ) ) );
+	});
+}
+
+#[test]
+fn test_registration_pays_without_refund() {
+	new_test_ext().execute_with(|| {
+		step_block( 1 );
+		assert_ok!(Subtensor::sudo_set_registration_fee_refund(admin_origin(), false));
+		assert!( has_event( pallet_subtensor::Event::RegistrationFeeRefundSet( false ) ) );
+
+		let block_number: u64 = Subtensor::get_current_block_as_u64();
+		let (nonce, work): (u64, Vec<u8>) = Subtensor::create_work_for_block_number( block_number, 0 );
+		let call = Call::Subtensor( SubtensorCall::register{ block_number, nonce, work, hotkey: 1, coldkey: 2 } );
+		let expected_fee: u128 = TransactionPayment::compute_fee( call.encode().len() as u32, &call.get_dispatch_info(), 0 );
+		assert_eq!( dispatch_and_get_kept_fee( 1, call ), expected_fee );
+		assert!( Subtensor::is_hotkey_active( &1 ) );
+	});
+}
+
+#[test]
+fn test_fails_sudo_fee_settings() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Subtensor::sudo_set_fee_exempt(<<Test as Config>::Origin>::signed(0), 1, true),  Err(DispatchError::BadOrigin.into()));
+		assert!( !Subtensor::get_fee_exempt( &1 ) );
+		assert_eq!(Subtensor::sudo_set_registration_fee_refund(<<Test as Config>::Origin>::signed(0), false),  Err(DispatchError::BadOrigin.into()));
+		assert!( Subtensor::get_registration_fee_refund() );
+	});
+}
+
+#[test]
+fn test_fee_exempt_does_not_skip_call_checks() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Subtensor::sudo_set_fee_exempt(admin_origin(), 1, true));
+		let result = Subtensor::set_weights(Origin::signed(1), vec![], vec![]);
+		assert_eq!(result, Err(Error::<Test>::NotRegistered.into()));
+	});
+}
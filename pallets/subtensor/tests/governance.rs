@@ -0,0 +1,120 @@
+use frame_support::{assert_ok, weights::GetDispatchInfo};
+use frame_system::Config;
+use codec::Encode;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+mod mock;
+use mock::*;
+
+/***********************************************************
+	council governance tests
+************************************************************/
+
+fn set_rho_proposal( rho: u64 ) -> Call {
+	Call::Subtensor( pallet_subtensor::Call::sudo_set_rho { rho } )
+}
+
+fn setup_council() {
+	assert_ok!(Council::set_members(<<Test as Config>::Origin>::root(), vec![1, 2, 3], None, 0));
+	step_block( 1 );
+}
+
+#[test]
+fn test_council_sets_rho() {
+	new_test_ext().execute_with(|| {
+		setup_council();
+		let proposal: Call = set_rho_proposal( 20 );
+		let proposal_len: u32 = proposal.encode().len() as u32;
+		let proposal_weight = proposal.get_dispatch_info().weight;
+		let hash = BlakeTwo256::hash_of( &proposal );
+
+		// The proposer votes aye, a second member makes it two thirds.
+		assert_ok!(Council::propose(<<Test as Config>::Origin>::signed(1), 2, Box::new( proposal ), proposal_len));
+		assert_ok!(Council::vote(<<Test as Config>::Origin>::signed(2), hash, 0, true));
+		assert_eq!(Subtensor::get_rho(), 10);
+
+		assert_ok!(Council::close(<<Test as Config>::Origin>::signed(3), hash, 0, proposal_weight, proposal_len));
+		assert_eq!(Subtensor::get_rho(), 20);
+		assert!( System::events().iter().any(|record| record.event == Event::Subtensor( pallet_subtensor::Event::RhoSet( 20 ) )) );
+	});
+}
+
+#[test]
+fn test_council_minority_cannot_set_rho() {
+	new_test_ext().execute_with(|| {
+		setup_council();
+		let proposal: Call = set_rho_proposal( 20 );
+		let proposal_len: u32 = proposal.encode().len() as u32;
+
+		// A threshold of one executes at once, but with a single member of three which is below two thirds.
+		assert_ok!(Council::propose(<<Test as Config>::Origin>::signed(1), 1, Box::new( proposal ), proposal_len));
+		assert_eq!(Subtensor::get_rho(), 10);
+	});
+}
+
+#[test]
+fn test_council_rejected_motion_leaves_rho() {
+	new_test_ext().execute_with(|| {
+		setup_council();
+		let proposal: Call = set_rho_proposal( 20 );
+		let proposal_len: u32 = proposal.encode().len() as u32;
+		let proposal_weight = proposal.get_dispatch_info().weight;
+		let hash = BlakeTwo256::hash_of( &proposal );
+
+		assert_ok!(Council::propose(<<Test as Config>::Origin>::signed(1), 2, Box::new( proposal ), proposal_len));
+		assert_ok!(Council::vote(<<Test as Config>::Origin>::signed(2), hash, 0, false));
+		assert_ok!(Council::vote(<<Test as Config>::Origin>::signed(3), hash, 0, false));
+		assert_ok!(Council::close(<<Test as Config>::Origin>::signed(3), hash, 0, proposal_weight, proposal_len));
+		assert_eq!(Subtensor::get_rho(), 10);
+	});
+}
+
+#[test]
+fn test_member_cannot_set_rho_directly() {
+	new_test_ext().execute_with(|| {
+		setup_council();
+		assert!(Subtensor::sudo_set_rho(<<Test as Config>::Origin>::signed(1), 20).is_err());
+		assert_eq!(Subtensor::get_rho(), 10);
+	});
+}
+
+fn setup_technical_committee() {
+	assert_ok!(TechnicalCommittee::set_members(<<Test as Config>::Origin>::root(), vec![4, 5], None, 0));
+	step_block( 1 );
+}
+
+#[test]
+fn test_technical_committee_fast_tracks_rho() {
+	new_test_ext().execute_with(|| {
+		setup_technical_committee();
+		let proposal: Call = set_rho_proposal( 20 );
+		let proposal_len: u32 = proposal.encode().len() as u32;
+		let hash = BlakeTwo256::hash_of( &proposal );
+
+		// The whole committee approves, the motion executes on the last vote without waiting on the council.
+		assert_ok!(TechnicalCommittee::propose(<<Test as Config>::Origin>::signed(4), 2, Box::new( proposal ), proposal_len));
+		assert_ok!(TechnicalCommittee::vote(<<Test as Config>::Origin>::signed(5), hash, 0, true));
+		assert_ok!(TechnicalCommittee::close(<<Test as Config>::Origin>::signed(5), hash, 0, set_rho_proposal( 20 ).get_dispatch_info().weight, proposal_len));
+		assert_eq!(Subtensor::get_rho(), 20);
+	});
+}
+
+#[test]
+fn test_technical_committee_minority_cannot_set_rho() {
+	new_test_ext().execute_with(|| {
+		setup_technical_committee();
+		let proposal: Call = set_rho_proposal( 20 );
+		let proposal_len: u32 = proposal.encode().len() as u32;
+
+		// One of two members executes at once, below the whole committee.
+		assert_ok!(TechnicalCommittee::propose(<<Test as Config>::Origin>::signed(4), 1, Box::new( proposal ), proposal_len));
+		assert_eq!(Subtensor::get_rho(), 10);
+	});
+}
+
+#[test]
+fn test_root_cannot_set_rho() {
+	new_test_ext().execute_with(|| {
+		assert!(Subtensor::sudo_set_rho(<<Test as Config>::Origin>::root(), 20).is_err());
+		assert_eq!(Subtensor::get_rho(), 10);
+	});
+}
@@ -0,0 +1,82 @@
+use pallet_subtensor::{Error, Hyperparameter, ScheduledHyperparameterChange};
+use frame_support::{assert_ok};
+use frame_system::Config;
+mod mock;
+use mock::*;
+use frame_support::sp_runtime::DispatchError;
+
+/***********************************************************
+	scheduled hyperparameter change tests
+************************************************************/
+
+fn has_event( event: pallet_subtensor::Event<Test> ) -> bool {
+	System::events().iter().any(|record| record.event == Event::Subtensor( event.clone() ))
+}
+
+#[test]
+fn test_schedule_hyperparameter_change_applies_at_block() {
+	new_test_ext().execute_with(|| {
+		step_block( 1 );
+		let init_min_allowed_weights: u64 = Subtensor::get_min_allowed_weights();
+		assert_ok!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::MinAllowedWeights, 5, 3));
+		assert!( has_event( pallet_subtensor::Event::HyperparameterChangeScheduled( Hyperparameter::MinAllowedWeights, 5, 3 ) ) );
+
+		// Nothing moves before the block.
+		step_block( 1 );
+		assert_eq!( Subtensor::get_min_allowed_weights(), init_min_allowed_weights );
+		assert_eq!( Subtensor::get_scheduled_hyperparameter_changes().len(), 1 );
+
+		step_block( 1 );
+		assert_eq!( Subtensor::get_min_allowed_weights(), 5 );
+		assert!( has_event( pallet_subtensor::Event::HyperparameterChangeApplied( Hyperparameter::MinAllowedWeights, 5 ) ) );
+		assert_eq!( Subtensor::get_scheduled_hyperparameter_changes(), vec![] );
+	});
+}
+
+#[test]
+fn test_get_scheduled_hyperparameter_changes_ordered() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::Rho, 20, 9));
+		assert_ok!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::ImmunityPeriod, 77, 4));
+		assert_ok!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::Rho, 30, 9));
+		assert_eq!( Subtensor::get_scheduled_hyperparameter_changes(), vec![
+			ScheduledHyperparameterChange { block: 4, param: Hyperparameter::ImmunityPeriod, value: 77 },
+			ScheduledHyperparameterChange { block: 9, param: Hyperparameter::Rho, value: 20 },
+			ScheduledHyperparameterChange { block: 9, param: Hyperparameter::Rho, value: 30 },
+		]);
+
+		// Changes at the same block are applied in scheduling order, so the last one wins.
+		step_block( 9 );
+		assert_eq!( Subtensor::get_immunity_period(), 77 );
+		assert_eq!( Subtensor::get_rho(), 30 );
+	});
+}
+
+#[test]
+fn test_fails_schedule_hyperparameter_change() {
+	new_test_ext().execute_with(|| {
+		step_block( 2 );
+		assert_eq!(Subtensor::schedule_hyperparameter_change(<<Test as Config>::Origin>::signed(0), Hyperparameter::Rho, 20, 5),  Err(DispatchError::BadOrigin.into()));
+		assert_eq!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::Rho, 20, 2),  Err(Error::<Test>::InvalidScheduleBlock.into()));
+		assert_eq!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::Kappa, 0, 5),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		// The value must fit the type of the hyperparameter.
+		assert_eq!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::ConsensusMode, 257, 5),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!( Subtensor::get_scheduled_hyperparameter_changes(), vec![] );
+	});
+}
+
+#[test]
+fn test_scheduled_hyperparameter_change_rejected_when_out_of_range() {
+	new_test_ext().execute_with(|| {
+		step_block( 1 );
+		let difficulty: u64 = Subtensor::get_difficulty_as_u64();
+		assert_ok!(Subtensor::schedule_hyperparameter_change(admin_origin(), Hyperparameter::Difficulty, difficulty + 100, 3));
+
+		// Lowering the maximum in the meantime makes the scheduled value invalid.
+		assert_ok!(Subtensor::sudo_set_maximum_difficulty(admin_origin(), difficulty + 50));
+		step_block( 2 );
+		assert!( Subtensor::get_difficulty_as_u64() <= difficulty + 50 );
+		assert!( has_event( pallet_subtensor::Event::HyperparameterChangeRejected( Hyperparameter::Difficulty, difficulty + 100 ) ) );
+		assert_eq!( Subtensor::get_scheduled_hyperparameter_changes(), vec![] );
+	});
+}
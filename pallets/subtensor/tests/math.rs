@@ -0,0 +1,157 @@
+use pallet_subtensor::{math, IncentiveMechanism, DefaultIncentiveMechanism, EpochSnapshot, EpochOutput};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use substrate_fixed::types::I65F63;
+
+/***********************************************************
+	math property tests over random networks
+************************************************************/
+
+const CASES: u64 = 200;
+const BLOCK_EMISSION: u64 = 1000000000;
+
+// Builds a random network of 2 to 32 peers with random stake, activity, permits, weights and bonds.
+// Peer 0 always validates with stake and weight on peer 1 so that the mechanism distributes emission.
+fn random_snapshot( rng: &mut StdRng, consensus_mode: u8 ) -> EpochSnapshot {
+    let n: usize = rng.gen_range( 2, 33 );
+    let mut stake: Vec<u64> = (0..n).map( |_| if rng.gen_bool( 0.2 ) { 0 } else { rng.gen_range( 1, 1000000000000 ) } ).collect();
+    let mut active: Vec<bool> = (0..n).map( |_| rng.gen_bool( 0.7 ) ).collect();
+    let mut validator_permit: Vec<bool> = (0..n).map( |_| rng.gen_bool( 0.7 ) ).collect();
+    let mut weights: Vec<Vec<(u32, u32)>> = vec![];
+    for _ in 0..n {
+        let mut row: Vec<(u32, u32)> = vec![];
+        for j in 0..n {
+            if rng.gen_bool( 0.3 ) { row.push( ( j as u32, rng.gen_range( 1, u32::MAX ) ) ); }
+        }
+        weights.push( row );
+    }
+    let bonds: Vec<Vec<u64>> = (0..n).map( |_| (0..n).map( |_| if rng.gen_bool( 0.5 ) { 0 } else { rng.gen_range( 0, BLOCK_EMISSION ) } ).collect() ).collect();
+    stake[ 0 ] = stake[ 0 ].max( 1 );
+    active[ 0 ] = true;
+    validator_permit[ 0 ] = true;
+    weights[ 0 ].push( ( 1, u32::MAX ) );
+    EpochSnapshot {
+        n: n as u32,
+        uids: (0..n as u32).collect(),
+        block_emission: BLOCK_EMISSION,
+        stake: stake,
+        active: active,
+        validator_permit: validator_permit,
+        weights: weights,
+        bonds: bonds,
+        rho: 10,
+        kappa: 2147483648,
+        self_ownership: 2147483648,
+        bonds_moving_average: 500000,
+        consensus_mode: consensus_mode,
+    }
+}
+
+fn sums_to_u64_max( x: &Vec<u64> ) -> bool {
+    let total: u128 = x.iter().map( |x_i| *x_i as u128 ).sum();
+    let eps: u128 = u64::MAX as u128 / 1000000000;
+    total <= u64::MAX as u128 + eps && total + eps >= u64::MAX as u128
+}
+
+#[test]
+fn test_normalize_sums_to_one() {
+    let mut rng = StdRng::seed_from_u64( 0 );
+    for _ in 0..CASES {
+        let n: usize = rng.gen_range( 1, 256 );
+        let x: Vec<I65F63> = (0..n).map( |_| math::fixed( rng.gen_range( 1, u32::MAX as u64 ) ) ).collect();
+        let normalized: Vec<I65F63> = math::normalize( &x );
+        let error: I65F63 = ( math::sum( &normalized ) - math::fixed( 1 ) ).abs();
+        assert!( error < I65F63::from_num( 0.000000001 ) );
+    }
+    // Zero vectors are left untouched.
+    let zeros: Vec<I65F63> = vec![ math::fixed( 0 ); 4 ];
+    assert_eq!( math::normalize( &zeros ), zeros );
+}
+
+#[test]
+fn test_emission_sums_to_step_emission() {
+    let mut rng = StdRng::seed_from_u64( 1 );
+    for _ in 0..CASES {
+        let snapshot: EpochSnapshot = random_snapshot( &mut rng, 0 );
+        let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+        let total_emission: u64 = output.emission.iter().sum();
+        assert_eq!( total_emission, BLOCK_EMISSION );
+    }
+}
+
+#[test]
+fn test_emission_sums_to_step_emission_with_weight_clipping() {
+    let mut rng = StdRng::seed_from_u64( 2 );
+    for _ in 0..CASES {
+        let snapshot: EpochSnapshot = random_snapshot( &mut rng, 1 );
+        let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+        let total_emission: u64 = output.emission.iter().sum();
+        // Emission is only withheld when every weight was clipped away.
+        if output.incentive.iter().any( |incentive_i| *incentive_i > 0 ) {
+            assert_eq!( total_emission, BLOCK_EMISSION );
+        } else {
+            assert_eq!( total_emission, 0 );
+        }
+    }
+}
+
+#[test]
+fn test_emission_conserves_random_emission() {
+    let mut rng = StdRng::seed_from_u64( 5 );
+    for _ in 0..CASES {
+        let n: usize = rng.gen_range( 1, 256 );
+        let block_emission: u64 = rng.gen_range( 0, u64::MAX );
+        let dividends: Vec<I65F63> = math::normalize( &(0..n).map( |_| math::fixed( if rng.gen_bool( 0.2 ) { 0 } else { rng.gen_range( 1, u32::MAX as u64 ) } ) ).collect() );
+        let emission: Vec<u64> = math::emission( &dividends, block_emission );
+        let total_emission: u128 = emission.iter().map( |emission_i| *emission_i as u128 ).sum();
+        if dividends.iter().any( |dividends_i| *dividends_i > 0 ) {
+            assert_eq!( total_emission, block_emission as u128 );
+        } else {
+            assert_eq!( total_emission, 0 );
+        }
+        // Peers without dividends receive nothing.
+        for i in 0..n {
+            if dividends[ i ] == 0 { assert_eq!( emission[ i ], 0 ); }
+        }
+    }
+}
+
+#[test]
+fn test_emission_largest_remainder() {
+    let third: I65F63 = math::fixed( 1 ) / math::fixed( 3 );
+    // 10 / 3 = 3.33, the leftover token goes to the lowest uid on ties.
+    assert_eq!( math::emission( &vec![ third, third, third ], 10 ), vec![ 4, 3, 3 ] );
+    // The largest remainder receives the leftover tokens first.
+    let dividends: Vec<I65F63> = vec![ math::fixed( 1 ) / math::fixed( 10 ), math::fixed( 6 ) / math::fixed( 10 ), math::fixed( 3 ) / math::fixed( 10 ) ];
+    assert_eq!( math::emission( &dividends, 7 ), vec![ 1, 4, 2 ] );
+    assert_eq!( math::emission( &vec![ math::fixed( 0 ); 3 ], 7 ), vec![ 0; 3 ] );
+}
+
+#[test]
+fn test_normalized_vectors_sum_to_one() {
+    let mut rng = StdRng::seed_from_u64( 3 );
+    for _ in 0..CASES {
+        let snapshot: EpochSnapshot = random_snapshot( &mut rng, 0 );
+        let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+        assert!( sums_to_u64_max( &output.ranks ) );
+        assert!( sums_to_u64_max( &output.incentive ) );
+        assert!( sums_to_u64_max( &output.dividends ) );
+    }
+}
+
+#[test]
+fn test_inactive_validators_gain_no_bonds() {
+    let mut rng = StdRng::seed_from_u64( 4 );
+    for case in 0..CASES {
+        let snapshot: EpochSnapshot = random_snapshot( &mut rng, ( case % 2 ) as u8 );
+        let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+        let bonds_moving_average: I65F63 = math::fixed( snapshot.bonds_moving_average ) / math::fixed( 1000000 );
+        let ( decayed_bonds, _ ) = math::decay_bonds( &snapshot.bonds, bonds_moving_average );
+        for i in 0..snapshot.n as usize {
+            if snapshot.active[ i ] && snapshot.validator_permit[ i ] { continue }
+            // Bonds held by inactive or non permitted peers only decay.
+            for ( j, bonds_ij ) in output.bonds[ i ].iter() {
+                assert_eq!( *bonds_ij, decayed_bonds[ i ][ *j as usize ] );
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+mod mock;
+use mock::*;
+use frame_support::traits::{Get, Hooks, GetStorageVersion, StorageVersion};
+use frame_system::Config;
+
+/***********************************************************
+	migration tests
+************************************************************/
+
+#[test]
+fn test_migrate_to_v1_converts_kappa() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new( 0 ).put::<Subtensor>();
+		Subtensor::set_kappa( 4 ); // Stored as the denominator of 1/kappa before v1.
+		Subtensor::on_runtime_upgrade();
+		assert_eq!( Subtensor::get_kappa(), 1073741824 ); // 0.25 as I32F32 bits.
+		assert_eq!( Subtensor::on_chain_storage_version(), 3 );
+
+		// Running the upgrade again is a no-op.
+		Subtensor::on_runtime_upgrade();
+		assert_eq!( Subtensor::get_kappa(), 1073741824 );
+	});
+}
+
+#[test]
+fn test_migrate_to_v1_converts_unset_kappa() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new( 0 ).put::<Subtensor>();
+		pallet_subtensor::Kappa::<Test>::kill(); // Live chains never wrote the old default of 1/2.
+		Subtensor::on_runtime_upgrade();
+		assert_eq!( Subtensor::get_kappa(), 2147483648 ); // 0.5 as I32F32 bits.
+	});
+}
+
+#[test]
+fn test_migrate_to_v2_moves_difficulty_bounds_to_storage() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new( 1 ).put::<Subtensor>();
+		Subtensor::set_difficulty_from_u64( 1 ); // Below the minimum difficulty.
+		Subtensor::on_runtime_upgrade();
+		assert!( pallet_subtensor::MinimumDifficulty::<Test>::exists() );
+		assert!( pallet_subtensor::MaximumDifficulty::<Test>::exists() );
+		assert!( pallet_subtensor::BlockEmission::<Test>::exists() );
+		assert_eq!( Subtensor::get_minimum_difficulty(), 10000 );
+		assert_eq!( Subtensor::get_maximum_difficulty(), u64::MAX/4 );
+		assert_eq!( Subtensor::get_block_emission(), 1000000000 );
+		assert_eq!( Subtensor::get_difficulty_as_u64(), 10000 );
+		assert_eq!( Subtensor::on_chain_storage_version(), 3 );
+	});
+}
+
+#[test]
+fn test_migrate_to_v3_indexes_owned_hotkeys() {
+	new_test_ext().execute_with(|| {
+		register_ok_neuron( 1, 10 );
+		register_ok_neuron( 2, 10 );
+		step_block( 1 );
+		register_ok_neuron( 3, 11 );
+
+		// Drop the index as it was before v3.
+		let _ = pallet_subtensor::OwnedHotkeys::<Test>::remove_all( None );
+		StorageVersion::new( 2 ).put::<Subtensor>();
+		let weight = Subtensor::on_runtime_upgrade();
+
+		// One read per neuron and one write per coldkey, on top of the version checks.
+		let db_weight = <Test as Config>::DbWeight::get();
+		assert_eq!( weight, db_weight.reads( 2 ) + db_weight.reads_writes( 4, 3 ) );
+		let mut owned: Vec<u64> = Subtensor::owned_hotkeys( 10 );
+		owned.sort();
+		assert_eq!( owned, vec![1, 2] );
+		assert_eq!( Subtensor::owned_hotkeys( 11 ), vec![3] );
+		assert_eq!( Subtensor::on_chain_storage_version(), 3 );
+	});
+}
@@ -1,7 +1,6 @@
 
-use frame_support::{assert_ok, parameter_types, traits::{EnsureInherentsAreFirst, Hooks, OnRuntimeUpgrade, StorageMapShim, Contains}, weights::{Weight, IdentityFee, GetDispatchInfo, DispatchInfo}};
+use frame_support::{assert_ok, parameter_types, traits::{EnsureInherentsAreFirst, EnsureOneOf, Hooks, OnRuntimeUpgrade, StorageMapShim, Contains}, weights::{Weight, IdentityFee, GetDispatchInfo, DispatchInfo}};
 
-use pallet_transaction_payment::{CurrencyAdapter};
 use sp_runtime::{
 	KeyTypeId,
 	CryptoTypeId,
@@ -25,7 +24,7 @@ use sp_runtime::{
 	codec::{Codec, Encode, Decode}
 };
 
-use pallet_subtensor::{NeuronMetadata};
+use pallet_subtensor::{NeuronMetadata, IncentiveMechanism, DefaultIncentiveMechanism, DefaultScores, EpochSnapshot, EpochOutput};
 use std::net::{Ipv6Addr, Ipv4Addr};
 use serde::{Serialize, Serializer, Deserialize, de::Error as DeError, Deserializer};
 use std::{fmt::{self, Debug}, ops::Deref, cell::RefCell};
@@ -35,7 +34,7 @@ use sp_core::{
 };
 
 use frame_support::pallet_prelude::TypeInfo;
-use frame_system::{limits};
+use frame_system::limits;
 use frame_system::ChainContext;
 
 
@@ -56,7 +55,10 @@ frame_support::construct_runtime!(
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
 		Subtensor: pallet_subtensor::{Pallet, Call, Config, Storage, Event<T>},
+		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>},
 	}
 );
 
@@ -80,10 +82,18 @@ parameter_types! {
 	pub const TransactionByteFee: Balance = 100;
 	pub const SDebug:u64 = 1;
 	pub const InitialRho: u64 = 10;
-	pub const InitialKappa: u64 = 2;
-	pub const SelfOwnership: u64 = 2;
+	pub const InitialKappa: u64 = 2147483648; // 0.5
+	pub const InitialConsensusMode: u8 = 0;
+	pub const InitialSelfOwnership: u64 = 2147483648; // 0.5
 	pub const InitialImmunityPeriod: u64 = 2;
 	pub const InitialMaxAllowedUids: u64 = 100;
+	pub const InitialMaxAllowedValidators: u64 = 100;
+	pub const InitialEpochChunkSize: u64 = 0;
+	pub const InitialEpochHistoryLength: u64 = 3;
+	pub const InitialBondsResetChunkSize: u64 = 100;
+	pub const InitialBondsResetRetention: u64 = 0;
+	pub const InitialInactivityHorizon: u64 = 0;
+	pub const InitialDeregistrationBatchSize: u64 = 4096;
 	pub const InitialBondsMovingAverage: u64 = 500_000;
 	pub const InitialIncentivePruningDenominator: u64 = 1;
 	pub const InitialStakePruningDenominator: u64 = 1;
@@ -93,9 +103,13 @@ parameter_types! {
 	pub const InitialValidatorBatchSize: u64 = 10;
 	pub const InitialValidatorSequenceLen: u64 = 10;
 	pub const InitialValidatorEpochLen: u64 = 10;
-	pub const InitialValidatorEpochsPerReset: u64 = 10;
+	pub const InitialValidatorEpochsPerReset: u64 = 0;
 	pub const InitialValidatorPruneLen: u64 = 0;
 	pub const InitialValidatorLogitsDivergence: u64 = 0;
+	pub const InitialMinStake: u64 = 0;
+	pub const InitialMinStakeChange: u64 = 1;
+	pub const InitialRegistrationFeeRefund: bool = true;
+	pub const ColdkeySwapDelay: u64 = 5;
 	pub const InitialValidatorExcludeQuantile: u8 = 10;
 	pub const InitialScalingLawPower: u8 = 50;
 	pub const InitialSynergyScalingLawPower: u8 = 60;
@@ -106,9 +120,10 @@ parameter_types! {
 	pub const InitialBlocksPerStep: u64 = 1;
 	pub const InitialIssuance: u64 = 548833985028256;
 	pub const InitialDifficulty: u64 = 10000;
-	pub const MinimumDifficulty: u64 = 10000;
+	pub const InitialMinimumDifficulty: u64 = 10000;
 	pub const InitialActivityCutoff: u64 = 5000;
-	pub const MaximumDifficulty: u64 = u64::MAX/4;
+	pub const InitialMaximumDifficulty: u64 = u64::MAX/4;
+	pub const InitialBlockEmission: u64 = 1000000000;
 	pub const InitialAdjustmentInterval: u64 = 100;
 	pub const InitialMaxRegistrationsPerBlock: u64 = 2;
 	pub const InitialTargetRegistrationsPerInterval: u64 = 2;
@@ -135,6 +150,8 @@ impl Contains<Call> for TestBaseCallFilter {
 			Call::Balances(pallet_balances::Call::transfer { .. }) => true,
 			// For benchmarking, this acts as a noop call
 			Call::System(frame_system::Call::remark { .. }) => true,
+			// Hyperparameter calls are dispatched by council motions.
+			Call::Subtensor(..) => true,
 			_ => false,
 		}
 	}
@@ -153,7 +170,7 @@ impl frame_system::Config for Test {
 	type AccountId = u64;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = Event;
 	type BlockHashCount = BlockHashCount;
 	type Version = RuntimeVersion;
 	type PalletInfo = PalletInfo;
@@ -171,7 +188,7 @@ pub type AccountId = u64;
 
 impl pallet_balances::Config for Test {
 	type Balance = Balance;
-	type Event = ();
+	type Event = Event;
 	type DustRemoval = ();
 	type ExistentialDeposit = ();
 	type AccountStore = StorageMapShim<
@@ -187,13 +204,17 @@ impl pallet_balances::Config for Test {
 }
 
 impl pallet_subtensor::Config for Test {
-	type Event = ();
+	type Event = Event;
 	type Currency = Balances;
+	type AdminOrigin = SubtensorAdminOrigin;
 	type TransactionByteFee = TransactionByteFee;
+	type ColdkeySwapDelay = ColdkeySwapDelay;
+	type IncentiveMechanism = TestIncentiveMechanism;
 	type SDebug = SDebug;
 	type InitialRho = InitialRho;
 	type InitialKappa = InitialKappa;
-	type SelfOwnership = SelfOwnership;
+	type InitialConsensusMode = InitialConsensusMode;
+	type InitialSelfOwnership = InitialSelfOwnership;
 	
 	type InitialValidatorBatchSize = InitialValidatorBatchSize;
 	type InitialValidatorSequenceLen = InitialValidatorSequenceLen;
@@ -201,12 +222,22 @@ impl pallet_subtensor::Config for Test {
 	type InitialValidatorEpochsPerReset = InitialValidatorEpochsPerReset;
 	type InitialValidatorPruneLen = InitialValidatorPruneLen;
 	type InitialValidatorLogitsDivergence = InitialValidatorLogitsDivergence;
+	type InitialMinStake = InitialMinStake;
+	type InitialMinStakeChange = InitialMinStakeChange;
+	type InitialRegistrationFeeRefund = InitialRegistrationFeeRefund;
 	type InitialValidatorExcludeQuantile = InitialValidatorExcludeQuantile;
 	type InitialScalingLawPower = InitialScalingLawPower;
 	type InitialSynergyScalingLawPower = InitialSynergyScalingLawPower;
 
 	type InitialImmunityPeriod = InitialImmunityPeriod;
 	type InitialMaxAllowedUids = InitialMaxAllowedUids;
+	type InitialMaxAllowedValidators = InitialMaxAllowedValidators;
+	type InitialEpochChunkSize = InitialEpochChunkSize;
+	type InitialEpochHistoryLength = InitialEpochHistoryLength;
+	type InitialBondsResetChunkSize = InitialBondsResetChunkSize;
+	type InitialBondsResetRetention = InitialBondsResetRetention;
+	type InitialInactivityHorizon = InitialInactivityHorizon;
+	type InitialDeregistrationBatchSize = InitialDeregistrationBatchSize;
 	type InitialMinAllowedWeights = InitialMinAllowedWeights;
 	type InitialBondsMovingAverage = InitialBondsMovingAverage;
 	type InitialMaxAllowedMaxMinRatio = InitialMaxAllowedMaxMinRatio;
@@ -217,8 +248,9 @@ impl pallet_subtensor::Config for Test {
 	type InitialFoundationDistribution = InitialFoundationDistribution;
 	type InitialIssuance = InitialIssuance;
 	type InitialDifficulty = InitialDifficulty;
-	type MinimumDifficulty = MinimumDifficulty;
-	type MaximumDifficulty = MaximumDifficulty;
+	type InitialMinimumDifficulty = InitialMinimumDifficulty;
+	type InitialMaximumDifficulty = InitialMaximumDifficulty;
+	type InitialBlockEmission = InitialBlockEmission;
 	type InitialBlocksPerStep = InitialBlocksPerStep;
 	type InitialActivityCutoff = InitialActivityCutoff;
 	type InitialAdjustmentInterval = InitialAdjustmentInterval;
@@ -227,16 +259,147 @@ impl pallet_subtensor::Config for Test {
 
 }
 
+thread_local!{
+	/// True when the mock runtime steps with StakeProportionalMechanism instead of the default mechanism.
+	pub static STAKE_PROPORTIONAL_MECHANISM: RefCell<bool> = RefCell::new(false);
+}
+
+// A research mechanism which pays out emission in proportion to stake alone.
+pub struct StakeProportionalMechanism;
+impl IncentiveMechanism for StakeProportionalMechanism {
+	type Scores = EpochSnapshot;
+	fn begin( snapshot: &EpochSnapshot ) -> EpochSnapshot { snapshot.clone() }
+	fn score_column( _scores: &mut EpochSnapshot, _uid_j: u32, _received_weights: &Vec<(u32, u32)> ) {}
+	fn score_row( _scores: &mut EpochSnapshot, _uid_i: u32, _weights_i: &Vec<(u32, u32)>, _bonds_i: &mut Vec<(u32, u64)> ) {}
+	fn end_scoring( _scores: &mut EpochSnapshot ) {}
+	fn distribute_row( _scores: &mut EpochSnapshot, _uid_i: u32, bonds_i: &mut Vec<(u32, u64)> ) { bonds_i.clear(); }
+	fn finish( snapshot: EpochSnapshot ) -> EpochOutput {
+		let total_stake: u128 = snapshot.stake.iter().map( |stake_i| *stake_i as u128 ).sum();
+		let shares: Vec<u64> = snapshot.stake.iter().map( |stake_i| ( *stake_i as u128 * u64::MAX as u128 / total_stake ) as u64 ).collect();
+		let emission: Vec<u64> = snapshot.stake.iter().map( |stake_i| ( *stake_i as u128 * snapshot.block_emission as u128 / total_stake ) as u64 ).collect();
+		EpochOutput {
+			ranks: shares.clone(),
+			trust: shares.clone(),
+			consensus: shares.clone(),
+			incentive: shares.clone(),
+			dividends: shares,
+			vtrust: vec![ u64::MAX; snapshot.n as usize ],
+			emission: emission,
+			bonds: vec![],
+			bonds_purchased: 0,
+		}
+	}
+}
+
+#[derive(PartialEq, Clone, Encode, Decode, TypeInfo)]
+pub enum TestScores {
+	Default( DefaultScores ),
+	StakeProportional( EpochSnapshot ),
+}
+
+// The mechanism of the mock runtime: the default one unless a test switched to StakeProportionalMechanism.
+pub struct TestIncentiveMechanism;
+impl IncentiveMechanism for TestIncentiveMechanism {
+	type Scores = TestScores;
+	fn begin( snapshot: &EpochSnapshot ) -> TestScores {
+		if STAKE_PROPORTIONAL_MECHANISM.with( |v| *v.borrow() ) {
+			TestScores::StakeProportional( StakeProportionalMechanism::begin( snapshot ) )
+		} else {
+			TestScores::Default( DefaultIncentiveMechanism::begin( snapshot ) )
+		}
+	}
+	fn score_column( scores: &mut TestScores, uid_j: u32, received_weights: &Vec<(u32, u32)> ) {
+		match scores {
+			TestScores::Default( scores ) => DefaultIncentiveMechanism::score_column( scores, uid_j, received_weights ),
+			TestScores::StakeProportional( scores ) => StakeProportionalMechanism::score_column( scores, uid_j, received_weights ),
+		}
+	}
+	fn score_row( scores: &mut TestScores, uid_i: u32, weights_i: &Vec<(u32, u32)>, bonds_i: &mut Vec<(u32, u64)> ) {
+		match scores {
+			TestScores::Default( scores ) => DefaultIncentiveMechanism::score_row( scores, uid_i, weights_i, bonds_i ),
+			TestScores::StakeProportional( scores ) => StakeProportionalMechanism::score_row( scores, uid_i, weights_i, bonds_i ),
+		}
+	}
+	fn end_scoring( scores: &mut TestScores ) {
+		match scores {
+			TestScores::Default( scores ) => DefaultIncentiveMechanism::end_scoring( scores ),
+			TestScores::StakeProportional( scores ) => StakeProportionalMechanism::end_scoring( scores ),
+		}
+	}
+	fn distribute_row( scores: &mut TestScores, uid_i: u32, bonds_i: &mut Vec<(u32, u64)> ) {
+		match scores {
+			TestScores::Default( scores ) => DefaultIncentiveMechanism::distribute_row( scores, uid_i, bonds_i ),
+			TestScores::StakeProportional( scores ) => StakeProportionalMechanism::distribute_row( scores, uid_i, bonds_i ),
+		}
+	}
+	fn finish( scores: TestScores ) -> EpochOutput {
+		match scores {
+			TestScores::Default( scores ) => DefaultIncentiveMechanism::finish( scores ),
+			TestScores::StakeProportional( scores ) => StakeProportionalMechanism::finish( scores ),
+		}
+	}
+}
+
+#[allow(dead_code)]
+pub fn use_stake_proportional_mechanism( enabled: bool ) {
+	STAKE_PROPORTIONAL_MECHANISM.with( |v| *v.borrow_mut() = enabled );
+}
+
 impl pallet_sudo::Config for Test {
-	type Event = ();
+	type Event = Event;
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 100;
+	pub const CouncilMaxProposals: u32 = 10;
+	pub const CouncilMaxMembers: u32 = 10;
+	pub const TechnicalMotionDuration: BlockNumber = 10;
+	pub const TechnicalMaxProposals: u32 = 10;
+	pub const TechnicalMaxMembers: u32 = 10;
+}
+
+pub type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Test {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+pub type TechnicalCollective = pallet_collective::Instance2;
+impl pallet_collective::Config<TechnicalCollective> for Test {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = TechnicalMaxProposals;
+	type MaxMembers = TechnicalMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+// Matches the runtime: root cannot set hyperparameters.
+pub type SubtensorAdminOrigin = EnsureOneOf<
+	pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 1>,
+>;
+
+// The origin of a motion passed by the whole technical committee, accepted as the admin origin.
+#[allow(dead_code)]
+pub fn admin_origin() -> Origin {
+	pallet_collective::RawOrigin::<AccountId, TechnicalCollective>::Members( 1, 1 ).into()
+}
+
 impl pallet_transaction_payment::Config for Test {
 	type TransactionByteFee = TransactionByteFee;
 	type WeightToFee = IdentityFee<Balance>;
 	type FeeMultiplierUpdate = ();
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = pallet_subtensor::SubtensorFeeAdapter<Balances, ()>;
     type OperationalFeeMultiplier = frame_support::traits::ConstU8<5>;
 }
 
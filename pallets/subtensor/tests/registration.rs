@@ -22,7 +22,7 @@ fn test_subscribe_ok_dispatch_info_ok() {
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
 			weight: 0,
 			class: DispatchClass::Normal,
-			pays_fee: Pays::No
+			pays_fee: Pays::Yes
 		});
 	});
 }
@@ -395,3 +395,142 @@ fn test_get_next_uid() {
 	});
 }
 
+
+/********************************************
+	deregistration tests
+*********************************************/
+
+// Returns the stake reported by the NeuronDeregistered event for the uid.
+fn deregistered_stake( uid: u32 ) -> Option<u64> {
+	System::events().iter().find_map( |record| match record.event {
+		Event::Subtensor( pallet_subtensor::Event::NeuronDeregistered( uid_i, _, stake ) ) if uid_i == uid => Some( stake ),
+		_ => None,
+	})
+}
+
+#[test]
+fn test_deregister_inactive_neuron() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_inactivity_horizon( 3 );
+		let neuron0 = register_ok_neuron_with_nonce( 0, 10, 38282211 );
+		Subtensor::add_stake_to_neuron_hotkey_account( neuron0.uid, 1000000000 );
+		step_block ( 2 );
+		let neuron1 = register_ok_neuron_with_nonce( 1, 11, 18912831231 );
+		assert_eq!( neuron1.uid, 1 );
+		assert!( Subtensor::is_hotkey_active( &0 ) );
+
+		// Neuron 0 has not set weights for 3 blocks and is deregistered, neuron 1 registered 1 block ago.
+		step_block ( 1 );
+		assert!( !Subtensor::is_hotkey_active( &0 ) );
+		assert!( !Subtensor::is_uid_active( 0 ) );
+		assert!( Subtensor::is_uid_free( 0 ) );
+		assert!( Subtensor::is_hotkey_active( &1 ) );
+
+		// The stake is returned to the coldkey.
+		let stake: u64 = deregistered_stake( 0 ).unwrap();
+		assert!( stake >= 1000000000 );
+		assert_eq!( Subtensor::get_coldkey_balance( &10 ) as u64, stake );
+		assert_eq!( Subtensor::get_total_stake(), Subtensor::get_neuron_for_uid( 1 ).stake );
+
+		// The freed uid is reused by the next registration.
+		let neuron2 = register_ok_neuron_with_nonce( 2, 12, 1979183123 );
+		assert_eq!( neuron2.uid, 0 );
+		assert!( !Subtensor::is_uid_free( 0 ) );
+		assert_eq!( Subtensor::get_neuron_count(), 2 );
+	});
+}
+
+#[test]
+fn test_deregistration_respects_immunity_period() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_inactivity_horizon( 1 );
+		Subtensor::set_immunity_period( 2 );
+		register_ok_neuron_with_nonce( 0, 0, 38282211 );
+		step_block ( 1 );
+		assert!( Subtensor::is_hotkey_active( &0 ) );
+		step_block ( 1 );
+		assert!( !Subtensor::is_hotkey_active( &0 ) );
+	});
+}
+
+#[test]
+fn test_deregistration_in_batches() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_inactivity_horizon( 1 );
+		Subtensor::set_immunity_period( 0 );
+		Subtensor::set_deregistration_batch_size( 1 );
+		Subtensor::set_max_registratations_per_block( 100 );
+		for i in 0..3 { register_ok_neuron_with_nonce( i, i, 1000000000 * i ); }
+		step_block ( 1 );
+		assert!( Subtensor::is_uid_free( 0 ) );
+		assert!( Subtensor::is_uid_active( 1 ) && Subtensor::is_uid_active( 2 ) );
+		step_block ( 1 );
+		assert!( Subtensor::is_uid_free( 1 ) );
+		assert!( Subtensor::is_uid_active( 2 ) );
+		step_block ( 1 );
+		assert!( Subtensor::is_uid_free( 2 ) );
+		assert_eq!( Subtensor::get_stake(), vec![ 0; 3 ] );
+	});
+}
+
+#[test]
+fn test_free_uids_are_taken_lowest_first() {
+	new_test_ext().execute_with(|| {
+		Subtensor::insert_free_uid( 2 );
+		Subtensor::insert_free_uid( 0 );
+		Subtensor::insert_free_uid( 2 );
+		assert_eq!( Subtensor::get_free_uid(), Some( 0 ) );
+		assert_eq!( Subtensor::take_free_uid(), Some( 0 ) );
+		assert!( !Subtensor::is_uid_free( 0 ) );
+		assert_eq!( Subtensor::take_free_uid(), Some( 2 ) );
+		assert_eq!( Subtensor::take_free_uid(), None );
+	});
+}
+
+#[test]
+fn test_deregistration_disabled_by_default() {
+	new_test_ext().execute_with(|| {
+		assert_eq!( Subtensor::get_inactivity_horizon(), 0 );
+		register_ok_neuron_with_nonce( 0, 0, 38282211 );
+		step_block ( 100 );
+		assert!( Subtensor::is_hotkey_active( &0 ) );
+	});
+}
+
+#[test]
+fn test_owned_hotkeys_follow_registration_and_pruning() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_max_allowed_uids ( 2 );
+		Subtensor::set_immunity_period ( 0 );
+		register_ok_neuron_with_nonce( 0, 10, 38282211 );
+		register_ok_neuron_with_nonce( 1, 10, 18912831231 );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), vec![0, 1] );
+		Subtensor::set_stake_from_vector( vec![ 1000, 2000 ] );
+		assert_eq!( Subtensor::get_total_stake_for_coldkey( &10 ), 3000 );
+		assert_eq!( Subtensor::get_neurons_by_coldkey( &10 ).iter().map(|neuron| neuron.hotkey).collect::<Vec<u64>>(), vec![0, 1] );
+		step_block ( 1 );
+
+		// The new neuron replaces uid 0, which leaves the index of coldkey 10.
+		let neuron2 = register_ok_neuron_with_nonce( 2, 11, 1979183123 );
+		assert_eq!( neuron2.uid, 0 );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), vec![1] );
+		assert_eq!( Subtensor::owned_hotkeys( 11 ), vec![2] );
+		assert_eq!( Subtensor::get_total_stake_for_coldkey( &10 ), 2000 );
+		assert_eq!( Subtensor::get_total_stake_for_coldkey( &11 ), 0 );
+		assert_eq!( Subtensor::get_neurons_by_coldkey( &12 ).len(), 0 );
+	});
+}
+
+#[test]
+fn test_owned_hotkeys_follow_deregistration() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_inactivity_horizon( 3 );
+		register_ok_neuron_with_nonce( 0, 10, 38282211 );
+		step_block ( 2 );
+		register_ok_neuron_with_nonce( 1, 10, 18912831231 );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), vec![0, 1] );
+		step_block ( 1 );
+		assert!( !Subtensor::is_hotkey_active( &0 ) );
+		assert_eq!( Subtensor::owned_hotkeys( 10 ), vec![1] );
+	});
+}
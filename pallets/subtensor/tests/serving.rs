@@ -21,7 +21,7 @@ fn test_serve_ok_dispatch_info_ok() {
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
 			weight: 0,
 			class: DispatchClass::Normal,
-			pays_fee: Pays::No
+			pays_fee: Pays::Yes
 		});
 	});
 }
@@ -4,8 +4,9 @@ mod mock;
 use mock::*;
 use mock::{TestXt};
 use frame_support::sp_runtime::DispatchError;
-use pallet_subtensor::{Error, Call as SubtensorCall};
+use pallet_subtensor::{Error, Call as SubtensorCall, EmissionDestination};
 use frame_support::weights::{GetDispatchInfo, DispatchInfo, DispatchClass, Pays};
+use frame_support::traits::Get;
 
 /***********************************************************
 	staking::add_stake() tests
@@ -21,7 +22,7 @@ fn test_add_stake_dispatch_info_ok() {
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
 			weight: 0,
 			class: DispatchClass::Normal,
-			pays_fee: Pays::No
+			pays_fee: Pays::Yes
 		});
 	});
 }
@@ -194,7 +195,7 @@ fn test_remove_stake_dispatch_info_ok() {
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
 			weight: 0,
 			class: DispatchClass::Normal,
-			pays_fee: Pays::No
+			pays_fee: Pays::Yes
 		});
 	});
 }
@@ -280,6 +281,458 @@ fn test_remove_stake_no_enough_stake() {
 }
 
 
+/***********************************************************
+	staking::move_stake() tests
+************************************************************/
+
+#[test]
+fn test_move_stake_ok() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let from_hotkey = 4001;
+		let to_hotkey = 4002;
+
+		let from_neuron = register_ok_neuron( from_hotkey, coldkey_id );
+		let to_neuron = register_ok_neuron( to_hotkey, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(from_neuron.uid, 10000);
+		Subtensor::add_stake_to_neuron_hotkey_account(to_neuron.uid, 500);
+		step_block( 1 );
+
+		assert_ok!(Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), from_hotkey, to_hotkey, 4000));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(from_neuron.uid), 6000);
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(to_neuron.uid), 4500);
+
+		// The stake never left the staking accounts.
+		assert_eq!(Subtensor::get_total_stake(), 10500);
+		assert_eq!(Subtensor::get_coldkey_balance(&coldkey_id), 0);
+		assert!(System::events().iter().any(|record| record.event == Event::Subtensor(pallet_subtensor::Event::StakeMoved(from_hotkey, to_hotkey, 4000))));
+	});
+}
+
+#[test]
+fn test_move_stake_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let from_hotkey = 4001;
+
+		let from_neuron = register_ok_neuron( from_hotkey, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(from_neuron.uid, 10000);
+
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), from_hotkey, 4002, 1000);
+		assert_eq!(result, Err(Error::<Test>::NotRegistered.into()));
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), 4002, from_hotkey, 1000);
+		assert_eq!(result, Err(Error::<Test>::NotRegistered.into()));
+	});
+}
+
+#[test]
+fn test_move_stake_err_neuron_does_not_belong_to_coldkey() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let other_coldkey_id = 668;
+		let from_hotkey = 4001;
+		let to_hotkey = 4002;
+
+		let from_neuron = register_ok_neuron( from_hotkey, coldkey_id );
+		let _to_neuron = register_ok_neuron( to_hotkey, other_coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(from_neuron.uid, 10000);
+
+		// Neither direction is allowed when one of the neurons is owned by another coldkey.
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), from_hotkey, to_hotkey, 1000);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(other_coldkey_id), from_hotkey, to_hotkey, 1000);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(from_neuron.uid), 10000);
+	});
+}
+
+#[test]
+fn test_stake_frozen_during_epoch_snapshot() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let hotkey = 4001;
+		let other_hotkey = 4002;
+		let neuron = register_ok_neuron( hotkey, coldkey_id );
+		register_ok_neuron( other_hotkey, coldkey_id );
+		register_ok_neuron( 4003, 668 );
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron.uid, 10000);
+		Subtensor::add_balance_to_coldkey_account( &coldkey_id, 10000 );
+		Subtensor::set_epoch_chunk_size( 2 );
+
+		step_block( 1 ); // Snapshot uids 0, 1.
+		assert!(Subtensor::stake_frozen_by_epoch());
+		assert_eq!(Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, 1000), Err(Error::<Test>::StakeFrozenByEpoch.into()));
+		assert_eq!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, 1000), Err(Error::<Test>::StakeFrozenByEpoch.into()));
+		assert_eq!(Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, other_hotkey, 1000), Err(Error::<Test>::StakeFrozenByEpoch.into()));
+		assert_eq!(Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(hotkey, 1000)]), Err(Error::<Test>::StakeFrozenByEpoch.into()));
+		assert_eq!(Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(hotkey, 1000)]), Err(Error::<Test>::StakeFrozenByEpoch.into()));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), 10000);
+
+		// Once every stake has been read the epoch keeps running but stake can change again.
+		step_block( 1 ); // Snapshot uid 2.
+		assert!(Subtensor::epoch_in_progress());
+		assert!(!Subtensor::stake_frozen_by_epoch());
+		assert_ok!(Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, 1000));
+		assert_ok!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, 2000));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), 9000);
+	});
+}
+
+#[test]
+fn test_stake_frozen_during_epoch_commit() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let hotkey = 4001;
+		let neuron = register_ok_neuron( hotkey, coldkey_id );
+		register_ok_neuron( 4002, 668 );
+		register_ok_neuron( 4003, 669 );
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron.uid, 10000);
+		Subtensor::set_epoch_chunk_size( 2 );
+
+		// 2 snapshot, 2 consensus, 2 ranks, one incentive and 2 dividends blocks, then commit uids 0, 1.
+		step_block( 10 );
+		assert_eq!(Subtensor::current_epoch().phase, pallet_subtensor::EpochPhase::Commit);
+		assert!(Subtensor::stake_frozen_by_epoch());
+
+		// The committed emission is already counted in the totals.
+		let stake: u64 = Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid);
+		assert!(stake > 10000);
+		assert_eq!(Subtensor::get_stake().iter().sum::<u64>(), Subtensor::get_total_stake());
+		assert_eq!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, stake), Err(Error::<Test>::StakeFrozenByEpoch.into()));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), stake);
+
+		step_block( 1 ); // Commit uid 2.
+		assert!(!Subtensor::epoch_in_progress());
+		assert_eq!(Subtensor::get_stake().iter().sum::<u64>(), Subtensor::get_total_stake());
+		assert_ok!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey, stake));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), 0);
+		assert_eq!(Subtensor::get_stake().iter().sum::<u64>(), Subtensor::get_total_stake());
+	});
+}
+
+#[test]
+fn test_move_stake_err_not_enough_stake() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let from_hotkey = 4001;
+		let to_hotkey = 4002;
+
+		let from_neuron = register_ok_neuron( from_hotkey, coldkey_id );
+		let _to_neuron = register_ok_neuron( to_hotkey, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(from_neuron.uid, 1000);
+
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), from_hotkey, to_hotkey, 1001);
+		assert_eq!(result, Err(Error::<Test>::NotEnoughStaketoWithdraw.into()));
+	});
+}
+
+#[test]
+fn test_move_stake_err_same_hotkey() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let hotkey_id = 4001;
+
+		let neuron = register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron.uid, 1000);
+
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, hotkey_id, 500);
+		assert_eq!(result, Err(Error::<Test>::MoveStakeToSameHotkey.into()));
+	});
+}
+
+/***********************************************************
+	staking::add_stake_multiple() and staking::remove_stake_multiple() tests
+************************************************************/
+
+#[test]
+fn test_stake_multiple_dispatch_info_scales_with_entries() {
+	new_test_ext().execute_with(|| {
+		let one = Call::Subtensor(SubtensorCall::add_stake_multiple{stakes: vec![(1, 100)]}).get_dispatch_info();
+		let three = Call::Subtensor(SubtensorCall::add_stake_multiple{stakes: vec![(1, 100), (2, 100), (3, 100)]}).get_dispatch_info();
+		assert!(three.weight >= one.weight);
+		assert_eq!(three.class, DispatchClass::Normal);
+		assert_eq!(three.pays_fee, Pays::Yes);
+		let three_removed = Call::Subtensor(SubtensorCall::remove_stake_multiple{stakes: vec![(1, 100), (2, 100), (3, 100)]}).get_dispatch_info();
+		assert_eq!(three_removed, three);
+	});
+}
+
+#[test]
+fn test_add_stake_multiple_ok() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let neuron_a = register_ok_neuron( 4001, coldkey_id );
+		let neuron_b = register_ok_neuron( 4002, coldkey_id );
+		Subtensor::add_balance_to_coldkey_account(&coldkey_id, 100000);
+		step_block( 1 );
+
+		assert_ok!(Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 10000), (4002, 20000), (4001, 5000)]));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_a.uid), 15000);
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_b.uid), 20000);
+		assert_eq!(Subtensor::get_total_stake(), 35000);
+		assert_eq!(Subtensor::get_coldkey_balance(&coldkey_id), 65000);
+
+		// One event per entry.
+		let added: Vec<(u64, u64)> = System::events().iter().filter_map(|record| match record.event {
+			Event::Subtensor(pallet_subtensor::Event::StakeAdded(hotkey, amount)) => Some((hotkey, amount)),
+			_ => None,
+		}).collect();
+		assert_eq!(added, vec![(4001, 10000), (4002, 20000), (4001, 5000)]);
+	});
+}
+
+#[test]
+fn test_add_stake_multiple_err_is_atomic() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let other_coldkey_id = 668;
+		let neuron_a = register_ok_neuron( 4001, coldkey_id );
+		let _neuron_b = register_ok_neuron( 4002, other_coldkey_id );
+		Subtensor::add_balance_to_coldkey_account(&coldkey_id, 100000);
+
+		// The second entry is owned by another coldkey, so the first is not staked either.
+		let result = Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 10000), (4002, 20000)]);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		let result = Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 10000), (4003, 20000)]);
+		assert_eq!(result, Err(Error::<Test>::NotRegistered.into()));
+
+		// Each entry fits the balance but their sum does not.
+		let result = Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 60000), (4001, 60000)]);
+		assert_eq!(result, Err(Error::<Test>::NotEnoughBalanceToStake.into()));
+
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_a.uid), 0);
+		assert_eq!(Subtensor::get_total_stake(), 0);
+		assert_eq!(Subtensor::get_coldkey_balance(&coldkey_id), 100000);
+	});
+}
+
+#[test]
+fn test_remove_stake_multiple_ok() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let neuron_a = register_ok_neuron( 4001, coldkey_id );
+		let neuron_b = register_ok_neuron( 4002, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron_a.uid, 10000);
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron_b.uid, 20000);
+		step_block( 1 );
+
+		assert_ok!(Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 4000), (4002, 20000), (4001, 6000)]));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_a.uid), 0);
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_b.uid), 0);
+		assert_eq!(Subtensor::get_total_stake(), 0);
+		assert_eq!(Subtensor::get_coldkey_balance(&coldkey_id), 30000);
+		assert_eq!(System::events().iter().filter(|record| matches!(record.event, Event::Subtensor(pallet_subtensor::Event::StakeRemoved(..)))).count(), 3);
+	});
+}
+
+#[test]
+fn test_remove_stake_multiple_err_is_atomic() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let neuron_a = register_ok_neuron( 4001, coldkey_id );
+		let neuron_b = register_ok_neuron( 4002, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron_a.uid, 10000);
+		Subtensor::add_stake_to_neuron_hotkey_account(neuron_b.uid, 20000);
+
+		// Repeated entries of a hotkey are summed against its stake.
+		let result = Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4002, 20000), (4001, 6000), (4001, 6000)]);
+		assert_eq!(result, Err(Error::<Test>::NotEnoughStaketoWithdraw.into()));
+		let result = Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(668), vec![(4001, 1000)]);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_a.uid), 10000);
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid(neuron_b.uid), 20000);
+		assert_eq!(Subtensor::get_total_stake(), 30000);
+		assert_eq!(Subtensor::get_coldkey_balance(&coldkey_id), 0);
+	});
+}
+
+/***********************************************************
+	minimum stake and minimum stake change tests
+************************************************************/
+
+#[test]
+fn test_add_stake_below_minimums() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id = 4001;
+		let coldkey_id = 667;
+		let neuron = register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_balance_to_coldkey_account( &coldkey_id, 100000 );
+		Subtensor::set_min_stake( 5000 );
+		Subtensor::set_min_stake_change( 100 );
+
+		// Zero and dust amounts are rejected.
+		let result = Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 0);
+		assert_eq!(result, Err(Error::<Test>::StakeChangeBelowMinimum.into()));
+		let result = Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 99);
+		assert_eq!(result, Err(Error::<Test>::StakeChangeBelowMinimum.into()));
+
+		// The first stake must bring the neuron up to the minimum stake.
+		let result = Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 4999);
+		assert_eq!(result, Err(Error::<Test>::StakeBelowMinimum.into()));
+		assert_ok!(Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 5000));
+
+		// Afterwards any amount above the minimum change is accepted.
+		assert_ok!(Subtensor::add_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 100));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( neuron.uid ), 5100);
+		assert_eq!(Subtensor::get_coldkey_balance( &coldkey_id ), 94900);
+	});
+}
+
+#[test]
+fn test_remove_stake_below_minimums() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id = 4001;
+		let coldkey_id = 667;
+		let neuron = register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account( neuron.uid, 10000 );
+		Subtensor::set_min_stake( 5000 );
+		Subtensor::set_min_stake_change( 100 );
+
+		let result = Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 0);
+		assert_eq!(result, Err(Error::<Test>::StakeChangeBelowMinimum.into()));
+		let result = Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 99);
+		assert_eq!(result, Err(Error::<Test>::StakeChangeBelowMinimum.into()));
+
+		// A partial withdrawal must leave the minimum stake on the neuron.
+		let result = Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 5001);
+		assert_eq!(result, Err(Error::<Test>::RemainingStakeBelowMinimum.into()));
+		assert_ok!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 5000));
+
+		// The whole stake can always be withdrawn.
+		assert_ok!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 5000));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( neuron.uid ), 0);
+		assert_eq!(Subtensor::get_coldkey_balance( &coldkey_id ), 10000);
+	});
+}
+
+#[test]
+fn test_remove_stake_dust_below_minimum_change() {
+	new_test_ext().execute_with(|| {
+		let hotkey_id = 4001;
+		let coldkey_id = 667;
+		let neuron = register_ok_neuron( hotkey_id, coldkey_id );
+		Subtensor::add_stake_to_neuron_hotkey_account( neuron.uid, 50 );
+		Subtensor::set_min_stake( 5000 );
+		Subtensor::set_min_stake_change( 100 );
+
+		// Dust left from before the minimums were raised is cleared by a full withdrawal.
+		let result = Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 10);
+		assert_eq!(result, Err(Error::<Test>::StakeChangeBelowMinimum.into()));
+		assert_ok!(Subtensor::remove_stake(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, 50));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( neuron.uid ), 0);
+	});
+}
+
+#[test]
+fn test_move_stake_below_minimums() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let from_neuron = register_ok_neuron_with_nonce( 4001, coldkey_id, 38282211 );
+		let to_neuron = register_ok_neuron_with_nonce( 4002, coldkey_id, 18912831231 );
+		Subtensor::add_stake_to_neuron_hotkey_account( from_neuron.uid, 10000 );
+		Subtensor::set_min_stake( 5000 );
+		Subtensor::set_min_stake_change( 100 );
+
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), 4001, 4002, 6000);
+		assert_eq!(result, Err(Error::<Test>::RemainingStakeBelowMinimum.into()));
+		let result = Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), 4001, 4002, 4000);
+		assert_eq!(result, Err(Error::<Test>::StakeBelowMinimum.into()));
+		assert_ok!(Subtensor::move_stake(<<Test as Config>::Origin>::signed(coldkey_id), 4001, 4002, 5000));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( from_neuron.uid ), 5000);
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( to_neuron.uid ), 5000);
+	});
+}
+
+#[test]
+fn test_stake_multiple_below_minimums() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let neuron = register_ok_neuron( 4001, coldkey_id );
+		Subtensor::add_balance_to_coldkey_account( &coldkey_id, 100000 );
+		Subtensor::set_min_stake( 5000 );
+		Subtensor::set_min_stake_change( 100 );
+
+		// Entries are checked as if staked one after the other.
+		let result = Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 3000), (4001, 3000)]);
+		assert_eq!(result, Err(Error::<Test>::StakeBelowMinimum.into()));
+		assert_ok!(Subtensor::add_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 5000), (4001, 5000)]));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( neuron.uid ), 10000);
+
+		let result = Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 3000), (4001, 3000)]);
+		assert_eq!(result, Err(Error::<Test>::RemainingStakeBelowMinimum.into()));
+		let result = Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 3000), (4001, 50)]);
+		assert_eq!(result, Err(Error::<Test>::StakeChangeBelowMinimum.into()));
+		assert_ok!(Subtensor::remove_stake_multiple(<<Test as Config>::Origin>::signed(coldkey_id), vec![(4001, 3000), (4001, 7000)]));
+		assert_eq!(Subtensor::get_stake_of_neuron_hotkey_account_by_uid( neuron.uid ), 0);
+		assert_eq!(Subtensor::get_coldkey_balance( &coldkey_id ), 100000);
+	});
+}
+
+/***********************************************************
+	staking::set_emission_destination() tests
+************************************************************/
+
+#[test]
+fn test_set_emission_destination_dispatch_info_ok() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Subtensor(SubtensorCall::set_emission_destination{hotkey: 0, destination: EmissionDestination::Coldkey});
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Config>::DbWeight::get().reads_writes(2, 1),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_set_emission_destination_ok() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 544;
+		let hotkey_id = 54544;
+		let _neuron = register_ok_neuron( hotkey_id, coldkey_id );
+		assert_eq!(Subtensor::emission_destination(&hotkey_id), EmissionDestination::Stake);
+
+		assert_ok!(Subtensor::set_emission_destination(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, EmissionDestination::Split(30)));
+		assert_eq!(Subtensor::emission_destination(&hotkey_id), EmissionDestination::Split(30));
+		assert_eq!(Subtensor::get_emission_to_coldkey(&hotkey_id, 1000), 300);
+	});
+}
+
+#[test]
+fn test_set_emission_destination_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = Subtensor::set_emission_destination(<<Test as Config>::Origin>::signed(435445), 54544, EmissionDestination::Coldkey);
+		assert_eq!(result, Err(Error::<Test>::NotRegistered.into()));
+	});
+}
+
+#[test]
+fn test_set_emission_destination_err_neuron_does_not_belong_to_coldkey() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 544;
+		let hotkey_id = 54544;
+		let other_cold_key = 99498;
+		let _neuron = register_ok_neuron( hotkey_id, coldkey_id );
+
+		let result = Subtensor::set_emission_destination(<<Test as Config>::Origin>::signed(other_cold_key), hotkey_id, EmissionDestination::Coldkey);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+	});
+}
+
+#[test]
+fn test_set_emission_destination_err_invalid_split() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 544;
+		let hotkey_id = 54544;
+		let _neuron = register_ok_neuron( hotkey_id, coldkey_id );
+
+		let result = Subtensor::set_emission_destination(<<Test as Config>::Origin>::signed(coldkey_id), hotkey_id, EmissionDestination::Split(101));
+		assert_eq!(result, Err(Error::<Test>::InvalidEmissionSplit.into()));
+		assert_eq!(Subtensor::emission_destination(&hotkey_id), EmissionDestination::Stake);
+	});
+}
+
 /***********************************************************
 	staking::get_coldkey_balance() tests
 ************************************************************/
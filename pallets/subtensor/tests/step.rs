@@ -1,5 +1,7 @@
 mod mock;
 use mock::*;
+use pallet_subtensor::{IncentiveMechanism, DefaultIncentiveMechanism, EpochSnapshot, EpochOutput, EpochPhase, EmissionDestination};
+use frame_support::{assert_ok};
 
 /***********************************************************
 	staking::add_stake() tests
@@ -172,6 +174,7 @@ pub fn mat_approx_equals( a_vec: &Vec<Vec<u64>>, b_vec: &Vec<Vec<u64>>, eps: u64
 fn test_two_steps_with_many_outward_weights() {
     new_test_ext().execute_with( || {
         Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_kappa( 1073741824 ); // 0.25, a single validator backs each weight.
         let initial_stake:u64 = 1000000000;
         let u64m: u64 = 18446744073709551615;
         for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
@@ -197,7 +200,7 @@ fn test_two_steps_with_many_outward_weights() {
         assert!( vec_approx_equals ( &Subtensor::get_ranks(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_trust(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert_eq!( Subtensor::get_active(), vec![1; 4] );
-        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![1399336432749266785, 1399336432749266785, 1399336432749266785, 1399336432749266785], 10) );
+        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![u64m/2, u64m/2, u64m/2, u64m/2], 10) );
         assert!( vec_approx_equals ( &Subtensor::get_incentive(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_dividends(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_emission(), &vec![250000000, 250000000, 250000000, 250000000], 10) );
@@ -219,7 +222,7 @@ fn test_two_steps_with_many_outward_weights() {
         assert!( vec_approx_equals ( &Subtensor::get_ranks(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_trust(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert_eq!( Subtensor::get_active(), vec![1; 4] );
-        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![1399336432749266785, 1399336432749266785, 1399336432749266785, 1399336432749266785], 10) );
+        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![u64m/2, u64m/2, u64m/2, u64m/2], 10) );
         assert!( vec_approx_equals ( &Subtensor::get_incentive(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_dividends(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_emission(), &vec![250000000, 250000000, 250000000, 250000000], 10) );
@@ -240,7 +243,7 @@ fn test_two_steps_with_many_outward_weights() {
         assert!( vec_approx_equals ( &Subtensor::get_ranks(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_trust(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert_eq!( Subtensor::get_active(), vec![1; 4] );
-        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![1399336432749266785, 1399336432749266785, 1399336432749266785, 1399336432749266785], 10) );
+        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![u64m/2, u64m/2, u64m/2, u64m/2], 10) );
         assert!( vec_approx_equals ( &Subtensor::get_incentive(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_dividends(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals (  &Subtensor::get_emission(), &vec![250000000, 250000000, 250000000, 250000000], 10) );
@@ -255,10 +258,114 @@ fn test_two_steps_with_many_outward_weights() {
     });
 }
 
+#[test]
+fn test_step_with_validator_permits() {
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_kappa( 1073741824 ); // 0.25, both permitted validators back their weights alone.
+        let u64m: u64 = 18446744073709551615;
+        for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        // Set stake, only the top 2 stakers receive a permit.
+        Subtensor::set_stake_from_vector( vec![ 4000000000, 3000000000, 2000000000, 1000000000 ] );
+        Subtensor::set_max_allowed_validators( 2 );
+        // Shifted weights.
+        let weights_matrix: Vec<Vec<u32>> = vec! [
+            vec! [0, u32::max_value(), 0, 0 ],
+            vec! [0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, u32::max_value() ], 
+            vec! [u32::max_value(), 0, 0, 0 ],
+        ];
+        Subtensor::set_weights_from_matrix( weights_matrix.clone() );
+
+        step_block (1);
+
+        assert_eq!( Subtensor::get_validator_permit(), vec![ true, true, false, false ] );
+        assert!( vec_approx_equals ( &Subtensor::get_ranks(), &vec![0, u64m / 7 * 4, u64m / 7 * 3, 0], 1000) );
+        assert!( vec_approx_equals ( &Subtensor::get_trust(), &vec![0, u64m / 7 * 4, u64m / 7 * 3, 0], 1000) );
+        let expected_bonds: Vec<Vec<u64>> = vec! [
+            vec! [0, 285714285, 0, 0 ],
+            vec! [0, 0, 214285714, 0 ],
+            vec! [0, 0, 0, 0 ], 
+            vec! [0, 0, 0, 0 ],
+        ]; // 1,000,000,000 * stake * 1/2
+        assert!( mat_approx_equals ( &Subtensor::get_bonds(), &expected_bonds, 10) );
+    });
+}
+
+// Runs a single step where two equally staked validators agree on two miners, under the given consensus mode.
+fn run_agreeing_validators_step( consensus_mode: u8 ) -> ( Vec<u64>, Vec<u64>, Vec<u64>, Vec<u64>, Vec<Vec<u64>> ) {
+    let mut result = ( vec![], vec![], vec![], vec![], vec![] );
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_consensus_mode( consensus_mode );
+        for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        Subtensor::set_stake_from_vector( vec![ 1000000000, 1000000000, 0, 0 ] );
+        let weights_matrix: Vec<Vec<u32>> = vec! [
+            vec! [0, 0, u32::max_value() / 2, u32::max_value() / 2 ],
+            vec! [0, 0, u32::max_value() / 2, u32::max_value() / 2 ],
+            vec! [0, 0, u32::max_value(), 0 ], 
+            vec! [0, 0, 0, u32::max_value() ],
+        ];
+        Subtensor::set_weights_from_matrix( weights_matrix );
+        step_block (1);
+        result = ( Subtensor::get_stake(), Subtensor::get_ranks(), Subtensor::get_incentive(), Subtensor::get_emission(), Subtensor::get_bonds() );
+    });
+    result
+}
+
+#[test]
+fn test_step_consensus_modes_agree_without_outliers() {
+    // When all validators agree nothing is clipped and both modes distribute the same emission.
+    let ( stake_0, ranks_0, incentive_0, emission_0, bonds_0 ) = run_agreeing_validators_step( 0 );
+    let ( stake_1, ranks_1, incentive_1, emission_1, bonds_1 ) = run_agreeing_validators_step( 1 );
+    assert!( vec_approx_equals ( &stake_0, &stake_1, 10) );
+    assert!( vec_approx_equals ( &ranks_0, &ranks_1, 1000) );
+    assert!( vec_approx_equals ( &incentive_0, &incentive_1, 1000) );
+    assert!( vec_approx_equals ( &emission_0, &emission_1, 10) );
+    assert!( mat_approx_equals ( &bonds_0, &bonds_1, 10) );
+}
+
+#[test]
+fn test_step_with_weight_clipping() {
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_consensus_mode( 1 );
+        let u64m: u64 = 18446744073709551615;
+        for i in 0..5 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        // Two large validators agree on miner 3, a small validator weights miner 4 alone.
+        Subtensor::set_stake_from_vector( vec![ 4000000000, 4000000000, 1000000000, 0, 0 ] );
+        let weights_matrix: Vec<Vec<u32>> = vec! [
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, 0, u32::max_value() ], 
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, 0, u32::max_value() ],
+        ];
+        Subtensor::set_weights_from_matrix( weights_matrix );
+
+        step_block (1);
+
+        // Miner 4 lacks kappa of the stake supporting it, its weight is clipped to zero.
+        let ranks: Vec<u64> = Subtensor::get_ranks();
+        let incentive: Vec<u64> = Subtensor::get_incentive();
+        assert_eq!( ranks[4], 0 );
+        assert_eq!( incentive[4], 0 );
+        assert!( approx_equals( incentive[3], u64m, 1000 ) );
+        assert!( approx_equals( Subtensor::get_trust()[3], u64m, 1000 ) );
+        assert_eq!( Subtensor::get_trust()[4], 0 );
+        assert_eq!( Subtensor::get_emission()[4], 0 );
+        let bonds: Vec<Vec<u64>> = Subtensor::get_bonds();
+        assert_eq!( bonds[2][4], 0 );
+        assert!( approx_equals( bonds[0][3], 222222222, 10 ) ); // 1,000,000,000 * 4/9 * 1/2
+        assert!( approx_equals( bonds[1][3], 222222222, 10 ) );
+    });
+}
+
 #[test]
 fn test_two_steps_with_reset_bonds() {
     new_test_ext().execute_with( || {
         Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_kappa( 1073741824 ); // 0.25, a single validator backs each weight.
         let initial_stake:u64 = 1000000000;
         for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
         // Set stake.
@@ -431,6 +538,7 @@ fn test_step_only_every_3_with_many_outward_weights() {
 fn test_two_steps_with_activity_cuttoff() {
     new_test_ext().execute_with( || {
         Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_kappa( 1073741824 ); // 0.25, a single validator backs each weight.
         let initial_stake:u64 = 1000000000;
         let u64m: u64 = 18446744073709551615;
         for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
@@ -458,7 +566,7 @@ fn test_two_steps_with_activity_cuttoff() {
         assert!( vec_approx_equals ( &Subtensor::get_ranks(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_trust(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert_eq!( Subtensor::get_active(), vec![1; 4] );
-        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![1399336432749266785, 1399336432749266785, 1399336432749266785, 1399336432749266785], 10) );
+        assert!( vec_approx_equals ( &Subtensor::get_consensus(), &vec![u64m/2, u64m/2, u64m/2, u64m/2], 10) );
         assert!( vec_approx_equals ( &Subtensor::get_incentive(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_dividends(), &vec![u64m/4, u64m/4, u64m/4, u64m/4], 100) );
         assert!( vec_approx_equals ( &Subtensor::get_emission(), &vec![250000000, 250000000, 250000000, 250000000], 10) );
@@ -670,3 +778,560 @@ fn test_step_with_neuron_with_balances() {
         assert_eq!( Subtensor::get_dividends(), vec![0] );
     });
 }
+
+fn shifted_weights_snapshot() -> EpochSnapshot {
+    EpochSnapshot {
+        n: 4,
+        uids: vec![ 0, 1, 2, 3 ],
+        block_emission: 1000000000,
+        stake: vec![ 1000000000; 4 ],
+        active: vec![ true; 4 ],
+        validator_permit: vec![ true; 4 ],
+        weights: vec! [
+            vec! [ (1, u32::max_value()) ],
+            vec! [ (2, u32::max_value()) ],
+            vec! [ (3, u32::max_value()) ],
+            vec! [ (0, u32::max_value()) ],
+        ],
+        bonds: vec![ vec![ 0; 4 ]; 4 ],
+        rho: 10,
+        kappa: 2147483648,
+        self_ownership: 2147483648,
+        bonds_moving_average: 500000,
+        consensus_mode: 0,
+    }
+}
+
+#[test]
+fn test_default_mechanism_on_snapshot() {
+    let u64m: u64 = 18446744073709551615;
+    let mut snapshot: EpochSnapshot = shifted_weights_snapshot();
+    snapshot.kappa = 1073741824; // 0.25, a single validator backs each weight.
+    let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+    assert!( vec_approx_equals ( &output.ranks, &vec![ u64m / 4; 4 ], 1000) );
+    assert!( vec_approx_equals ( &output.incentive, &vec![ u64m / 4; 4 ], 1000) );
+    assert!( vec_approx_equals ( &output.emission, &vec![ 250000000; 4 ], 10) );
+    assert_eq!( output.bonds, vec! [
+        vec! [ (1, 125000000) ],
+        vec! [ (2, 125000000) ],
+        vec! [ (3, 125000000) ],
+        vec! [ (0, 125000000) ],
+    ]); // 250,000,000 * 1/2
+    assert_eq!( output.bonds_purchased, 500000000 );
+}
+
+#[test]
+fn test_epoch_snapshot_matches_storage() {
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        Subtensor::set_stake_from_vector( vec![ 1000000000; 4 ] );
+        Subtensor::set_weights_from_matrix( vec! [
+            vec! [0, u32::max_value(), 0, 0 ],
+            vec! [0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, u32::max_value() ], 
+            vec! [u32::max_value(), 0, 0, 0 ],
+        ]);
+        let snapshot: EpochSnapshot = Subtensor::get_epoch_snapshot( 1000000000 );
+        assert_eq!( snapshot, shifted_weights_snapshot() );
+
+        // Stepping the chain applies the same output as running the mechanism on the snapshot.
+        let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+        step_block (1);
+        assert_eq!( Subtensor::get_ranks(), output.ranks );
+        assert_eq!( Subtensor::get_emission(), output.emission );
+    });
+}
+
+#[test]
+fn test_alternative_mechanism_on_snapshot() {
+    let mut snapshot: EpochSnapshot = shifted_weights_snapshot();
+    snapshot.stake = vec![ 3000000000, 1000000000, 0, 0 ];
+    let output: EpochOutput = StakeProportionalMechanism::run( &snapshot );
+    assert_eq!( output.emission, vec![ 750000000, 250000000, 0, 0 ] );
+    assert_eq!( output.bonds, vec![ vec![]; 4 ] );
+}
+
+#[test]
+fn test_step_runs_configured_mechanism() {
+    let mut expected: Vec<u64> = vec![];
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_stake_from_vector( vec![ 3000000000, 1000000000, 0, 0 ] );
+        step_block (1);
+        expected = Subtensor::get_emission();
+    });
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_stake_from_vector( vec![ 3000000000, 1000000000, 0, 0 ] );
+        use_stake_proportional_mechanism( true );
+        step_block (1);
+        // The runtime paid out the output of the mechanism set on Config, not the default one.
+        assert_eq!( Subtensor::get_emission(), vec![ 750000000, 250000000, 0, 0 ] );
+        assert_ne!( Subtensor::get_emission(), expected );
+        assert_eq!( Subtensor::get_bonds(), vec![ vec![ 0; 4 ]; 4 ] );
+    });
+}
+
+// Registers 4 staked neurons with shifted weights.
+fn setup_shifted_weights_network() {
+    Subtensor::set_max_registratations_per_block( 100 );
+    for i in 0..4 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+    Subtensor::set_stake_from_vector( vec![ 1000000000; 4 ] );
+    Subtensor::set_kappa( 1073741824 ); // 0.25, each validator alone backs its weight so vtrust stays one.
+    Subtensor::set_weights_from_matrix( vec! [
+        vec! [0, u32::max_value(), 0, 0 ],
+        vec! [0, 0, u32::max_value(), 0 ],
+        vec! [0, 0, 0, u32::max_value() ], 
+        vec! [u32::max_value(), 0, 0, 0 ],
+    ]);
+}
+
+#[test]
+fn test_chunked_epoch_matches_single_block_epoch() {
+    let mut expected = ( vec![], vec![], vec![], vec![] );
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        step_block (1);
+        expected = ( Subtensor::get_stake(), Subtensor::get_ranks(), Subtensor::get_emission(), Subtensor::get_bonds() );
+    });
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_epoch_chunk_size( 1 );
+        step_block (1);
+        assert!( Subtensor::epoch_in_progress() );
+        // 3 more snapshot blocks, 4 consensus, 4 ranks, one incentive, 4 dividends and 4 commit blocks.
+        step_block (20);
+        assert!( !Subtensor::epoch_in_progress() );
+        assert_eq!( ( Subtensor::get_stake(), Subtensor::get_ranks(), Subtensor::get_emission(), Subtensor::get_bonds() ), expected );
+    });
+}
+
+#[test]
+fn test_chunked_epoch_runs_every_phase_in_chunks() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_epoch_chunk_size( 2 );
+        let phases: Vec<EpochPhase> = (0..10).map( |_| { step_block (1); Subtensor::current_epoch().phase } ).collect();
+        assert_eq!( phases, vec![
+            EpochPhase::Snapshot, // Snapshot uids 0, 1.
+            EpochPhase::Consensus, // Snapshot uids 2, 3.
+            EpochPhase::Consensus,
+            EpochPhase::Ranks,
+            EpochPhase::Ranks,
+            EpochPhase::Incentive,
+            EpochPhase::Dividends,
+            EpochPhase::Dividends,
+            EpochPhase::Commit,
+            EpochPhase::Commit, // Commit uids 0, 1.
+        ]);
+        let ranks: Vec<u64> = Subtensor::get_ranks();
+        assert!( ranks[0..2].iter().all( |rank| *rank > 0 ) );
+        assert_eq!( ranks[2..4].to_vec(), vec![ 0, 0 ] );
+        assert_eq!( Subtensor::get_stake()[2..4].to_vec(), vec![ 1000000000; 2 ] );
+        step_block (1); // Commit uids 2, 3.
+        assert_eq!( Subtensor::current_epoch().phase, EpochPhase::Idle );
+        assert!( Subtensor::get_ranks().iter().all( |rank| *rank > 0 ) );
+        assert!( vec_approx_equals( &Subtensor::get_emission(), &vec![ 250000000; 4 ], 10 ) );
+        assert_eq!( Subtensor::get_epoch_history().len(), 1 );
+    });
+}
+
+#[test]
+fn test_chunked_epoch_accumulates_emission_for_missed_steps() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_epoch_chunk_size( 4 );
+        step_block (6); // One block per phase.
+        assert!( !Subtensor::epoch_in_progress() );
+        assert_eq!( Subtensor::get_emission().iter().sum::<u64>(), 1000000000 );
+        // The next epoch emits for the five blocks spent running the previous one.
+        step_block (6);
+        assert!( approx_equals( Subtensor::get_emission().iter().sum::<u64>(), 6000000000, 10 ) );
+    });
+}
+
+#[test]
+fn test_chunked_epoch_skips_neurons_registered_during_epoch() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_epoch_chunk_size( 1 );
+        step_block (1);
+        register_ok_neuron_with_nonce( 4, 4, 4000000000 );
+        Subtensor::add_stake_to_neuron_hotkey_account( 4, 1000000000 );
+        step_block (20);
+        assert!( !Subtensor::epoch_in_progress() );
+        let neuron = Subtensor::get_neuron_for_uid( 4 );
+        assert_eq!( neuron.stake, 1000000000 );
+        assert_eq!( neuron.emission, 0 );
+        assert_eq!( neuron.rank, 0 );
+        assert!( vec_approx_equals( &Subtensor::get_emission()[0..4].to_vec(), &vec![ 250000000; 4 ], 10 ) );
+    });
+}
+
+#[test]
+fn test_chunked_epoch_withholds_emission_of_replaced_uid() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_max_allowed_uids( 4 );
+        Subtensor::set_epoch_chunk_size( 1 );
+        step_block (1);
+        // The graph is full, the new neuron replaces one of the uids read by the epoch.
+        let uid: u32 = register_ok_neuron_with_nonce( 4, 4, 4000000000 ).uid;
+        assert!( uid < 4 );
+        let evicted_coldkey: u64 = uid as u64;
+        let evicted_balance: u128 = Subtensor::get_coldkey_balance( &evicted_coldkey );
+        let total_issuance: u64 = Subtensor::get_total_issuance();
+        step_block (20);
+        assert!( !Subtensor::epoch_in_progress() );
+
+        // Neither the new owner nor the replaced one receive the emission of the uid, it is not issued.
+        let emission: Vec<u64> = Subtensor::get_emission();
+        assert_eq!( emission[ uid as usize ], 0 );
+        assert!( approx_equals( emission.iter().sum::<u64>(), 750000000, 10 ) );
+        assert_eq!( pallet_subtensor::TotalEmission::<Test>::get(), emission.iter().sum::<u64>() );
+        assert_eq!( Subtensor::get_total_issuance(), total_issuance + emission.iter().sum::<u64>() );
+        assert_eq!( Subtensor::get_coldkey_balance( &evicted_coldkey ), evicted_balance );
+        assert_eq!( Subtensor::get_stake().iter().sum::<u64>(), Subtensor::get_total_stake() );
+
+        // The new owner keeps its fresh state.
+        let neuron = Subtensor::get_neuron_for_uid( uid );
+        assert_eq!( neuron.hotkey, 4 );
+        assert_eq!( neuron.emission, 0 );
+        assert_eq!( neuron.stake, 0 );
+        assert_eq!( neuron.rank, 0 );
+        assert!( neuron.bonds.is_empty() );
+        let record = Subtensor::get_epoch_history().last().unwrap().clone();
+        assert_eq!( record.emission[ uid as usize ], 0 );
+        assert_eq!( record.ranks[ uid as usize ], 0 );
+    });
+}
+
+#[test]
+fn test_epoch_history_keeps_last_epochs() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        assert_eq!( Subtensor::get_epoch_history_length(), 3 );
+        step_block (5);
+        assert_eq!( Subtensor::get_epoch_counter(), 5 );
+        let history = Subtensor::get_epoch_history();
+        assert_eq!( history.len(), 3 );
+        assert_eq!( history.iter().map( |record| record.block ).collect::<Vec<u64>>(), vec![ 3, 4, 5 ] );
+        let last = history.last().unwrap();
+        assert_eq!( last.emission, Subtensor::get_emission() );
+        assert_eq!( last.ranks, Subtensor::get_ranks() );
+        assert_eq!( last.total_emission, last.emission.iter().sum::<u64>() );
+        assert_eq!( last.total_bonds_purchased, pallet_subtensor::TotalBondsPurchased::<Test>::get() );
+        // The dropped epochs are removed from storage.
+        assert!( Subtensor::epoch_history( 1 ).is_none() );
+    });
+}
+
+#[test]
+fn test_epoch_history_for_uid() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        step_block (2);
+        let history = Subtensor::get_epoch_history_for_uid( 1 );
+        assert_eq!( history.len(), 2 );
+        assert_eq!( history[1].emission, Subtensor::get_neuron_for_uid( 1 ).emission );
+        assert_eq!( history[1].rank, Subtensor::get_neuron_for_uid( 1 ).rank );
+        assert_eq!( Subtensor::get_epoch_history_for_uid( 10 ).len(), 0 );
+    });
+}
+
+#[test]
+fn test_epoch_history_shrinks() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        step_block (3);
+        Subtensor::set_epoch_history_length( 1 );
+        assert_eq!( Subtensor::get_epoch_history().len(), 1 );
+        assert!( Subtensor::epoch_history( 0 ).is_none() );
+        assert!( Subtensor::epoch_history( 1 ).is_none() );
+        assert!( Subtensor::epoch_history( 2 ).is_some() );
+        Subtensor::set_epoch_history_length( 0 );
+        step_block (1);
+        assert_eq!( Subtensor::get_epoch_history().len(), 0 );
+        assert!( Subtensor::epoch_history( 3 ).is_none() );
+    });
+}
+
+#[test]
+fn test_epoch_completed_event() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        step_block (1);
+        let total_emission: u64 = Subtensor::get_emission().iter().sum();
+        let expected = Event::Subtensor( pallet_subtensor::Event::EpochCompleted( 1, 4, total_emission, pallet_subtensor::TotalBondsPurchased::<Test>::get(), 4000000000 ) );
+        assert!( System::events().iter().any( |record| record.event == expected ) );
+    });
+}
+
+#[test]
+fn test_neuron_pruned_events() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_max_allowed_uids( 4 );
+        step_block (1);
+        register_ok_neuron_with_nonce( 4, 4, 4000000000 );
+        let uid: u32 = Subtensor::get_uid_for_hotkey( &4 );
+        let expected = Event::Subtensor( pallet_subtensor::Event::NeuronPruned( uid, uid as u64 ) );
+        assert_eq!( System::events().iter().filter( |record| record.event == expected ).count(), 1 );
+        // Clearing the bonds held in the uid at the next epoch does not emit it again.
+        step_block (1);
+        assert_eq!( System::events().iter().filter( |record| record.event == expected ).count(), 1 );
+        assert!( !Subtensor::will_be_prunned( uid ) );
+
+        // The next eviction reports the hotkey it evicted, also when the same uid is evicted again.
+        Subtensor::set_immunity_period( 0 );
+        register_ok_neuron_with_nonce( 5, 5, 5000000000 );
+        let second_uid: u32 = Subtensor::get_uid_for_hotkey( &5 );
+        let evicted_hotkey: u64 = if second_uid == uid { 4 } else { second_uid as u64 };
+        let expected = Event::Subtensor( pallet_subtensor::Event::NeuronPruned( second_uid, evicted_hotkey ) );
+        assert_eq!( System::events().iter().filter( |record| record.event == expected ).count(), 1 );
+    });
+}
+
+#[test]
+fn test_bonds_reset_every_two_epochs() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_validator_epochs_per_reset( 2 );
+        step_block (1);
+        assert!( Subtensor::get_bonds().iter().any( |row| row.iter().any( |bonds_ij| *bonds_ij > 0 ) ) );
+        step_block (1);
+        assert!( Subtensor::get_bonds().iter().all( |row| row.iter().all( |bonds_ij| *bonds_ij == 0 ) ) );
+        assert!( System::events().iter().any( |record| record.event == Event::Subtensor( pallet_subtensor::Event::ResetBonds() ) ) );
+        step_block (1);
+        assert!( Subtensor::get_bonds().iter().any( |row| row.iter().any( |bonds_ij| *bonds_ij > 0 ) ) );
+    });
+}
+
+#[test]
+fn test_bonds_reset_with_retention() {
+    let mut expected: Vec<Vec<u64>> = vec![];
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        step_block (1);
+        expected = Subtensor::get_bonds().iter().map( |row| row.iter().map( |bonds_ij| bonds_ij / 2 ).collect() ).collect();
+    });
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_validator_epochs_per_reset( 1 );
+        Subtensor::set_bonds_reset_retention( 500000 );
+        step_block (1);
+        assert!( mat_approx_equals( &Subtensor::get_bonds(), &expected, 1 ) );
+    });
+}
+
+#[test]
+fn test_chunked_bonds_reset() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_epoch_chunk_size( 2 );
+        Subtensor::set_validator_epochs_per_reset( 1 );
+        Subtensor::set_bonds_reset_chunk_size( 2 );
+        step_block (11); // Two blocks per phase, one incentive block.
+        assert!( !Subtensor::epoch_in_progress() );
+        assert!( Subtensor::bonds_reset_in_progress() );
+        assert!( Subtensor::get_bonds().iter().all( |row| row.iter().any( |bonds_ij| *bonds_ij > 0 ) ) );
+        step_block (1); // Reset uids 0, 1.
+        let bonds: Vec<Vec<u64>> = Subtensor::get_bonds();
+        assert!( bonds[0..2].iter().all( |row| row.iter().all( |bonds_ij| *bonds_ij == 0 ) ) );
+        assert!( bonds[2..4].iter().all( |row| row.iter().any( |bonds_ij| *bonds_ij > 0 ) ) );
+        assert!( !System::events().iter().any( |record| record.event == Event::Subtensor( pallet_subtensor::Event::ResetBonds() ) ) );
+        step_block (1); // Reset uids 2, 3.
+        assert!( !Subtensor::bonds_reset_in_progress() );
+        assert!( Subtensor::get_bonds().iter().all( |row| row.iter().all( |bonds_ij| *bonds_ij == 0 ) ) );
+        assert!( System::events().iter().any( |record| record.event == Event::Subtensor( pallet_subtensor::Event::ResetBonds() ) ) );
+    });
+}
+
+#[test]
+fn test_unchunked_epoch_bonds_reset_is_bounded() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_validator_epochs_per_reset( 1 );
+        Subtensor::set_bonds_reset_chunk_size( 3 );
+        step_block (1); // The epoch completes and resets uids 0, 1, 2 within the block.
+        let bonds: Vec<Vec<u64>> = Subtensor::get_bonds();
+        assert!( Subtensor::bonds_reset_in_progress() );
+        assert!( bonds[0..3].iter().all( |row| row.iter().all( |bonds_ij| *bonds_ij == 0 ) ) );
+        assert!( bonds[3].iter().any( |bonds_ij| *bonds_ij > 0 ) );
+    });
+}
+
+#[test]
+fn test_emission_destinations() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        assert_ok!( Subtensor::set_emission_destination( <<Test as frame_system::Config>::Origin>::signed( 1 ), 1, EmissionDestination::Coldkey ) );
+        assert_ok!( Subtensor::set_emission_destination( <<Test as frame_system::Config>::Origin>::signed( 2 ), 2, EmissionDestination::Split( 40 ) ) );
+        step_block (1);
+        let emission: Vec<u64> = Subtensor::get_emission();
+        let stake: Vec<u64> = Subtensor::get_stake();
+        assert!( emission.iter().all( |emission_i| *emission_i > 0 ) );
+
+        // Stake destination.
+        assert_eq!( stake[0], 1000000000 + emission[0] );
+        assert_eq!( Subtensor::get_coldkey_balance( &0 ), 0 );
+
+        // Coldkey destination.
+        assert_eq!( stake[1], 1000000000 );
+        assert_eq!( Subtensor::get_coldkey_balance( &1 ) as u64, emission[1] );
+
+        // Split destination.
+        let emission_to_coldkey: u64 = emission[2] * 40 / 100;
+        assert_eq!( stake[2], 1000000000 + emission[2] - emission_to_coldkey );
+        assert_eq!( Subtensor::get_coldkey_balance( &2 ) as u64, emission_to_coldkey );
+
+        // Total stake only counts staked emission.
+        assert_eq!( Subtensor::get_total_stake(), stake.iter().sum::<u64>() );
+    });
+}
+
+#[test]
+fn test_self_ownership_from_storage() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        assert_ok!( Subtensor::sudo_set_self_ownership( admin_origin(), 1 << 32 ) );
+        step_block (2); // The second step has bonds to pay dividends on.
+        // Peers keep all of their incentive, dividends follow incentive.
+        assert!( Subtensor::get_bonds().iter().any( |row| row.iter().any( |bonds_ij| *bonds_ij > 0 ) ) );
+        assert_eq!( Subtensor::get_dividends(), Subtensor::get_incentive() );
+    });
+}
+
+#[test]
+fn test_block_emission_from_storage() {
+    new_test_ext().execute_with( || {
+        setup_shifted_weights_network();
+        Subtensor::set_block_emission( 500000000 );
+        step_block (1);
+        assert!( approx_equals( Subtensor::get_emission().iter().sum::<u64>(), 500000000, 10 ) );
+    });
+}
+
+#[test]
+fn test_vtrust_with_weight_clipping() {
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_consensus_mode( 1 );
+        let u64m: u64 = 18446744073709551615;
+        for i in 0..5 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        // Two large validators agree on miner 3, a small validator weights miner 4 alone.
+        Subtensor::set_stake_from_vector( vec![ 4000000000, 4000000000, 1000000000, 0, 0 ] );
+        Subtensor::set_weights_from_matrix( vec! [
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, 0, u32::max_value() ], 
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, 0, u32::max_value() ],
+        ]);
+        step_block (1);
+        let vtrust: Vec<u64> = Subtensor::get_vtrust();
+        assert!( approx_equals( vtrust[0], u64m, 1000 ) );
+        assert!( approx_equals( vtrust[1], u64m, 1000 ) );
+        assert_eq!( vtrust[2], 0 ); // All of its weight was clipped.
+        assert_eq!( Subtensor::validator_trust( 2 ), 0 );
+    });
+}
+
+#[test]
+fn test_vtrust_without_weight_clipping() {
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        Subtensor::set_consensus_mode( 0 );
+        let u64m: u64 = 18446744073709551615;
+        for i in 0..5 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        // Two large validators agree on miner 3, a small validator diverges to miner 4.
+        Subtensor::set_stake_from_vector( vec![ 4000000000, 4000000000, 1000000000, 0, 0 ] );
+        Subtensor::set_weights_from_matrix( vec! [
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, 0, u32::max_value() ],
+            vec! [0, 0, 0, u32::max_value(), 0 ],
+            vec! [0, 0, 0, 0, u32::max_value() ],
+        ]);
+        step_block (1);
+        let vtrust: Vec<u64> = Subtensor::get_vtrust();
+        assert!( approx_equals( vtrust[0], u64m, 1000 ) );
+        assert!( approx_equals( vtrust[1], u64m, 1000 ) );
+        assert_eq!( vtrust[2], 0 ); // None of its weight is backed by consensus.
+        assert_eq!( Subtensor::validator_trust( 2 ), 0 );
+        assert!( Subtensor::get_ranks()[4] > 0 ); // Ranks are not clipped.
+    });
+}
+
+#[test]
+fn test_vtrust_in_neuron_info() {
+    new_test_ext().execute_with( || {
+        Subtensor::set_max_registratations_per_block( 100 );
+        for i in 0..3 { let nonce:u64 = 1000000000*i; register_ok_neuron_with_nonce(i as u64, i as u64, nonce); }
+        Subtensor::set_stake_from_vector( vec![ 4000000000, 4000000000, 0 ] );
+        Subtensor::set_weights_from_matrix( vec! [
+            vec! [0, 0, u32::max_value() ],
+            vec! [0, 0, u32::max_value() ],
+            vec! [0, 0, u32::max_value() ],
+        ]);
+        step_block (1);
+        // The metagraph view carries the vtrust of every uid next to its metadata.
+        let neurons = Subtensor::get_neurons_info();
+        assert_eq!( neurons.len(), 3 );
+        let vtrust: Vec<u64> = Subtensor::get_vtrust();
+        for ( uid_i, info ) in neurons.iter().enumerate() {
+            assert_eq!( info.neuron.uid, uid_i as u32 );
+            assert_eq!( info.vtrust, vtrust[ uid_i ] );
+            assert_eq!( info.validator_permit, Subtensor::has_validator_permit( uid_i as u32 ) );
+        }
+        assert!( neurons[0].vtrust > 0 );
+        assert_eq!( Subtensor::get_neuron_info( 0 ).unwrap().vtrust, vtrust[0] );
+        assert!( Subtensor::get_neuron_info( 3 ).is_none() );
+    });
+}
+
+#[test]
+fn test_vtrust_scales_dividends() {
+    // Validator 2 holds bonds in miner 3 but now weights miner 4 against consensus.
+    let mut snapshot: EpochSnapshot = EpochSnapshot {
+        n: 5,
+        uids: vec![ 0, 1, 2, 3, 4 ],
+        block_emission: 1000000000,
+        stake: vec![ 4000000000, 4000000000, 1000000000, 0, 0 ],
+        active: vec![ true; 5 ],
+        validator_permit: vec![ true; 5 ],
+        weights: vec! [
+            vec! [ (3, u32::MAX) ],
+            vec! [ (3, u32::MAX) ],
+            vec! [ (4, u32::MAX) ],
+            vec! [ (3, u32::MAX) ],
+            vec! [ (4, u32::MAX) ],
+        ],
+        bonds: vec! [
+            vec! [ 0, 0, 0, 100000000, 0 ],
+            vec! [ 0, 0, 0, 100000000, 0 ],
+            vec! [ 0, 0, 0, 100000000, 0 ],
+            vec! [ 0; 5 ],
+            vec! [ 0; 5 ],
+        ],
+        rho: 10,
+        kappa: 2147483648,
+        self_ownership: 2147483648,
+        bonds_moving_average: 500000,
+        consensus_mode: 1,
+    };
+    let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+    assert_eq!( output.vtrust[2], 0 );
+    assert_eq!( output.dividends[2], 0 );
+    assert_eq!( output.emission[2], 0 );
+    assert!( output.dividends[0] > 0 && output.dividends[1] > 0 );
+
+    // Without weight clipping ranks are not clipped, but vtrust is still measured against the weight consensus.
+    snapshot.consensus_mode = 0;
+    let output: EpochOutput = DefaultIncentiveMechanism::run( &snapshot );
+    assert!( output.ranks[4] > 0 );
+    assert_eq!( output.vtrust[2], 0 );
+    assert_eq!( output.dividends[2], 0 );
+    assert!( output.dividends[0] > 0 && output.dividends[1] > 0 );
+}
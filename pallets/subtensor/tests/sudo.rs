@@ -1,5 +1,6 @@
-use pallet_subtensor::{Error};
+use pallet_subtensor::{Error, Hyperparameter, SubnetHyperparams, SubnetHyperparamsUpdate};
 use frame_support::{assert_ok};
+use frame_support::weights::GetDispatchInfo;
 use frame_system::Config;
 mod mock;
 use mock::*;
@@ -45,7 +46,7 @@ pub fn mat_approx_equals( a_vec: &Vec<Vec<u64>>, b_vec: &Vec<Vec<u64>>, eps: u64
 fn test_sudo_set_rho() {
 	new_test_ext().execute_with(|| {
         let rho: u64 = 11;
-		assert_ok!(Subtensor::sudo_set_rho(<<Test as Config>::Origin>::root(), rho));
+		assert_ok!(Subtensor::sudo_set_rho(admin_origin(), rho));
         assert_eq!(Subtensor::get_rho(), rho);
     });
 }
@@ -54,7 +55,7 @@ fn test_sudo_set_rho() {
 fn test_sudo_set_kappa() {
 	new_test_ext().execute_with(|| {
         let kappa: u64 = 11;
-		assert_ok!(Subtensor::sudo_set_kappa(<<Test as Config>::Origin>::root(), kappa));
+		assert_ok!(Subtensor::sudo_set_kappa(admin_origin(), kappa));
         assert_eq!(Subtensor::get_kappa(), kappa);
     });
 }
@@ -63,7 +64,7 @@ fn test_sudo_set_kappa() {
 fn test_sudo_set_blocks_per_step() {
 	new_test_ext().execute_with(|| {
         let blocks_per_step: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_blocks_per_step(<<Test as Config>::Origin>::root(), blocks_per_step));
+		assert_ok!(Subtensor::sudo_set_blocks_per_step(admin_origin(), blocks_per_step));
         assert_eq!(Subtensor::get_blocks_per_step(), blocks_per_step);
     });
 }
@@ -72,7 +73,7 @@ fn test_sudo_set_blocks_per_step() {
 fn test_sudo_set_bonds_moving_average() {
 	new_test_ext().execute_with(|| {
         let bonds_moving_average: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_bonds_moving_average(<<Test as Config>::Origin>::root(), bonds_moving_average));
+		assert_ok!(Subtensor::sudo_set_bonds_moving_average(admin_origin(), bonds_moving_average));
         assert_eq!(Subtensor::get_bonds_moving_average(), bonds_moving_average);
     });
 }
@@ -80,8 +81,8 @@ fn test_sudo_set_bonds_moving_average() {
 #[test]
 fn test_sudo_set_difficulty() {
 	new_test_ext().execute_with(|| {
-        let difficulty: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_difficulty(<<Test as Config>::Origin>::root(), difficulty));
+        let difficulty: u64 = 20000;
+		assert_ok!(Subtensor::sudo_set_difficulty(admin_origin(), difficulty));
         assert_eq!(Subtensor::get_difficulty_as_u64(), difficulty);
     });
 }
@@ -91,7 +92,7 @@ fn test_sudo_set_difficulty() {
 fn test_sudo_set_adjustment_interval() {
 	new_test_ext().execute_with(|| {
         let adjustment_interval: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_adjustment_interval(<<Test as Config>::Origin>::root(), adjustment_interval));
+		assert_ok!(Subtensor::sudo_set_adjustment_interval(admin_origin(), adjustment_interval));
         assert_eq!(Subtensor::get_adjustment_interval(), adjustment_interval);
 
     });
@@ -101,7 +102,7 @@ fn test_sudo_set_adjustment_interval() {
 fn test_sudo_set_activity_cutoff() {
 	new_test_ext().execute_with(|| {
         let activity_cutoff: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_activity_cutoff(<<Test as Config>::Origin>::root(), activity_cutoff));
+		assert_ok!(Subtensor::sudo_set_activity_cutoff(admin_origin(), activity_cutoff));
         assert_eq!(Subtensor::get_activity_cutoff(), activity_cutoff);
 
     });
@@ -111,7 +112,7 @@ fn test_sudo_set_activity_cutoff() {
 fn test_sudo_target_registrations_per_interval() {
 	new_test_ext().execute_with(|| {
         let target_registrations_per_interval: u64 = 10;
-		assert_ok!(Subtensor::sudo_target_registrations_per_interval(<<Test as Config>::Origin>::root(), target_registrations_per_interval));
+		assert_ok!(Subtensor::sudo_target_registrations_per_interval(admin_origin(), target_registrations_per_interval));
         assert_eq!(Subtensor::get_target_registrations_per_interval(), target_registrations_per_interval);
     });
 }
@@ -120,7 +121,7 @@ fn test_sudo_target_registrations_per_interval() {
 fn test_sudo_set_validator_epoch_len() {
 	new_test_ext().execute_with(|| {
         let validator_epoch_len: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_epoch_len(<<Test as Config>::Origin>::root(), validator_epoch_len));
+		assert_ok!(Subtensor::sudo_set_validator_epoch_len(admin_origin(), validator_epoch_len));
         assert_eq!(Subtensor::get_validator_epoch_len(), validator_epoch_len);
     });
 }
@@ -129,7 +130,7 @@ fn test_sudo_set_validator_epoch_len() {
 fn test_sudo_set_validator_epochs_per_reset() {
 	new_test_ext().execute_with(|| {
         let validator_epochs_per_reset: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_epochs_per_reset(<<Test as Config>::Origin>::root(), validator_epochs_per_reset));
+		assert_ok!(Subtensor::sudo_set_validator_epochs_per_reset(admin_origin(), validator_epochs_per_reset));
         assert_eq!(Subtensor::get_validator_epochs_per_reset(), validator_epochs_per_reset);
     });
 }
@@ -138,7 +139,7 @@ fn test_sudo_set_validator_epochs_per_reset() {
 fn test_sudo_incentive_pruning_denominator() {
 	new_test_ext().execute_with(|| {
         let incentive_pruning_denominator: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_incentive_pruning_denominator(<<Test as Config>::Origin>::root(), incentive_pruning_denominator));
+		assert_ok!(Subtensor::sudo_set_incentive_pruning_denominator(admin_origin(), incentive_pruning_denominator));
         assert_eq!(Subtensor::get_incentive_pruning_denominator(), incentive_pruning_denominator);
     });
 }
@@ -147,7 +148,7 @@ fn test_sudo_incentive_pruning_denominator() {
 fn test_sudo_stake_pruning_denominator() {
 	new_test_ext().execute_with(|| {
         let stake_pruning_denominator: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_stake_pruning_denominator(<<Test as Config>::Origin>::root(), stake_pruning_denominator));
+		assert_ok!(Subtensor::sudo_set_stake_pruning_denominator(admin_origin(), stake_pruning_denominator));
         assert_eq!(Subtensor::get_stake_pruning_denominator(), stake_pruning_denominator);
     });
 }
@@ -156,7 +157,7 @@ fn test_sudo_stake_pruning_denominator() {
 fn test_sudo_stake_pruning_min() {
 	new_test_ext().execute_with(|| {
         let stake_pruning_min: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_stake_pruning_min(<<Test as Config>::Origin>::root(), stake_pruning_min));
+		assert_ok!(Subtensor::sudo_set_stake_pruning_min(admin_origin(), stake_pruning_min));
         assert_eq!(Subtensor::get_stake_pruning_min(), stake_pruning_min);
     });
 }
@@ -165,7 +166,7 @@ fn test_sudo_stake_pruning_min() {
 fn test_sudo_max_weight_limit() {
 	new_test_ext().execute_with(|| {
         let max_weight_limit: u32 = 10;
-		assert_ok!(Subtensor::sudo_set_max_weight_limit(<<Test as Config>::Origin>::root(), max_weight_limit));
+		assert_ok!(Subtensor::sudo_set_max_weight_limit(admin_origin(), max_weight_limit));
         assert_eq!(Subtensor::get_max_weight_limit(), max_weight_limit);
     });
 }
@@ -175,16 +176,124 @@ fn test_sudo_max_weight_limit() {
 fn test_sudo_max_allowed_uids() {
 	new_test_ext().execute_with(|| {
         let max_allowed_uids: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_max_allowed_uids(<<Test as Config>::Origin>::root(), max_allowed_uids));
+		assert_ok!(Subtensor::sudo_set_max_allowed_uids(admin_origin(), max_allowed_uids));
         assert_eq!(Subtensor::get_max_allowed_uids(), max_allowed_uids);
     });
 }
 
+#[test]
+fn test_sudo_set_consensus_mode() {
+	new_test_ext().execute_with(|| {
+        let consensus_mode: u8 = 1;
+		assert_ok!(Subtensor::sudo_set_consensus_mode(admin_origin(), consensus_mode));
+        assert_eq!(Subtensor::get_consensus_mode(), consensus_mode);
+    });
+}
+
+#[test]
+fn test_sudo_set_epoch_chunk_size() {
+	new_test_ext().execute_with(|| {
+        let epoch_chunk_size: u64 = 64;
+		assert_ok!(Subtensor::sudo_set_epoch_chunk_size(admin_origin(), epoch_chunk_size));
+        assert_eq!(Subtensor::get_epoch_chunk_size(), epoch_chunk_size);
+    });
+}
+
+#[test]
+fn test_sudo_set_epoch_history_length() {
+	new_test_ext().execute_with(|| {
+        let epoch_history_length: u64 = 10;
+		assert_ok!(Subtensor::sudo_set_epoch_history_length(admin_origin(), epoch_history_length));
+        assert_eq!(Subtensor::get_epoch_history_length(), epoch_history_length);
+    });
+}
+
+#[test]
+fn test_sudo_set_bonds_reset_chunk_size() {
+	new_test_ext().execute_with(|| {
+        let bonds_reset_chunk_size: u64 = 10;
+		assert_ok!(Subtensor::sudo_set_bonds_reset_chunk_size(admin_origin(), bonds_reset_chunk_size));
+        assert_eq!(Subtensor::get_bonds_reset_chunk_size(), bonds_reset_chunk_size);
+    });
+}
+
+#[test]
+fn test_sudo_set_bonds_reset_retention() {
+	new_test_ext().execute_with(|| {
+        let bonds_reset_retention: u64 = 500000;
+		assert_ok!(Subtensor::sudo_set_bonds_reset_retention(admin_origin(), bonds_reset_retention));
+        assert_eq!(Subtensor::get_bonds_reset_retention(), bonds_reset_retention);
+    });
+}
+
+#[test]
+fn test_sudo_set_inactivity_horizon() {
+	new_test_ext().execute_with(|| {
+        let inactivity_horizon: u64 = 1000;
+		assert_ok!(Subtensor::sudo_set_inactivity_horizon(admin_origin(), inactivity_horizon));
+        assert_eq!(Subtensor::get_inactivity_horizon(), inactivity_horizon);
+    });
+}
+
+#[test]
+fn test_sudo_set_deregistration_batch_size() {
+	new_test_ext().execute_with(|| {
+        let deregistration_batch_size: u64 = 64;
+		assert_ok!(Subtensor::sudo_set_deregistration_batch_size(admin_origin(), deregistration_batch_size));
+        assert_eq!(Subtensor::get_deregistration_batch_size(), deregistration_batch_size);
+    });
+}
+
+#[test]
+fn test_sudo_set_self_ownership() {
+	new_test_ext().execute_with(|| {
+        let self_ownership: u64 = 1073741824; // 0.25
+		assert_ok!(Subtensor::sudo_set_self_ownership(admin_origin(), self_ownership));
+        assert_eq!(Subtensor::get_self_ownership(), self_ownership);
+    });
+}
+
+#[test]
+fn test_sudo_set_minimum_difficulty() {
+	new_test_ext().execute_with(|| {
+        let minimum_difficulty: u64 = 5000;
+		assert_ok!(Subtensor::sudo_set_minimum_difficulty(admin_origin(), minimum_difficulty));
+        assert_eq!(Subtensor::get_minimum_difficulty(), minimum_difficulty);
+    });
+}
+
+#[test]
+fn test_sudo_set_maximum_difficulty() {
+	new_test_ext().execute_with(|| {
+        let maximum_difficulty: u64 = 20000;
+		assert_ok!(Subtensor::sudo_set_maximum_difficulty(admin_origin(), maximum_difficulty));
+        assert_eq!(Subtensor::get_maximum_difficulty(), maximum_difficulty);
+    });
+}
+
+#[test]
+fn test_sudo_set_block_emission() {
+	new_test_ext().execute_with(|| {
+        let block_emission: u64 = 500000000;
+		assert_ok!(Subtensor::sudo_set_block_emission(admin_origin(), block_emission));
+        assert_eq!(Subtensor::get_block_emission(), block_emission);
+    });
+}
+
+#[test]
+fn test_sudo_max_allowed_validators() {
+	new_test_ext().execute_with(|| {
+        let max_allowed_validators: u64 = 10;
+		assert_ok!(Subtensor::sudo_set_max_allowed_validators(admin_origin(), max_allowed_validators));
+        assert_eq!(Subtensor::get_max_allowed_validators(), max_allowed_validators);
+    });
+}
+
 #[test]
 fn test_sudo_min_allowed_weights() {
 	new_test_ext().execute_with(|| {
         let min_allowed_weights: u64 = 1;
-		assert_ok!(Subtensor::sudo_set_min_allowed_weights(<<Test as Config>::Origin>::root(), min_allowed_weights));
+		assert_ok!(Subtensor::sudo_set_min_allowed_weights(admin_origin(), min_allowed_weights));
         assert_eq!(Subtensor::get_min_allowed_weights(), min_allowed_weights);
     });
 }
@@ -193,7 +302,7 @@ fn test_sudo_min_allowed_weights() {
 fn test_sudo_immunity_period() {
 	new_test_ext().execute_with(|| {
         let immunity_period: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_immunity_period(<<Test as Config>::Origin>::root(), immunity_period));
+		assert_ok!(Subtensor::sudo_set_immunity_period(admin_origin(), immunity_period));
         assert_eq!(Subtensor::get_immunity_period(), immunity_period);
     });
 }
@@ -202,7 +311,7 @@ fn test_sudo_immunity_period() {
 fn test_sudo_validator_batch_size() {
 	new_test_ext().execute_with(|| {
         let validator_batch_size: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_batch_size(<<Test as Config>::Origin>::root(), validator_batch_size));
+		assert_ok!(Subtensor::sudo_set_validator_batch_size(admin_origin(), validator_batch_size));
         assert_eq!(Subtensor::get_validator_batch_size(), validator_batch_size);
     });
 }
@@ -211,7 +320,7 @@ fn test_sudo_validator_batch_size() {
 fn test_sudo_validator_sequence_length() {
 	new_test_ext().execute_with(|| {
         let validator_sequence_length: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_sequence_length(<<Test as Config>::Origin>::root(), validator_sequence_length));
+		assert_ok!(Subtensor::sudo_set_validator_sequence_length(admin_origin(), validator_sequence_length));
         assert_eq!(Subtensor::get_validator_sequence_length(), validator_sequence_length);
     });
 }
@@ -226,7 +335,7 @@ fn test_sudo_reset_bonds() {
             vec! [0, 0, 0, 10 ],
         ];
         Subtensor::set_bonds_from_matrix(ten_bonds);
-		assert_ok!(Subtensor::sudo_reset_bonds(<<Test as Config>::Origin>::root()));
+		assert_ok!(Subtensor::sudo_reset_bonds(admin_origin()));
         let zero_bonds: Vec<Vec<u64>> = vec! [
             vec! [0, 0, 0, 0 ],
             vec! [0, 0, 0, 0 ],
@@ -241,7 +350,7 @@ fn test_sudo_reset_bonds() {
 fn test_sudo_scaling_law_power() {
 	new_test_ext().execute_with(|| {
         let scaling_law_power: u8 = 10;
-		assert_ok!(Subtensor::sudo_set_scaling_law_power(<<Test as Config>::Origin>::root(), scaling_law_power));
+		assert_ok!(Subtensor::sudo_set_scaling_law_power(admin_origin(), scaling_law_power));
         assert_eq!(Subtensor::get_scaling_law_power(), scaling_law_power);
     });
 }
@@ -250,7 +359,7 @@ fn test_sudo_scaling_law_power() {
 fn test_sudo_synergy_scaling_law_power() {
 	new_test_ext().execute_with(|| {
         let synergy_scaling_law_power: u8 = 10;
-		assert_ok!(Subtensor::sudo_set_synergy_scaling_law_power(<<Test as Config>::Origin>::root(), synergy_scaling_law_power));
+		assert_ok!(Subtensor::sudo_set_synergy_scaling_law_power(admin_origin(), synergy_scaling_law_power));
         assert_eq!(Subtensor::get_synergy_scaling_law_power(), synergy_scaling_law_power);
     });
 }
@@ -259,7 +368,7 @@ fn test_sudo_synergy_scaling_law_power() {
 fn test_sudo_validator_exclude_quantile() {
 	new_test_ext().execute_with(|| {
         let validator_exclude_quantile: u8 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_exclude_quantile(<<Test as Config>::Origin>::root(), validator_exclude_quantile));
+		assert_ok!(Subtensor::sudo_set_validator_exclude_quantile(admin_origin(), validator_exclude_quantile));
         assert_eq!(Subtensor::get_validator_exclude_quantile(), validator_exclude_quantile);
     });
 }
@@ -268,7 +377,7 @@ fn test_sudo_validator_exclude_quantile() {
 fn test_sudo_validator_prune_len() {
 	new_test_ext().execute_with(|| {
         let validator_prune_len: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_prune_len(<<Test as Config>::Origin>::root(), validator_prune_len));
+		assert_ok!(Subtensor::sudo_set_validator_prune_len(admin_origin(), validator_prune_len));
         assert_eq!(Subtensor::get_validator_prune_len(), validator_prune_len);
     });
 }
@@ -277,11 +386,29 @@ fn test_sudo_validator_prune_len() {
 fn test_sudo_validator_logits_divergence() {
 	new_test_ext().execute_with(|| {
         let validator_logits_divergence: u64 = 10;
-		assert_ok!(Subtensor::sudo_set_validator_logits_divergence(<<Test as Config>::Origin>::root(), validator_logits_divergence));
+		assert_ok!(Subtensor::sudo_set_validator_logits_divergence(admin_origin(), validator_logits_divergence));
         assert_eq!(Subtensor::get_validator_logits_divergence(), validator_logits_divergence);
     });
 }
 
+#[test]
+fn test_sudo_min_stake() {
+	new_test_ext().execute_with(|| {
+        let min_stake: u64 = 5000;
+		assert_ok!(Subtensor::sudo_set_min_stake(admin_origin(), min_stake));
+        assert_eq!(Subtensor::get_min_stake(), min_stake);
+    });
+}
+
+#[test]
+fn test_sudo_min_stake_change() {
+	new_test_ext().execute_with(|| {
+        let min_stake_change: u64 = 100;
+		assert_ok!(Subtensor::sudo_set_min_stake_change(admin_origin(), min_stake_change));
+        assert_eq!(Subtensor::get_min_stake_change(), min_stake_change);
+    });
+}
+
 
 //#########################
 //## sudo failure tests ###
@@ -452,6 +579,126 @@ fn test_fails_sudo_max_weight_limit() {
     });
 }
 
+#[test]
+fn test_fails_sudo_set_consensus_mode() {
+	new_test_ext().execute_with(|| {
+        let consensus_mode: u8 = 1;
+        let init_consensus_mode: u8 = Subtensor::get_consensus_mode();
+		assert_eq!(Subtensor::sudo_set_consensus_mode(<<Test as Config>::Origin>::signed(0), consensus_mode),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_consensus_mode(), init_consensus_mode);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_epoch_chunk_size() {
+	new_test_ext().execute_with(|| {
+        let epoch_chunk_size: u64 = 64;
+        let init_epoch_chunk_size: u64 = Subtensor::get_epoch_chunk_size();
+		assert_eq!(Subtensor::sudo_set_epoch_chunk_size(<<Test as Config>::Origin>::signed(0), epoch_chunk_size),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_epoch_chunk_size(), init_epoch_chunk_size);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_epoch_history_length() {
+	new_test_ext().execute_with(|| {
+        let epoch_history_length: u64 = 10;
+        let init_epoch_history_length: u64 = Subtensor::get_epoch_history_length();
+		assert_eq!(Subtensor::sudo_set_epoch_history_length(<<Test as Config>::Origin>::signed(0), epoch_history_length),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_epoch_history_length(), init_epoch_history_length);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_bonds_reset_chunk_size() {
+	new_test_ext().execute_with(|| {
+        let bonds_reset_chunk_size: u64 = 10;
+        let init_bonds_reset_chunk_size: u64 = Subtensor::get_bonds_reset_chunk_size();
+		assert_eq!(Subtensor::sudo_set_bonds_reset_chunk_size(<<Test as Config>::Origin>::signed(0), bonds_reset_chunk_size),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_bonds_reset_chunk_size(), init_bonds_reset_chunk_size);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_bonds_reset_retention() {
+	new_test_ext().execute_with(|| {
+        let bonds_reset_retention: u64 = 500000;
+        let init_bonds_reset_retention: u64 = Subtensor::get_bonds_reset_retention();
+		assert_eq!(Subtensor::sudo_set_bonds_reset_retention(<<Test as Config>::Origin>::signed(0), bonds_reset_retention),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_bonds_reset_retention(), init_bonds_reset_retention);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_inactivity_horizon() {
+	new_test_ext().execute_with(|| {
+        let inactivity_horizon: u64 = 1000;
+        let init_inactivity_horizon: u64 = Subtensor::get_inactivity_horizon();
+		assert_eq!(Subtensor::sudo_set_inactivity_horizon(<<Test as Config>::Origin>::signed(0), inactivity_horizon),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_inactivity_horizon(), init_inactivity_horizon);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_deregistration_batch_size() {
+	new_test_ext().execute_with(|| {
+        let deregistration_batch_size: u64 = 64;
+        let init_deregistration_batch_size: u64 = Subtensor::get_deregistration_batch_size();
+		assert_eq!(Subtensor::sudo_set_deregistration_batch_size(<<Test as Config>::Origin>::signed(0), deregistration_batch_size),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_deregistration_batch_size(), init_deregistration_batch_size);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_self_ownership() {
+	new_test_ext().execute_with(|| {
+        let self_ownership: u64 = 1073741824;
+        let init_self_ownership: u64 = Subtensor::get_self_ownership();
+		assert_eq!(Subtensor::sudo_set_self_ownership(<<Test as Config>::Origin>::signed(0), self_ownership), Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_self_ownership(), init_self_ownership);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_minimum_difficulty() {
+	new_test_ext().execute_with(|| {
+        let minimum_difficulty: u64 = 5000;
+        let init_minimum_difficulty: u64 = Subtensor::get_minimum_difficulty();
+		assert_eq!(Subtensor::sudo_set_minimum_difficulty(<<Test as Config>::Origin>::signed(0), minimum_difficulty),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_minimum_difficulty(), init_minimum_difficulty);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_maximum_difficulty() {
+	new_test_ext().execute_with(|| {
+        let maximum_difficulty: u64 = 20000;
+        let init_maximum_difficulty: u64 = Subtensor::get_maximum_difficulty();
+		assert_eq!(Subtensor::sudo_set_maximum_difficulty(<<Test as Config>::Origin>::signed(0), maximum_difficulty),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_maximum_difficulty(), init_maximum_difficulty);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_block_emission() {
+	new_test_ext().execute_with(|| {
+        let block_emission: u64 = 500000000;
+        let init_block_emission: u64 = Subtensor::get_block_emission();
+		assert_eq!(Subtensor::sudo_set_block_emission(<<Test as Config>::Origin>::signed(0), block_emission),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_block_emission(), init_block_emission);
+    });
+}
+
+#[test]
+fn test_fails_sudo_max_allowed_validators() {
+	new_test_ext().execute_with(|| {
+        let max_allowed_validators: u64 = 10;
+        let init_max_allowed_validators: u64 = Subtensor::get_max_allowed_validators();
+		assert_eq!(Subtensor::sudo_set_max_allowed_validators(<<Test as Config>::Origin>::signed(0), max_allowed_validators),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_max_allowed_validators(), init_max_allowed_validators);
+    });
+}
+
 
 #[test]
 fn test_fails_sudo_set_validator_epoch_len() {
@@ -531,6 +778,26 @@ fn test_fails_sudo_validator_logits_divergence() {
     });
 }
 
+#[test]
+fn test_fails_sudo_min_stake() {
+	new_test_ext().execute_with(|| {
+        let min_stake: u64 = 5000;
+        let init_min_stake: u64 = Subtensor::get_min_stake();
+		assert_eq!(Subtensor::sudo_set_min_stake(<<Test as Config>::Origin>::signed(0), min_stake),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_min_stake(), init_min_stake);
+    });
+}
+
+#[test]
+fn test_fails_sudo_min_stake_change() {
+	new_test_ext().execute_with(|| {
+        let init_min_stake_change: u64 = Subtensor::get_min_stake_change();
+		assert_eq!(Subtensor::sudo_set_min_stake_change(<<Test as Config>::Origin>::signed(0), 100),  Err(DispatchError::BadOrigin.into()));
+		assert_eq!(Subtensor::sudo_set_min_stake_change(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_min_stake_change(), init_min_stake_change);
+    });
+}
+
 
 //##########################################
 //## sudo set with root; failure due to out of range ##
@@ -541,7 +808,7 @@ fn test_fails_sudo_scaling_law_power_out_of_range() {
 	new_test_ext().execute_with(|| {
         let scaling_law_power: u8 = 101; // max is 100. Should fail
         let init_scaling_law_power: u8 = Subtensor::get_scaling_law_power();
-		assert_eq!(Subtensor::sudo_set_scaling_law_power(<<Test as Config>::Origin>::root(), scaling_law_power),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_scaling_law_power(admin_origin(), scaling_law_power),  Err(Error::<Test>::StorageValueOutOfRange.into()));
         assert_eq!(Subtensor::get_scaling_law_power(), init_scaling_law_power);
     });
 }
@@ -551,7 +818,7 @@ fn test_fails_sudo_synergy_scaling_law_power_out_of_range() {
 	new_test_ext().execute_with(|| {
         let synergy_scaling_law_power: u8 = 101; // max is 100. Should fail
         let init_synergy_scaling_law_power: u8 = Subtensor::get_synergy_scaling_law_power();
-		assert_eq!(Subtensor::sudo_set_synergy_scaling_law_power(<<Test as Config>::Origin>::root(), synergy_scaling_law_power),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_synergy_scaling_law_power(admin_origin(), synergy_scaling_law_power),  Err(Error::<Test>::StorageValueOutOfRange.into()));
         assert_eq!(Subtensor::get_synergy_scaling_law_power(), init_synergy_scaling_law_power);
     });
 }
@@ -561,7 +828,250 @@ fn test_fails_sudo_validator_exclude_quantile_out_of_range() {
 	new_test_ext().execute_with(|| {
         let validator_exclude_quantile: u8 = 101; // max is 100. Should fail
         let init_validator_exclude_quantile: u8 = Subtensor::get_validator_exclude_quantile();
-		assert_eq!(Subtensor::sudo_set_validator_exclude_quantile(<<Test as Config>::Origin>::root(), validator_exclude_quantile),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_validator_exclude_quantile(admin_origin(), validator_exclude_quantile),  Err(Error::<Test>::StorageValueOutOfRange.into()));
         assert_eq!(Subtensor::get_validator_exclude_quantile(), init_validator_exclude_quantile);
     });
 }
+
+#[test]
+fn test_fails_sudo_consensus_mode_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let consensus_mode: u8 = 2; // max is 1. Should fail
+        let init_consensus_mode: u8 = Subtensor::get_consensus_mode();
+		assert_eq!(Subtensor::sudo_set_consensus_mode(admin_origin(), consensus_mode),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_consensus_mode(), init_consensus_mode);
+    });
+}
+
+#[test]
+fn test_fails_sudo_bonds_reset_retention_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let bonds_reset_retention: u64 = 1000001;
+		assert_eq!(Subtensor::sudo_set_bonds_reset_retention(admin_origin(), bonds_reset_retention),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+    });
+}
+
+#[test]
+fn test_fails_sudo_deregistration_batch_size_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_deregistration_batch_size: u64 = Subtensor::get_deregistration_batch_size();
+		assert_eq!(Subtensor::sudo_set_deregistration_batch_size(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_deregistration_batch_size(admin_origin(), 4097),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_deregistration_batch_size(), init_deregistration_batch_size);
+    });
+}
+
+#[test]
+fn test_fails_sudo_kappa_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_kappa: u64 = Subtensor::get_kappa();
+		assert_eq!(Subtensor::sudo_set_kappa(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_kappa(admin_origin(), (1 << 32) + 1),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_kappa(), init_kappa);
+    });
+}
+
+#[test]
+fn test_fails_sudo_self_ownership_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let self_ownership: u64 = (1 << 32) + 1; // max is 1 << 32. Should fail
+        let init_self_ownership: u64 = Subtensor::get_self_ownership();
+		assert_eq!(Subtensor::sudo_set_self_ownership(admin_origin(), self_ownership),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_self_ownership(), init_self_ownership);
+    });
+}
+
+#[test]
+fn test_fails_sudo_difficulty_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_difficulty: u64 = Subtensor::get_difficulty_as_u64();
+		assert_eq!(Subtensor::sudo_set_difficulty(admin_origin(), Subtensor::get_minimum_difficulty() - 1),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_difficulty(admin_origin(), Subtensor::get_maximum_difficulty() + 1),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_difficulty_as_u64(), init_difficulty);
+    });
+}
+
+#[test]
+fn test_fails_sudo_difficulty_bounds_out_of_range() {
+	new_test_ext().execute_with(|| {
+        // The minimum cannot exceed the current difficulty, nor the maximum fall below it.
+		assert_eq!(Subtensor::sudo_set_minimum_difficulty(admin_origin(), Subtensor::get_difficulty_as_u64() + 1),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_maximum_difficulty(admin_origin(), Subtensor::get_difficulty_as_u64() - 1),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_minimum_difficulty(), 10000);
+        assert_eq!(Subtensor::get_maximum_difficulty(), u64::MAX/4);
+    });
+}
+
+#[test]
+fn test_sudo_set_hyperparameters() {
+	new_test_ext().execute_with(|| {
+        let update = SubnetHyperparamsUpdate {
+            rho: Some( 20 ),
+            kappa: Some( 1 << 31 ),
+            immunity_period: Some( 77 ),
+            max_allowed_uids: Some( 512 ),
+            ..Default::default()
+        };
+        let init_hyperparameters: SubnetHyperparams = Subtensor::get_hyperparameters();
+		assert_ok!(Subtensor::sudo_set_hyperparameters(admin_origin(), update));
+        let hyperparameters: SubnetHyperparams = Subtensor::get_hyperparameters();
+        assert_eq!(Subtensor::get_rho(), 20);
+        assert_eq!(Subtensor::get_kappa(), 1 << 31);
+        assert_eq!(Subtensor::get_immunity_period(), 77);
+        assert_eq!(Subtensor::get_max_allowed_uids(), 512);
+        // Fields left as None keep their value.
+        assert_eq!(hyperparameters, SubnetHyperparams { rho: 20, kappa: 1 << 31, immunity_period: 77, max_allowed_uids: 512, ..init_hyperparameters });
+    });
+}
+
+#[test]
+fn test_sudo_set_hyperparameters_weight() {
+	new_test_ext().execute_with(|| {
+        let weight = |update: SubnetHyperparamsUpdate| Call::Subtensor( SubtensorCall::sudo_set_hyperparameters{ update } ).get_dispatch_info().weight;
+        let one_field: u64 = weight( SubnetHyperparamsUpdate { rho: Some( 20 ), ..Default::default() } );
+        let two_fields: u64 = weight( SubnetHyperparamsUpdate { rho: Some( 20 ), kappa: Some( 1 << 31 ), ..Default::default() } );
+        assert!( one_field > 0 );
+        assert!( two_fields > one_field );
+
+        // Shrinking the epoch history is charged for the records it removes.
+        Subtensor::set_epoch_history_length( 10 );
+        let shrink_by_one: u64 = weight( SubnetHyperparamsUpdate { epoch_history_length: Some( 9 ), ..Default::default() } );
+        let shrink_to_zero: u64 = weight( SubnetHyperparamsUpdate { epoch_history_length: Some( 0 ), ..Default::default() } );
+        assert!( shrink_to_zero > shrink_by_one );
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_hyperparameters() {
+	new_test_ext().execute_with(|| {
+        let init_hyperparameters: SubnetHyperparams = Subtensor::get_hyperparameters();
+		assert_eq!(Subtensor::sudo_set_hyperparameters(<<Test as Config>::Origin>::signed(0), SubnetHyperparamsUpdate { rho: Some( 20 ), ..Default::default() }),  Err(DispatchError::BadOrigin.into()));
+        assert_eq!(Subtensor::get_hyperparameters(), init_hyperparameters);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_hyperparameters_out_of_range() {
+	new_test_ext().execute_with(|| {
+        // A single invalid field rejects the whole set.
+        let update = SubnetHyperparamsUpdate {
+            rho: Some( 20 ),
+            immunity_period: Some( 77 ),
+            kappa: Some( 0 ),
+            ..Default::default()
+        };
+        let init_hyperparameters: SubnetHyperparams = Subtensor::get_hyperparameters();
+		assert_eq!(Subtensor::sudo_set_hyperparameters(admin_origin(), update),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_hyperparameters(), init_hyperparameters);
+    });
+}
+
+#[test]
+fn test_sudo_set_hyperparameters_validates_merged_set() {
+	new_test_ext().execute_with(|| {
+        // Raising the difficulty above the current maximum is allowed when the maximum moves with it.
+        let difficulty: u64 = Subtensor::get_maximum_difficulty() + 1;
+        let update = SubnetHyperparamsUpdate {
+            difficulty: Some( difficulty ),
+            maximum_difficulty: Some( difficulty ),
+            ..Default::default()
+        };
+		assert_ok!(Subtensor::sudo_set_hyperparameters(admin_origin(), update));
+        assert_eq!(Subtensor::get_difficulty_as_u64(), difficulty);
+        assert_eq!(Subtensor::get_maximum_difficulty(), difficulty);
+    });
+}
+
+#[test]
+fn test_fails_sudo_blocks_per_step_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_blocks_per_step: u64 = Subtensor::get_blocks_per_step();
+		assert_eq!(Subtensor::sudo_set_blocks_per_step(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_blocks_per_step(), init_blocks_per_step);
+    });
+}
+
+#[test]
+fn test_fails_sudo_adjustment_interval_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_adjustment_interval: u64 = Subtensor::get_adjustment_interval();
+		assert_eq!(Subtensor::sudo_set_adjustment_interval(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_adjustment_interval(), init_adjustment_interval);
+    });
+}
+
+#[test]
+fn test_fails_sudo_rho_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_rho: u64 = Subtensor::get_rho();
+		assert_eq!(Subtensor::sudo_set_rho(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_rho(admin_origin(), 41),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_rho(), init_rho);
+    });
+}
+
+#[test]
+fn test_fails_sudo_bonds_moving_average_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_bonds_moving_average: u64 = Subtensor::get_bonds_moving_average();
+		assert_eq!(Subtensor::sudo_set_bonds_moving_average(admin_origin(), 1_000_001),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_bonds_moving_average(), init_bonds_moving_average);
+    });
+}
+
+#[test]
+fn test_fails_sudo_max_allowed_uids_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_max_allowed_uids: u64 = Subtensor::get_max_allowed_uids();
+		assert_eq!(Subtensor::sudo_set_max_allowed_uids(admin_origin(), 0),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+		assert_eq!(Subtensor::sudo_set_max_allowed_uids(admin_origin(), u32::MAX as u64 + 1),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_max_allowed_uids(), init_max_allowed_uids);
+    });
+}
+
+#[test]
+fn test_fails_sudo_block_emission_out_of_range() {
+	new_test_ext().execute_with(|| {
+        let init_block_emission: u64 = Subtensor::get_block_emission();
+		assert_eq!(Subtensor::sudo_set_block_emission(admin_origin(), 1_000_000_000_001),  Err(Error::<Test>::StorageValueOutOfRange.into()));
+        assert_eq!(Subtensor::get_block_emission(), init_block_emission);
+    });
+}
+
+#[test]
+fn test_hyperparameter_ranges_cover_every_hyperparameter() {
+	new_test_ext().execute_with(|| {
+        let ranges = Subtensor::get_hyperparameter_ranges();
+        assert_eq!(ranges.len(), 39);
+        // The genesis set lies within every declared range.
+        let hyperparameters: SubnetHyperparams = Subtensor::get_hyperparameters();
+        for ( param, range ) in ranges.iter() {
+            let value: u64 = Subtensor::get_hyperparameter_value( &hyperparameters, *param );
+            assert!( range.min <= value && value <= range.max, "{:?} = {} outside of {:?}", param, value, range );
+        }
+        assert_eq!(Subtensor::get_hyperparameter_range( Hyperparameter::Kappa ).min, 1);
+        assert_eq!(Subtensor::get_hyperparameter_range( Hyperparameter::BlocksPerStep ).min, 1);
+        assert_eq!(Subtensor::get_hyperparameter_range( Hyperparameter::AdjustmentInterval ).min, 1);
+    });
+}
+
+#[test]
+fn test_fails_sudo_set_hyperparameters_outside_each_range() {
+	new_test_ext().execute_with(|| {
+        // Every value just outside of a declared range is rejected by the batch setter.
+        let init_hyperparameters: SubnetHyperparams = Subtensor::get_hyperparameters();
+        for ( param, range ) in Subtensor::get_hyperparameter_ranges() {
+            let mut outside: Vec<u64> = vec![];
+            if range.min > 0 { outside.push( range.min - 1 ); }
+            if range.max < u64::MAX { outside.push( range.max + 1 ); }
+            for value in outside {
+                match Subtensor::hyperparameter_update( param, value ) {
+                    Some( update ) => assert_eq!(Subtensor::sudo_set_hyperparameters(admin_origin(), update),  Err(Error::<Test>::StorageValueOutOfRange.into()), "{:?} = {}", param, value),
+                    // The value does not fit the type of the hyperparameter.
+                    None => {}
+                }
+            }
+        }
+        assert_eq!(Subtensor::get_hyperparameters(), init_hyperparameters);
+    });
+}
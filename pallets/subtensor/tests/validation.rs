@@ -0,0 +1,123 @@
+use frame_support::weights::GetDispatchInfo;
+mod mock;
+use mock::*;
+use sp_runtime::traits::SignedExtension;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError};
+use sp_runtime::codec::Encode;
+use pallet_subtensor::SubtensorSignedExtension;
+
+/***********************************************************
+	SubtensorSignedExtension::validate() tests
+************************************************************/
+
+fn validate( who: u64, call: Call ) -> TransactionValidity {
+	let info = call.get_dispatch_info();
+	let len: usize = call.encode().len();
+	SubtensorSignedExtension::<Test>::new().validate( &who, &call, &info, len )
+}
+
+fn invalid( code: u8 ) -> TransactionValidity {
+	Err( TransactionValidityError::Invalid( InvalidTransaction::Custom( code ) ) )
+}
+
+fn register_call( block_number: u64, nonce: u64, work: Vec<u8>, hotkey: u64 ) -> Call {
+	Call::Subtensor( SubtensorCall::register{ block_number, nonce, work, hotkey, coldkey: 667 } )
+}
+
+fn assert_ok_register( block_number: u64, nonce: u64, work: Vec<u8>, hotkey: u64 ) {
+	assert!( Subtensor::register( Origin::signed( hotkey ), block_number, nonce, work, hotkey, 667 ).is_ok() );
+}
+
+#[test]
+fn test_validate_set_weights_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron = register_ok_neuron( 1, 2 );
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![ neuron.uid ], weights: vec![ 1 ] } );
+		let valid = validate( 1, call ).unwrap();
+		assert_eq!( valid.provides, vec![ ("set_weights", 1u64).encode() ] );
+		assert!( valid.requires.is_empty() );
+		assert_eq!( valid.longevity, 1 );
+	});
+}
+
+#[test]
+fn test_validate_set_weights_errors() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![], weights: vec![] } );
+		assert_eq!( validate( 1, call ), invalid( 1 ) ); // NotRegistered
+
+		register_ok_neuron( 1, 2 );
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![ 0, 1 ], weights: vec![ 1 ] } );
+		assert_eq!( validate( 1, call ), invalid( 2 ) ); // WeightVecNotEqualSize
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![ 0, 0 ], weights: vec![ 1, 1 ] } );
+		assert_eq!( validate( 1, call ), invalid( 3 ) ); // DuplicateUids
+		let call = Call::Subtensor( SubtensorCall::set_weights{ dests: vec![ 0, 5 ], weights: vec![ 1, 1 ] } );
+		assert_eq!( validate( 1, call ), invalid( 4 ) ); // InvalidUid
+	});
+}
+
+#[test]
+fn test_validate_register_ok() {
+	new_test_ext().execute_with(|| {
+		let block_number: u64 = 0;
+		let (nonce, work): (u64, Vec<u8>) = Subtensor::create_work_for_block_number( block_number, 0 );
+		let valid = validate( 1, register_call( block_number, nonce, work.clone(), 1 ) ).unwrap();
+		assert_eq!( valid.provides, vec![ ("register", work.clone()).encode() ] );
+		assert!( valid.requires.is_empty() );
+		assert_eq!( valid.longevity, 3 );
+
+		// The same work submitted for another hotkey provides the same tag, so only one sits in the pool.
+		let other = validate( 2, register_call( block_number, nonce, work.clone(), 2 ) ).unwrap();
+		assert_eq!( other.provides, valid.provides );
+
+		// The longevity shrinks as the work block ages.
+		step_block( 2 );
+		assert_eq!( validate( 1, register_call( block_number, nonce, work, 1 ) ).unwrap().longevity, 1 );
+	});
+}
+
+#[test]
+fn test_validate_register_errors() {
+	new_test_ext().execute_with(|| {
+		let (nonce, work): (u64, Vec<u8>) = Subtensor::create_work_for_block_number( 0, 0 );
+		assert_eq!( validate( 1, register_call( 1, nonce, work.clone(), 1 ) ), invalid( 6 ) ); // InvalidWorkBlock
+		assert_eq!( validate( 1, register_call( 0, nonce + 1, work.clone(), 1 ) ), invalid( 9 ) ); // InvalidSeal
+		assert_eq!( validate( 1, register_call( 0, nonce, vec![ 0; 3 ], 1 ) ), invalid( 9 ) ); // Malformed work.
+
+		let difficulty: u64 = Subtensor::get_difficulty_as_u64();
+		Subtensor::set_difficulty_from_u64( 18_446_744_073_709_551_615u64 );
+		assert_eq!( validate( 1, register_call( 0, nonce, work.clone(), 1 ) ), invalid( 8 ) ); // InvalidDifficulty
+		Subtensor::set_difficulty_from_u64( difficulty );
+
+		assert_ok_register( 0, nonce, work.clone(), 1 );
+		assert_eq!( validate( 1, register_call( 0, nonce, work.clone(), 1 ) ), invalid( 5 ) ); // AlreadyRegistered
+		assert_eq!( validate( 2, register_call( 0, nonce, work.clone(), 2 ) ), invalid( 7 ) ); // WorkRepeated
+
+		// Work older than 3 blocks is rejected.
+		step_block( 3 );
+		let (nonce, work): (u64, Vec<u8>) = Subtensor::create_work_for_block_number( 0, 1 );
+		assert_eq!( validate( 3, register_call( 0, nonce, work, 3 ) ), invalid( 6 ) ); // InvalidWorkBlock
+	});
+}
+
+#[test]
+fn test_validate_other_calls_unchanged() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Subtensor( SubtensorCall::add_stake{ hotkey: 1, ammount_staked: 1000 } );
+		let valid = validate( 2, call ).unwrap();
+		assert_eq!( valid.priority, u64::max_value() );
+		assert!( valid.provides.is_empty() );
+	});
+}
+
+#[test]
+fn test_existing_call_indices_unchanged() {
+	// Signed payloads encode the call index, the calls of the initial release keep theirs.
+	assert_eq!( SubtensorCall::set_weights{ dests: vec![], weights: vec![] }.encode()[0], 0 );
+	assert_eq!( SubtensorCall::add_stake{ hotkey: 0, ammount_staked: 0 }.encode()[0], 1 );
+	assert_eq!( SubtensorCall::remove_stake{ hotkey: 0, ammount_unstaked: 0 }.encode()[0], 2 );
+	assert_eq!( SubtensorCall::serve_axon{ version: 0, ip: 0, port: 0, ip_type: 4, modality: 0 }.encode()[0], 3 );
+	assert_eq!( SubtensorCall::register{ block_number: 0, nonce: 0, work: vec![], hotkey: 0, coldkey: 0 }.encode()[0], 4 );
+	assert_eq!( SubtensorCall::sudo_set_blocks_per_step{ blocks_per_step: 0 }.encode()[0], 5 );
+	assert_eq!( SubtensorCall::sudo_set_validator_logits_divergence{ validator_logits_divergence: 0 }.encode()[0], 30 );
+}
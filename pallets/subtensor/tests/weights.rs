@@ -22,7 +22,7 @@ fn test_set_weights_dispatch_info_ok() {
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
 			weight: 0,
 			class: DispatchClass::Normal,
-			pays_fee: Pays::No
+			pays_fee: Pays::Yes
 		});
 	});
 }
@@ -219,3 +219,25 @@ fn test_set_weight_not_enough_values() {
 
 
 
+
+#[test]
+fn test_set_weights_err_no_validator_permit() {
+	new_test_ext().execute_with(|| {
+		Subtensor::set_max_registratations_per_block( 100 );
+		for i in 0..3 { register_ok_neuron_with_nonce(i as u64, i as u64, 1000000 * i as u64); }
+		Subtensor::set_stake_from_vector( vec![ 1000000000, 0, 0 ] );
+		Subtensor::set_max_allowed_validators( 1 );
+		step_block (1);
+		assert_eq!( Subtensor::get_validator_permit(), vec![ true, false, false ] );
+
+		// Should fail because uid 1 does not hold a validator permit.
+		let result = Subtensor::set_weights(Origin::signed(1), vec![0, 2], vec![10, 10]);
+		assert_eq!(result, Err(Error::<Test>::NoValidatorPermit.into()));
+
+		// Shouldnt fail because we are only setting the self weight.
+		assert_ok!( Subtensor::set_weights(Origin::signed(1), vec![1], vec![10]) );
+
+		// Shouldnt fail because uid 0 holds a validator permit.
+		assert_ok!( Subtensor::set_weights(Origin::signed(0), vec![1, 2], vec![10, 10]) );
+	});
+}
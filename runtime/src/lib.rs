@@ -26,7 +26,7 @@ use sp_version::RuntimeVersion;
 // A few exports that help ease life for downstream crates.
 pub use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{ConstU128, ConstU32, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo},
+	traits::{ConstU128, ConstU32, ConstU8, EnsureOneOf, KeyOwnerProofSystem, Randomness, StorageInfo},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
@@ -35,7 +35,6 @@ pub use frame_support::{
 };
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
-use pallet_transaction_payment::CurrencyAdapter;
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
@@ -98,10 +97,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 112,
+	spec_version: 115,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -254,7 +253,7 @@ parameter_types! {
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = pallet_subtensor::SubtensorFeeAdapter<Balances, ()>;
 	type TransactionByteFee = TransactionByteFee;
 	type OperationalFeeMultiplier = ConstU8<5>;
 	type WeightToFee = IdentityFee<Balance>;
@@ -266,17 +265,62 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+	pub const TechnicalMotionDuration: BlockNumber = 3 * HOURS;
+	pub const TechnicalMaxProposals: u32 = 100;
+	pub const TechnicalMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+type TechnicalCollective = pallet_collective::Instance2;
+impl pallet_collective::Config<TechnicalCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = TechnicalMaxProposals;
+	type MaxMembers = TechnicalMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// Hyperparameters are set by two thirds of the council, or by the whole technical committee
+/// to fast-track an emergency change without waiting on a council motion.
+type SubtensorAdminOrigin = EnsureOneOf<
+	pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 1>,
+>;
+
 parameter_types! {
 	pub const SDebug:u64 = 1;
 	pub const InitialRho: u64 = 10;
-	pub const InitialKappa: u64 = 2;
-	pub const SelfOwnership: u64 = 2;
+	pub const InitialKappa: u64 = 2147483648; // 0.5
+	pub const InitialConsensusMode: u8 = 0;
+	pub const InitialSelfOwnership: u64 = 2147483648; // 0.5
 	pub const InitialValidatorBatchSize: u64 = 10;
 	pub const InitialValidatorSequenceLen: u64 = 10;
 	pub const InitialValidatorEpochLen: u64 = 1000;
 	pub const InitialValidatorEpochsPerReset: u64 = 10;
 	pub const InitialValidatorPruneLen: u64 = 0;
 	pub const InitialValidatorLogitsDivergence: u64 = 0;
+	pub const InitialMinStake: u64 = 1_000_000; // 0.001 Tao
+	pub const InitialMinStakeChange: u64 = 1_000_000; // 0.001 Tao
+	pub const InitialRegistrationFeeRefund: bool = true;
+	pub const ColdkeySwapDelay: u64 = (7 * DAYS) as u64;
 	
 	// u8 where value (x) represents x * 10^-2
 	pub const InitialScalingLawPower: u8 = 50; // 0.5
@@ -286,6 +330,13 @@ parameter_types! {
 	pub const InitialImmunityPeriod: u64 = 200;
 	pub const InitialBlocksPerStep: u64 = 100;
 	pub const InitialMaxAllowedUids: u64 = 2000;
+	pub const InitialMaxAllowedValidators: u64 = 128;
+	pub const InitialEpochChunkSize: u64 = 512;
+	pub const InitialEpochHistoryLength: u64 = 16;
+	pub const InitialBondsResetChunkSize: u64 = 256;
+	pub const InitialBondsResetRetention: u64 = 0;
+	pub const InitialInactivityHorizon: u64 = 0;
+	pub const InitialDeregistrationBatchSize: u64 = 64;
 	pub const InitialMinAllowedWeights: u64 = 1;
 	pub const InitialMaxAllowedMaxMinRatio: u64 = 10;
 	pub const InitialMaxWeightLimit: u32 = u32::MAX;
@@ -296,9 +347,10 @@ parameter_types! {
 	pub const InitialStakePruningMin: u64 = 0;
 	pub const InitialFoundationDistribution: u64 = 0;
 	pub const InitialDifficulty: u64 = 10000000;
-	pub const MinimumDifficulty: u64 = 10000000;
+	pub const InitialMinimumDifficulty: u64 = 10000000;
 	pub const InitialActivityCutoff: u64 = 5000;
-	pub const MaximumDifficulty: u64 = u64::MAX/4;
+	pub const InitialMaximumDifficulty: u64 = u64::MAX/4;
+	pub const InitialBlockEmission: u64 = 1000000000;
 	pub const InitialAdjustmentInterval: u64 = 100;
 	pub const InitialMaxRegistrationsPerBlock: u64 = 2;
 	pub const InitialTargetRegistrationsPerInterval: u64 = 2;
@@ -308,22 +360,36 @@ parameter_types! {
 impl pallet_subtensor::Config for Runtime {
 	type Currency = Balances;
 	type Event = Event;
-	type TransactionByteFee = ();
+	type TransactionByteFee = TransactionByteFee;
+	type IncentiveMechanism = pallet_subtensor::DefaultIncentiveMechanism;
+	type AdminOrigin = SubtensorAdminOrigin;
+	type ColdkeySwapDelay = ColdkeySwapDelay;
 	type SDebug = SDebug;
 	type InitialRho = InitialRho;
 	type InitialKappa = InitialKappa;
-	type SelfOwnership = SelfOwnership;
+	type InitialConsensusMode = InitialConsensusMode;
+	type InitialSelfOwnership = InitialSelfOwnership;
 	type InitialScalingLawPower = InitialScalingLawPower;
 	type InitialSynergyScalingLawPower = InitialSynergyScalingLawPower;
 	type InitialValidatorExcludeQuantile = InitialValidatorExcludeQuantile;
 	type InitialValidatorPruneLen = InitialValidatorPruneLen;
 	type InitialValidatorLogitsDivergence = InitialValidatorLogitsDivergence;
+	type InitialMinStake = InitialMinStake;
+	type InitialMinStakeChange = InitialMinStakeChange;
+	type InitialRegistrationFeeRefund = InitialRegistrationFeeRefund;
 	type InitialValidatorBatchSize = InitialValidatorBatchSize;
 	type InitialValidatorSequenceLen = InitialValidatorSequenceLen;
 	type InitialValidatorEpochLen = InitialValidatorEpochLen;
 	type InitialValidatorEpochsPerReset = InitialValidatorEpochsPerReset;
 	type InitialImmunityPeriod = InitialImmunityPeriod;
 	type InitialMaxAllowedUids = InitialMaxAllowedUids;
+	type InitialMaxAllowedValidators = InitialMaxAllowedValidators;
+	type InitialEpochChunkSize = InitialEpochChunkSize;
+	type InitialEpochHistoryLength = InitialEpochHistoryLength;
+	type InitialBondsResetChunkSize = InitialBondsResetChunkSize;
+	type InitialBondsResetRetention = InitialBondsResetRetention;
+	type InitialInactivityHorizon = InitialInactivityHorizon;
+	type InitialDeregistrationBatchSize = InitialDeregistrationBatchSize;
 	type InitialMinAllowedWeights = InitialMinAllowedWeights;
 	type InitialBondsMovingAverage = InitialBondsMovingAverage;
 	type InitialMaxAllowedMaxMinRatio = InitialMaxAllowedMaxMinRatio;
@@ -335,8 +401,9 @@ impl pallet_subtensor::Config for Runtime {
 	type InitialBlocksPerStep = InitialBlocksPerStep;
 	type InitialIssuance = InitialIssuance;
 	type InitialDifficulty = InitialDifficulty;
-	type MinimumDifficulty = MinimumDifficulty;
-	type MaximumDifficulty = MaximumDifficulty;
+	type InitialMinimumDifficulty = InitialMinimumDifficulty;
+	type InitialMaximumDifficulty = InitialMaximumDifficulty;
+	type InitialBlockEmission = InitialBlockEmission;
 	type InitialActivityCutoff = InitialActivityCutoff;
 	type InitialAdjustmentInterval = InitialAdjustmentInterval;
 	type InitialMaxRegistrationsPerBlock = InitialMaxRegistrationsPerBlock;
@@ -361,6 +428,9 @@ construct_runtime!(
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		SubtensorModule: pallet_subtensor,
+		// Appended after SubtensorModule so that the indices of the existing pallets are unchanged.
+		Council: pallet_collective::<Instance1>,
+		TechnicalCommittee: pallet_collective::<Instance2>,
 	}
 );
 
@@ -539,6 +609,39 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_subtensor_runtime_api::SubtensorApi<Block, AccountId> for Runtime {
+		fn get_epoch_history() -> Vec<pallet_subtensor::EpochRecord> {
+			SubtensorModule::get_epoch_history()
+		}
+		fn get_epoch_history_for_uid( uid: u32 ) -> Vec<pallet_subtensor::NeuronEpochRecord> {
+			SubtensorModule::get_epoch_history_for_uid( uid )
+		}
+		fn get_vtrust() -> Vec<u64> {
+			SubtensorModule::get_vtrust()
+		}
+		fn get_hyperparameters() -> pallet_subtensor::SubnetHyperparams {
+			SubtensorModule::get_hyperparameters()
+		}
+		fn get_scheduled_hyperparameter_changes() -> Vec<pallet_subtensor::ScheduledHyperparameterChange> {
+			SubtensorModule::get_scheduled_hyperparameter_changes()
+		}
+		fn get_hyperparameter_ranges() -> Vec<(pallet_subtensor::Hyperparameter, pallet_subtensor::HyperparameterRange)> {
+			SubtensorModule::get_hyperparameter_ranges()
+		}
+		fn get_neurons_by_coldkey( coldkey: AccountId ) -> Vec<pallet_subtensor::NeuronMetadata<AccountId>> {
+			SubtensorModule::get_neurons_by_coldkey( &coldkey )
+		}
+		fn get_total_stake_for_coldkey( coldkey: AccountId ) -> u64 {
+			SubtensorModule::get_total_stake_for_coldkey( &coldkey )
+		}
+		fn get_neurons() -> Vec<pallet_subtensor::NeuronInfo<AccountId>> {
+			SubtensorModule::get_neurons_info()
+		}
+		fn get_neuron( uid: u32 ) -> Option<pallet_subtensor::NeuronInfo<AccountId>> {
+			SubtensorModule::get_neuron_info( uid )
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (